@@ -1,10 +1,13 @@
 use std::{
-    ffi::{c_char, CString},
+    ffi::{c_char, c_void, CString},
     path::PathBuf,
     ptr,
     sync::Arc,
 };
 
+use futures_util::{stream, StreamExt, TryStreamExt};
+use tokio::{sync::mpsc, task::JoinHandle};
+
 #[cfg(feature = "blob-azure")]
 use crate::blob_store::AzureBlob;
 #[cfg(feature = "blob-local")]
@@ -14,7 +17,7 @@ use crate::blob_store::GCS;
 #[cfg(feature = "blob-s3")]
 use crate::blob_store::S3;
 use crate::{
-    blob_store::{BlobPut, BlobStore},
+    blob_store::{sync, BlobPut, BlobStore, BlobStream, ListOptions, SyncOptions, SyncReport},
     ffi::{
         error::{map_anyhow, run_ffi, FfiError, IgStatus},
         runtime::IgRuntimeHandle,
@@ -31,6 +34,61 @@ pub struct IgBlobStoreHandle {
     pub(crate) store: Arc<dyn BlobStore + Send + Sync>,
 }
 
+/// Number of chunks buffered between `ig_blob_upload_write` and the store.
+const UPLOAD_CHANNEL_CAPACITY: usize = 4;
+
+/// Opaque handle to a chunked blob upload started with `ig_blob_store_put_stream_begin`.
+///
+/// Chunks are sent as `Some`, and `None` marks the end of the upload. A channel closed
+/// without it fails the upload, so a dropped handle never stores a truncated blob.
+pub struct IgBlobUploadHandle {
+    sender: Option<mpsc::Sender<Option<Vec<u8>>>>,
+    task: Option<JoinHandle<anyhow::Result<String>>>,
+}
+
+impl IgBlobUploadHandle {
+    /// Ends the chunk stream and waits for the store to finish the upload.
+    fn finish(&mut self, runtime: &IgRuntimeHandle) -> Result<String, FfiError> {
+        if let Some(sender) = self.sender.take() {
+            // fails if the upload has already failed, the task reports why
+            let _ = runtime.block_on(sender.send(None));
+        }
+        let task = self.task.take().ok_or_else(|| {
+            FfiError::new(
+                IgStatus::InvalidInput,
+                "upload has already finished".to_owned(),
+            )
+        })?;
+
+        let cid = runtime.block_on(task).map_err(|e| {
+            FfiError::new(IgStatus::RuntimeError, format!("upload task failed: {e}"))
+        })?;
+        map_anyhow(cid)
+    }
+}
+
+impl Drop for IgBlobUploadHandle {
+    fn drop(&mut self) {
+        // an unfinished upload must not be stored with the chunks written so far, abort it
+        // before closing the channel
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        drop(self.sender.take());
+    }
+}
+
+/// Opaque handle to a chunked blob download opened with `ig_blob_store_get_stream_open`.
+pub struct IgBlobDownloadHandle {
+    chunks: BlobStream,
+}
+
+/// Called with the JSON sync report after every batch of `ig_blob_store_sync`.
+///
+/// The report string is only valid for the duration of the call.
+pub type IgBlobSyncProgressCallback =
+    Option<extern "C" fn(report_json: *const c_char, user_data: *mut c_void)>;
+
 #[repr(C)]
 pub struct IgBlobPutRequest {
     pub blob_ptr: *const u8,
@@ -239,6 +297,177 @@ pub extern "C" fn ig_blob_store_put(
     })
 }
 
+#[no_mangle]
+pub extern "C" fn ig_blob_store_put_stream_begin(
+    runtime: *const IgRuntimeHandle,
+    store: *const IgBlobStoreHandle,
+    multicodec_code: u64,
+    expected_cid_or_null: *const c_char,
+    out_upload: *mut *mut IgBlobUploadHandle,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let store = as_ref(store, "store")?.store.clone();
+        let expected_cid = optional_cstr_to_string(expected_cid_or_null)?;
+
+        let (sender, receiver) = mpsc::channel(UPLOAD_CHANNEL_CAPACITY);
+        let chunks = stream::unfold(Some(receiver), |receiver| async move {
+            let mut receiver = receiver?;
+            match receiver.recv().await {
+                Some(Some(chunk)) => Some((Ok(chunk), Some(receiver))),
+                Some(None) => None,
+                None => Some((Err(anyhow::anyhow!("upload was cancelled")), None)),
+            }
+        })
+        .boxed();
+        let task = runtime.runtime.spawn(async move {
+            store
+                .put_stream(chunks, multicodec_code, expected_cid.as_deref())
+                .await
+        });
+
+        let upload = IgBlobUploadHandle {
+            sender: Some(sender),
+            task: Some(task),
+        };
+        write_out_ptr(out_upload, upload, "out_upload")
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ig_blob_upload_write(
+    runtime: *const IgRuntimeHandle,
+    upload: *mut IgBlobUploadHandle,
+    chunk_ptr: *const u8,
+    chunk_len: usize,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let upload = as_mut(upload, "upload")?;
+        let chunk = bytes_from_raw(chunk_ptr, chunk_len, "chunk_ptr")?;
+
+        let sender = upload.sender.as_ref().ok_or_else(|| {
+            FfiError::new(
+                IgStatus::InvalidInput,
+                "upload has already finished".to_owned(),
+            )
+        })?;
+
+        // the store stops reading chunks once the upload fails, report why
+        if runtime.block_on(sender.send(Some(chunk))).is_err() {
+            upload.finish(runtime)?;
+            return Err(FfiError::new(
+                IgStatus::RuntimeError,
+                "upload ended before all chunks were written".to_owned(),
+            ));
+        }
+
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ig_blob_upload_finish(
+    runtime: *const IgRuntimeHandle,
+    upload: *mut IgBlobUploadHandle,
+    out_cid: *mut *mut c_char,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let upload = as_mut(upload, "upload")?;
+
+        let cid = upload.finish(runtime)?;
+        write_c_string(out_cid, cid, "out_cid")
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ig_blob_upload_free(upload: *mut IgBlobUploadHandle) {
+    if upload.is_null() {
+        return;
+    }
+
+    unsafe {
+        drop(Box::from_raw(upload));
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn ig_blob_store_get_stream_open(
+    runtime: *const IgRuntimeHandle,
+    store: *const IgBlobStoreHandle,
+    cid: *const c_char,
+    out_download: *mut *mut IgBlobDownloadHandle,
+    out_found: *mut bool,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let store = as_ref(store, "store")?;
+        let cid = cstr_to_string(cid, "cid")?;
+
+        let chunks = map_anyhow(runtime.block_on(store.store.get_stream(&cid)))?;
+        match chunks {
+            Some(chunks) => {
+                write_out_ptr(
+                    out_download,
+                    IgBlobDownloadHandle { chunks },
+                    "out_download",
+                )?;
+                write_bool(out_found, true, "out_found")
+            }
+            None => {
+                let out_download = as_mut(out_download, "out_download")?;
+                *out_download = ptr::null_mut();
+                write_bool(out_found, false, "out_found")
+            }
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ig_blob_download_read(
+    runtime: *const IgRuntimeHandle,
+    download: *mut IgBlobDownloadHandle,
+    out_chunk: *mut IgBytes,
+    out_done: *mut bool,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let download = as_mut(download, "download")?;
+
+        let out_chunk = as_mut(out_chunk, "out_chunk")?;
+        *out_chunk = IgBytes {
+            ptr: ptr::null_mut(),
+            len: 0,
+        };
+
+        let chunk = map_anyhow(runtime.block_on(download.chunks.try_next()))?;
+        match chunk {
+            Some(chunk) => {
+                write_ig_bytes(out_chunk, chunk, "out_chunk")?;
+                write_bool(out_done, false, "out_done")
+            }
+            None => write_bool(out_done, true, "out_done"),
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ig_blob_download_free(download: *mut IgBlobDownloadHandle) {
+    if download.is_null() {
+        return;
+    }
+
+    unsafe {
+        drop(Box::from_raw(download));
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn ig_blob_store_exists_many(
     runtime: *const IgRuntimeHandle,
@@ -337,6 +566,71 @@ pub extern "C" fn ig_blob_store_put_many(
     })
 }
 
+#[no_mangle]
+pub extern "C" fn ig_blob_store_sync(
+    runtime: *const IgRuntimeHandle,
+    src: *const IgBlobStoreHandle,
+    dst: *const IgBlobStoreHandle,
+    start_after_or_null: *const c_char,
+    concurrency_limit: usize,
+    on_progress: IgBlobSyncProgressCallback,
+    user_data: *mut c_void,
+    out_report_json: *mut *mut c_char,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let src = as_ref(src, "src")?;
+        let dst = as_ref(dst, "dst")?;
+        let start_after = optional_cstr_to_string(start_after_or_null)?;
+
+        let options = SyncOptions {
+            list: ListOptions {
+                start_after,
+                ..Default::default()
+            },
+            concurrency_limit: (concurrency_limit > 0).then_some(concurrency_limit),
+            batch_size: None,
+        };
+
+        let report = map_anyhow(runtime.block_on(sync(
+            src.store.as_ref(),
+            dst.store.as_ref(),
+            &options,
+            |report| {
+                let Some(on_progress) = on_progress else {
+                    return;
+                };
+                if let Ok(report_json) = CString::new(sync_report_json(report)) {
+                    on_progress(report_json.as_ptr(), user_data);
+                }
+            },
+        )))?;
+
+        write_c_string(
+            out_report_json,
+            sync_report_json(&report),
+            "out_report_json",
+        )
+    })
+}
+
+fn sync_report_json(report: &SyncReport) -> String {
+    serde_json::json!({
+        "listed": report.listed,
+        "skipped": report.skipped,
+        "copied": report.copied,
+        "bytes_copied": report.bytes_copied,
+        "failed": report
+            .failed
+            .iter()
+            .map(|failure| serde_json::json!({ "cid": failure.cid, "error": failure.error }))
+            .collect::<Vec<_>>(),
+        "resume_after": report.resume_after,
+    })
+    .to_string()
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn ig_blob_store_exists_results_free(
     results: *mut IgBlobExistsResult,
@@ -1,12 +1,12 @@
 use std::{ffi::c_char, str::FromStr, sync::Arc};
 
 use crate::{
-    dsse::{self, PayloadType},
+    dsse::{self, Envelope, PayloadType, ThresholdPolicy, VerifyOptions},
     ffi::{
         error::{map_anyhow, run_ffi, FfiError, IgStatus},
         runtime::IgRuntimeHandle,
         signer::IgSignerHandle,
-        util::{as_ref, bytes_from_raw, cstr_to_string, write_c_string},
+        util::{as_ref, bytes_from_raw, cstr_to_string, write_bool, write_c_string},
     },
     signer::Signer,
 };
@@ -61,17 +61,91 @@ pub extern "C" fn ig_dsse_sign_integrity_statement(
     })
 }
 
+#[no_mangle]
+pub extern "C" fn ig_dsse_cosign(
+    runtime: *const IgRuntimeHandle,
+    signer: *const IgSignerHandle,
+    envelope_json: *const c_char,
+    out_envelope_json: *mut *mut c_char,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let signer = as_ref(signer, "signer")?;
+        let envelope_json = cstr_to_string(envelope_json, "envelope_json")?;
+        let envelope = map_anyhow(Envelope::try_from_json_string(&envelope_json))?;
+
+        let signer_arc: Arc<dyn Signer> = Arc::new(signer.signer.clone());
+        let envelope = map_anyhow(runtime.block_on(dsse::cosign_dsse(envelope, signer_arc)))?;
+
+        let envelope_json = map_anyhow(envelope.into_json_string())?;
+        write_c_string(out_envelope_json, envelope_json, "out_envelope_json")
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn ig_dsse_verify(
-    _runtime: *const IgRuntimeHandle,
-    _envelope_json: *const c_char,
-    _out_valid: *mut bool,
+    runtime: *const IgRuntimeHandle,
+    envelope_json: *const c_char,
+    out_valid: *mut bool,
     err_out: *mut *mut c_char,
 ) -> IgStatus {
     run_ffi(err_out, || {
-        Err(FfiError::new(
-            IgStatus::NotSupported,
-            "DSSE verify is not implemented in the Rust core yet",
-        ))
+        let runtime = as_ref(runtime, "runtime")?;
+        let envelope_json = cstr_to_string(envelope_json, "envelope_json")?;
+
+        let verification = map_anyhow(runtime.block_on(dsse::verify_dsse(&envelope_json)))?;
+        write_bool(out_valid, verification.is_valid(), "out_valid")
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ig_dsse_verify_with_options(
+    runtime: *const IgRuntimeHandle,
+    envelope_json: *const c_char,
+    allow_legacy: bool,
+    out_valid: *mut bool,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let envelope_json = cstr_to_string(envelope_json, "envelope_json")?;
+        let options = VerifyOptions { allow_legacy };
+
+        let verification =
+            map_anyhow(runtime.block_on(dsse::verify_dsse_with_options(&envelope_json, options)))?;
+        write_bool(out_valid, verification.is_valid(), "out_valid")
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ig_dsse_verify_threshold(
+    runtime: *const IgRuntimeHandle,
+    envelope_json: *const c_char,
+    trusted_dids_json: *const c_char,
+    threshold: usize,
+    out_valid: *mut bool,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let envelope_json = cstr_to_string(envelope_json, "envelope_json")?;
+        let trusted_dids_json = cstr_to_string(trusted_dids_json, "trusted_dids_json")?;
+        let trusted_dids =
+            serde_json::from_str::<Vec<String>>(&trusted_dids_json).map_err(|e| {
+                FfiError::new(
+                    IgStatus::JsonError,
+                    format!("failed to parse trusted dids json: {e}"),
+                )
+            })?;
+        let policy = ThresholdPolicy::new(threshold, trusted_dids)
+            .map_err(|e| FfiError::new(IgStatus::InvalidInput, e.to_string()))?;
+
+        let verification = map_anyhow(runtime.block_on(dsse::verify_dsse_threshold(
+            &envelope_json,
+            &policy,
+            VerifyOptions::default(),
+        )))?;
+        write_bool(out_valid, verification.satisfied, "out_valid")
     })
 }
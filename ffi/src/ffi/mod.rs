@@ -13,7 +13,7 @@ mod util;
 mod vc;
 mod version;
 
-pub use blob_store::IgBlobStoreHandle;
+pub use blob_store::{IgBlobDownloadHandle, IgBlobStoreHandle, IgBlobUploadHandle};
 pub use error::IgStatus;
 pub use runtime::IgRuntimeHandle;
 pub use signer::IgSignerHandle;
@@ -1,16 +1,58 @@
 use std::{collections::HashMap, ffi::c_char};
 
-use serde_json::Value;
+use serde_json::{json, Value};
 
 use crate::{
     ffi::{
-        error::{map_anyhow, run_ffi, FfiError, IgStatus},
+        error::{map_anyhow, run_ffi, FfiError, FfiResult, IgStatus},
         runtime::IgRuntimeHandle,
         util::{as_ref, cstr_to_string, optional_cstr_to_string, write_c_string},
     },
     model_signing::{self, DirectoryInfo},
+    sigstore_bundle::{PublicKey, SigstoreBundle},
 };
 
+fn parse_path_hashes(path_hashes_json: &str) -> FfiResult<HashMap<String, [u8; 32]>> {
+    let path_hashes_hex = serde_json::from_str::<HashMap<String, String>>(path_hashes_json)
+        .map_err(|e| {
+            FfiError::new(
+                IgStatus::JsonError,
+                format!("failed to parse path_hashes_json: {e}"),
+            )
+        })?;
+
+    let mut path_hashes = HashMap::with_capacity(path_hashes_hex.len());
+    for (path, digest_hex) in path_hashes_hex {
+        let digest_bytes = hex::decode(digest_hex).map_err(|e| {
+            FfiError::new(
+                IgStatus::InvalidInput,
+                format!("failed to decode hex digest for '{path}': {e}"),
+            )
+        })?;
+
+        if digest_bytes.len() != 32 {
+            return Err(FfiError::new(
+                IgStatus::InvalidInput,
+                format!(
+                    "digest for '{path}' must be 32 bytes, got {}",
+                    digest_bytes.len()
+                ),
+            ));
+        }
+
+        let digest: [u8; 32] = digest_bytes.try_into().map_err(|_| {
+            FfiError::new(
+                IgStatus::InvalidInput,
+                format!("invalid digest length for '{path}'"),
+            )
+        })?;
+
+        path_hashes.insert(path, digest);
+    }
+
+    Ok(path_hashes)
+}
+
 #[no_mangle]
 pub extern "C" fn ig_model_signing_create_intoto_statement_from_hashes(
     runtime: *const IgRuntimeHandle,
@@ -26,42 +68,7 @@ pub extern "C" fn ig_model_signing_create_intoto_statement_from_hashes(
         let model_name = cstr_to_string(model_name, "model_name")?;
         let path_hashes_json = cstr_to_string(path_hashes_json, "path_hashes_json")?;
 
-        let path_hashes_hex = serde_json::from_str::<HashMap<String, String>>(&path_hashes_json)
-            .map_err(|e| {
-                FfiError::new(
-                    IgStatus::JsonError,
-                    format!("failed to parse path_hashes_json: {e}"),
-                )
-            })?;
-
-        let mut path_hashes = HashMap::with_capacity(path_hashes_hex.len());
-        for (path, digest_hex) in path_hashes_hex {
-            let digest_bytes = hex::decode(digest_hex).map_err(|e| {
-                FfiError::new(
-                    IgStatus::InvalidInput,
-                    format!("failed to decode hex digest for '{path}': {e}"),
-                )
-            })?;
-
-            if digest_bytes.len() != 32 {
-                return Err(FfiError::new(
-                    IgStatus::InvalidInput,
-                    format!(
-                        "digest for '{path}' must be 32 bytes, got {}",
-                        digest_bytes.len()
-                    ),
-                ));
-            }
-
-            let digest: [u8; 32] = digest_bytes.try_into().map_err(|_| {
-                FfiError::new(
-                    IgStatus::InvalidInput,
-                    format!("invalid digest length for '{path}'"),
-                )
-            })?;
-
-            path_hashes.insert(path, digest);
-        }
+        let path_hashes = parse_path_hashes(&path_hashes_json)?;
 
         let ignore_paths = match optional_cstr_to_string(ignore_paths_json_or_null)? {
             Some(json) => serde_json::from_str::<Vec<String>>(&json).map_err(|e| {
@@ -118,3 +125,59 @@ pub extern "C" fn ig_model_signing_create_sigstore_bundle(
         )
     })
 }
+
+#[no_mangle]
+pub extern "C" fn ig_model_signing_verify_sigstore_bundle_from_hashes(
+    runtime: *const IgRuntimeHandle,
+    sigstore_bundle_json: *const c_char,
+    path_hashes_json: *const c_char,
+    trusted_did_keys_json: *const c_char,
+    out_verification_json: *mut *mut c_char,
+    err_out: *mut *mut c_char,
+) -> IgStatus {
+    run_ffi(err_out, || {
+        let runtime = as_ref(runtime, "runtime")?;
+        let bundle_json = cstr_to_string(sigstore_bundle_json, "sigstore_bundle_json")?;
+        let path_hashes_json = cstr_to_string(path_hashes_json, "path_hashes_json")?;
+        let trusted_did_keys_json = cstr_to_string(trusted_did_keys_json, "trusted_did_keys_json")?;
+
+        let bundle = map_anyhow(SigstoreBundle::from_json(&bundle_json))?;
+        let path_hashes = parse_path_hashes(&path_hashes_json)?;
+
+        let trusted_did_keys = serde_json::from_str::<Vec<String>>(&trusted_did_keys_json)
+            .map_err(|e| {
+                FfiError::new(
+                    IgStatus::JsonError,
+                    format!("failed to parse trusted_did_keys_json: {e}"),
+                )
+            })?;
+        let trusted_keys = trusted_did_keys
+            .iter()
+            .map(|did| PublicKey::from_did_key(did))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|e| FfiError::new(IgStatus::InvalidInput, e.to_string()))?;
+
+        let verification =
+            map_anyhow(runtime.block_on(model_signing::verify_model_signing_bundle(
+                &bundle,
+                DirectoryInfo::PathHashMap(path_hashes),
+                &trusted_keys,
+            )))?;
+
+        let signing_key_id = map_anyhow(verification.signing_key.key_id())?;
+        let verification_json = json!({
+            "valid": verification.is_valid(),
+            "signingKeyId": hex::encode(signing_key_id),
+            "missing": verification.missing,
+            "extra": verification.extra,
+            "modified": verification.modified,
+        })
+        .to_string();
+
+        write_c_string(
+            out_verification_json,
+            verification_json,
+            "out_verification_json",
+        )
+    })
+}
@@ -91,6 +91,72 @@ fn ffi_runtime_signer_dsse_smoke() {
             == 1
     );
 
+    let envelope_json_c = cstring(&envelope_json);
+    let mut is_valid = false;
+    let status = dsse::ig_dsse_verify(
+        runtime_handle,
+        envelope_json_c.as_ptr(),
+        &mut is_valid,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    assert!(is_valid);
+
+    let mut tampered = envelope;
+    tampered["payload"] = Value::String(String::from("dGFtcGVyZWQ="));
+    let tampered_c = cstring(&tampered.to_string());
+    let status = dsse::ig_dsse_verify_with_options(
+        runtime_handle,
+        tampered_c.as_ptr(),
+        true,
+        &mut is_valid,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    assert!(!is_valid);
+
+    let mut cosigner_handle = ptr::null_mut();
+    let mut cosigner_did = ptr::null_mut();
+    let status =
+        signer::ig_signer_p256_create(&mut cosigner_handle, &mut cosigner_did, &mut err_out);
+    assert_ok(status, err_out);
+    let cosigner_did = take_owned_c_string(cosigner_did);
+
+    let mut cosigned_json_ptr = ptr::null_mut();
+    let status = dsse::ig_dsse_cosign(
+        runtime_handle,
+        cosigner_handle,
+        envelope_json_c.as_ptr(),
+        &mut cosigned_json_ptr,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    let cosigned_json = cstring(&take_owned_c_string(cosigned_json_ptr));
+
+    let trusted_dids = cstring(&serde_json::json!([signer_did, cosigner_did]).to_string());
+    let status = dsse::ig_dsse_verify_threshold(
+        runtime_handle,
+        cosigned_json.as_ptr(),
+        trusted_dids.as_ptr(),
+        2,
+        &mut is_valid,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    assert!(is_valid);
+
+    let status = dsse::ig_dsse_verify_threshold(
+        runtime_handle,
+        envelope_json_c.as_ptr(),
+        trusted_dids.as_ptr(),
+        2,
+        &mut is_valid,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    assert!(!is_valid);
+
+    signer::ig_signer_free(cosigner_handle);
     signer::ig_signer_free(signer_handle);
     runtime::ig_runtime_free(runtime_handle);
 }
@@ -316,6 +382,242 @@ fn ffi_blob_store_local_fs_roundtrip() {
     let _ = std::fs::remove_dir_all(tmp_dir);
 }
 
+#[test]
+fn ffi_blob_store_chunked_upload_and_download() {
+    let mut runtime_handle = ptr::null_mut();
+    let mut err_out = ptr::null_mut();
+    let status = runtime::ig_runtime_new(&mut runtime_handle, &mut err_out);
+    assert_ok(status, err_out);
+
+    let tmp_dir = std::env::temp_dir().join(format!("integrity-ffi-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&tmp_dir).expect("create temp dir");
+    let path = cstring(tmp_dir.to_string_lossy().as_ref());
+
+    let mut store_handle = ptr::null_mut();
+    let status = blob_store::ig_blob_store_local_fs_new(
+        runtime_handle,
+        path.as_ptr(),
+        &mut store_handle,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+
+    let chunks: [&[u8]; 3] = [b"ffi ", b"chunked ", b"blob"];
+    let mut upload_handle = ptr::null_mut();
+    let status = blob_store::ig_blob_store_put_stream_begin(
+        runtime_handle,
+        store_handle,
+        0x55,
+        ptr::null(),
+        &mut upload_handle,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    for chunk in chunks {
+        let status = blob_store::ig_blob_upload_write(
+            runtime_handle,
+            upload_handle,
+            chunk.as_ptr(),
+            chunk.len(),
+            &mut err_out,
+        );
+        assert_ok(status, err_out);
+    }
+    let mut cid_ptr = ptr::null_mut();
+    let status = blob_store::ig_blob_upload_finish(
+        runtime_handle,
+        upload_handle,
+        &mut cid_ptr,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    blob_store::ig_blob_upload_free(upload_handle);
+    let cid = take_owned_c_string(cid_ptr);
+    let cid_c = cstring(&cid);
+
+    let mut download_handle = ptr::null_mut();
+    let mut found = false;
+    let status = blob_store::ig_blob_store_get_stream_open(
+        runtime_handle,
+        store_handle,
+        cid_c.as_ptr(),
+        &mut download_handle,
+        &mut found,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    assert!(found);
+
+    let mut downloaded = vec![];
+    loop {
+        let mut chunk = IgBytes::default();
+        let mut done = false;
+        let status = blob_store::ig_blob_download_read(
+            runtime_handle,
+            download_handle,
+            &mut chunk,
+            &mut done,
+            &mut err_out,
+        );
+        assert_ok(status, err_out);
+        if done {
+            break;
+        }
+        downloaded.extend_from_slice(unsafe { std::slice::from_raw_parts(chunk.ptr, chunk.len) });
+        unsafe {
+            super::ig_bytes_free(chunk);
+        }
+    }
+    blob_store::ig_blob_download_free(download_handle);
+    assert_eq!(downloaded, b"ffi chunked blob");
+
+    // a mismatched CID fails on finish and stores nothing
+    let wrong_cid = cstring("bafkr4ibogus");
+    let mut upload_handle = ptr::null_mut();
+    let status = blob_store::ig_blob_store_put_stream_begin(
+        runtime_handle,
+        store_handle,
+        0x55,
+        wrong_cid.as_ptr(),
+        &mut upload_handle,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    let status = blob_store::ig_blob_upload_write(
+        runtime_handle,
+        upload_handle,
+        b"other".as_ptr(),
+        5,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    let mut cid_ptr = ptr::null_mut();
+    let status = blob_store::ig_blob_upload_finish(
+        runtime_handle,
+        upload_handle,
+        &mut cid_ptr,
+        &mut err_out,
+    );
+    assert_ne!(status, IgStatus::Ok);
+    assert!(cid_ptr.is_null());
+    assert!(take_owned_c_string(err_out).contains("doesn't match"));
+    blob_store::ig_blob_upload_free(upload_handle);
+    assert_eq!(std::fs::read_dir(&tmp_dir).unwrap().count(), 1);
+
+    // freeing an unfinished upload, even with a full channel, stores nothing
+    let mut upload_handle = ptr::null_mut();
+    let status = blob_store::ig_blob_store_put_stream_begin(
+        runtime_handle,
+        store_handle,
+        0x55,
+        ptr::null(),
+        &mut upload_handle,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    for _ in 0..8 {
+        let status = blob_store::ig_blob_upload_write(
+            runtime_handle,
+            upload_handle,
+            b"partial".as_ptr(),
+            7,
+            &mut err_out,
+        );
+        assert_ok(status, err_out);
+    }
+    blob_store::ig_blob_upload_free(upload_handle);
+    // the aborted task removes its temporary file once it's dropped
+    for _ in 0..100 {
+        if std::fs::read_dir(&tmp_dir).unwrap().count() == 1 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(std::fs::read_dir(&tmp_dir).unwrap().count(), 1);
+
+    blob_store::ig_blob_store_free(store_handle);
+    runtime::ig_runtime_free(runtime_handle);
+    let _ = std::fs::remove_dir_all(tmp_dir);
+}
+
+extern "C" fn count_sync_progress(report_json: *const c_char, user_data: *mut std::ffi::c_void) {
+    let report_json = unsafe { CStr::from_ptr(report_json) }.to_str().unwrap();
+    assert!(serde_json::from_str::<Value>(report_json).is_ok());
+    unsafe {
+        *(user_data as *mut usize) += 1;
+    }
+}
+
+#[test]
+fn ffi_blob_store_sync_copies_missing_blobs() {
+    let mut runtime_handle = ptr::null_mut();
+    let mut err_out = ptr::null_mut();
+    let status = runtime::ig_runtime_new(&mut runtime_handle, &mut err_out);
+    assert_ok(status, err_out);
+
+    let mut stores = vec![];
+    let mut dirs = vec![];
+    for _ in 0..2 {
+        let dir = std::env::temp_dir().join(format!("integrity-ffi-test-{}", uuid::Uuid::new_v4()));
+        let path = cstring(dir.to_string_lossy().as_ref());
+        let mut store_handle = ptr::null_mut();
+        let status = blob_store::ig_blob_store_local_fs_new(
+            runtime_handle,
+            path.as_ptr(),
+            &mut store_handle,
+            &mut err_out,
+        );
+        assert_ok(status, err_out);
+        stores.push(store_handle);
+        dirs.push(dir);
+    }
+
+    let mut cid_ptr = ptr::null_mut();
+    let blob = b"ffi synced blob";
+    let status = blob_store::ig_blob_store_put(
+        runtime_handle,
+        stores[0],
+        blob.as_ptr(),
+        blob.len(),
+        0x55,
+        ptr::null(),
+        &mut cid_ptr,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    let cid = take_owned_c_string(cid_ptr);
+
+    let mut progress_calls = 0_usize;
+    let mut report_ptr = ptr::null_mut();
+    let status = blob_store::ig_blob_store_sync(
+        runtime_handle,
+        stores[0],
+        stores[1],
+        ptr::null(),
+        0,
+        Some(count_sync_progress),
+        &mut progress_calls as *mut usize as *mut std::ffi::c_void,
+        &mut report_ptr,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    assert_eq!(progress_calls, 1);
+
+    let report: Value = serde_json::from_str(&take_owned_c_string(report_ptr)).unwrap();
+    assert_eq!(report["copied"], 1);
+    assert_eq!(report["bytes_copied"], blob.len());
+    assert_eq!(report["resume_after"], cid);
+    assert!(dirs[1].join(&cid).exists());
+
+    for store_handle in stores {
+        blob_store::ig_blob_store_free(store_handle);
+    }
+    runtime::ig_runtime_free(runtime_handle);
+    for dir in dirs {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
+
 #[test]
 fn ffi_model_signing_and_intoto_digest_smoke() {
     let mut runtime_handle = ptr::null_mut();
@@ -361,6 +663,83 @@ fn ffi_model_signing_and_intoto_digest_smoke() {
     runtime::ig_runtime_free(runtime_handle);
 }
 
+#[test]
+fn ffi_model_signing_verify_sigstore_bundle_smoke() {
+    let mut runtime_handle = ptr::null_mut();
+    let mut err_out = ptr::null_mut();
+    let status = runtime::ig_runtime_new(&mut runtime_handle, &mut err_out);
+    assert_ok(status, err_out);
+
+    let mut signer_handle = ptr::null_mut();
+    let mut signer_did = ptr::null_mut();
+    let status = signer::ig_signer_p256_create(&mut signer_handle, &mut signer_did, &mut err_out);
+    assert_ok(status, err_out);
+    let signer_did = take_owned_c_string(signer_did);
+
+    let model_name = cstring("demo-model");
+    let mut hashes = std::collections::HashMap::new();
+    hashes.insert(String::from("weights.bin"), hex::encode([7_u8; 32]));
+    let hashes_json = cstring(&serde_json::to_string(&hashes).unwrap());
+
+    let mut statement_json_ptr = ptr::null_mut();
+    let status = model_signing::ig_model_signing_create_intoto_statement_from_hashes(
+        runtime_handle,
+        model_name.as_ptr(),
+        hashes_json.as_ptr(),
+        false,
+        ptr::null(),
+        &mut statement_json_ptr,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    let statement_json = cstring(&take_owned_c_string(statement_json_ptr));
+
+    let mut envelope_json_ptr = ptr::null_mut();
+    let status = intoto::ig_intoto_sign_statement(
+        runtime_handle,
+        signer_handle,
+        statement_json.as_ptr(),
+        &mut envelope_json_ptr,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    let envelope_json = cstring(&take_owned_c_string(envelope_json_ptr));
+
+    let signer_did_c = cstring(&signer_did);
+    let mut bundle_json_ptr = ptr::null_mut();
+    let status = model_signing::ig_model_signing_create_sigstore_bundle(
+        envelope_json.as_ptr(),
+        signer_did_c.as_ptr(),
+        &mut bundle_json_ptr,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+    let bundle_json = cstring(&take_owned_c_string(bundle_json_ptr));
+
+    let trusted_json = cstring(&serde_json::to_string(&[&signer_did]).unwrap());
+    hashes.insert(String::from("weights.bin"), hex::encode([8_u8; 32]));
+    let modified_json = cstring(&serde_json::to_string(&hashes).unwrap());
+
+    let mut verification_json_ptr = ptr::null_mut();
+    let status = model_signing::ig_model_signing_verify_sigstore_bundle_from_hashes(
+        runtime_handle,
+        bundle_json.as_ptr(),
+        modified_json.as_ptr(),
+        trusted_json.as_ptr(),
+        &mut verification_json_ptr,
+        &mut err_out,
+    );
+    assert_ok(status, err_out);
+
+    let verification: Value =
+        serde_json::from_str(&take_owned_c_string(verification_json_ptr)).unwrap();
+    assert_eq!(verification["valid"], Value::Bool(false));
+    assert_eq!(verification["modified"], serde_json::json!(["weights.bin"]));
+
+    signer::ig_signer_free(signer_handle);
+    runtime::ig_runtime_free(runtime_handle);
+}
+
 #[test]
 fn ffi_lineage_statement_create_and_utils_smoke() {
     let mut runtime_handle = ptr::null_mut();
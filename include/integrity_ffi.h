@@ -12,6 +12,8 @@ extern "C" {
 typedef struct IgRuntimeHandle IgRuntimeHandle;
 typedef struct IgSignerHandle IgSignerHandle;
 typedef struct IgBlobStoreHandle IgBlobStoreHandle;
+typedef struct IgBlobUploadHandle IgBlobUploadHandle;
+typedef struct IgBlobDownloadHandle IgBlobDownloadHandle;
 
 typedef enum IgStatus {
     IG_STATUS_OK = 0,
@@ -52,6 +54,8 @@ typedef struct IgBlobExistsResult {
     bool exists;
 } IgBlobExistsResult;
 
+typedef void (*IgBlobSyncProgressCallback)(const char *report_json, void *user_data);
+
 void ig_string_free(char *s);
 void ig_error_free(char *err);
 void ig_bytes_free(IgBytes bytes);
@@ -152,12 +156,34 @@ IgStatus ig_dsse_sign_integrity_statement(
     char **out_envelope_json,
     char **err_out
 );
+IgStatus ig_dsse_cosign(
+    const IgRuntimeHandle *runtime,
+    const IgSignerHandle *signer,
+    const char *envelope_json,
+    char **out_envelope_json,
+    char **err_out
+);
 IgStatus ig_dsse_verify(
     const IgRuntimeHandle *runtime,
     const char *envelope_json,
     bool *out_valid,
     char **err_out
 );
+IgStatus ig_dsse_verify_with_options(
+    const IgRuntimeHandle *runtime,
+    const char *envelope_json,
+    bool allow_legacy,
+    bool *out_valid,
+    char **err_out
+);
+IgStatus ig_dsse_verify_threshold(
+    const IgRuntimeHandle *runtime,
+    const char *envelope_json,
+    const char *trusted_dids_json,
+    size_t threshold,
+    bool *out_valid,
+    char **err_out
+);
 
 IgStatus ig_vc_issue(
     const IgRuntimeHandle *runtime,
@@ -211,6 +237,14 @@ IgStatus ig_model_signing_create_sigstore_bundle(
     char **out_sigstore_bundle_json,
     char **err_out
 );
+IgStatus ig_model_signing_verify_sigstore_bundle_from_hashes(
+    const IgRuntimeHandle *runtime,
+    const char *sigstore_bundle_json,
+    const char *path_hashes_json,
+    const char *trusted_did_keys_json,
+    char **out_verification_json,
+    char **err_out
+);
 
 IgStatus ig_lineage_statement_create_association(
     const IgRuntimeHandle *runtime,
@@ -371,6 +405,44 @@ IgStatus ig_blob_store_put(
     char **out_cid,
     char **err_out
 );
+IgStatus ig_blob_store_put_stream_begin(
+    const IgRuntimeHandle *runtime,
+    const IgBlobStoreHandle *store,
+    uint64_t multicodec_code,
+    const char *expected_cid_or_null,
+    IgBlobUploadHandle **out_upload,
+    char **err_out
+);
+IgStatus ig_blob_upload_write(
+    const IgRuntimeHandle *runtime,
+    IgBlobUploadHandle *upload,
+    const uint8_t *chunk_ptr,
+    size_t chunk_len,
+    char **err_out
+);
+IgStatus ig_blob_upload_finish(
+    const IgRuntimeHandle *runtime,
+    IgBlobUploadHandle *upload,
+    char **out_cid,
+    char **err_out
+);
+void ig_blob_upload_free(IgBlobUploadHandle *upload);
+IgStatus ig_blob_store_get_stream_open(
+    const IgRuntimeHandle *runtime,
+    const IgBlobStoreHandle *store,
+    const char *cid,
+    IgBlobDownloadHandle **out_download,
+    bool *out_found,
+    char **err_out
+);
+IgStatus ig_blob_download_read(
+    const IgRuntimeHandle *runtime,
+    IgBlobDownloadHandle *download,
+    IgBytes *out_chunk,
+    bool *out_done,
+    char **err_out
+);
+void ig_blob_download_free(IgBlobDownloadHandle *download);
 IgStatus ig_blob_store_exists_many(
     const IgRuntimeHandle *runtime,
     const IgBlobStoreHandle *store,
@@ -398,6 +470,17 @@ IgStatus ig_blob_store_put_many(
     size_t *out_results_len,
     char **err_out
 );
+IgStatus ig_blob_store_sync(
+    const IgRuntimeHandle *runtime,
+    const IgBlobStoreHandle *src,
+    const IgBlobStoreHandle *dst,
+    const char *start_after_or_null,
+    size_t concurrency_limit,
+    IgBlobSyncProgressCallback on_progress,
+    void *user_data,
+    char **out_report_json,
+    char **err_out
+);
 void ig_blob_store_exists_results_free(IgBlobExistsResult *results, size_t results_len);
 void ig_blob_store_get_results_free(IgBlobGetResult *results, size_t results_len);
 void ig_blob_store_put_results_free(IgBlobPutResult *results, size_t results_len);
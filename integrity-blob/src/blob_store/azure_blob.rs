@@ -2,13 +2,24 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use azure_storage::{prelude::*, ErrorKind};
 use azure_storage_blobs::prelude::*;
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
 use log::{debug, trace, warn};
 
-use crate::blob_store::{calc_and_validate_cid, BlobStore};
+use crate::blob_store::{
+    calc_and_validate_cid,
+    chunks::{blob_stream_from_file, rechunk, BlobStream, CidHasher},
+    filter_listing,
+    temp_file::spool,
+    BlobStore, ListOptions,
+};
+
+/// Size of the blocks staged for streamed uploads.
+const BLOCK_SIZE: usize = 8 * 1024 * 1024;
 
 /// Azure Blob Storage implementation of BlobStore
 ///
-/// Stores blobs in Azure Blob Storage containers, indexed by CID.
+/// Stores blobs in Azure Blob Storage containers, indexed by CID. Streamed blobs are
+/// staged as blocks and committed once their CID is verified.
 pub struct AzureBlob {
     account: String,
     key: String,
@@ -113,4 +124,134 @@ impl BlobStore for AzureBlob {
 
         Ok(cid)
     }
+
+    /// Stream a blob from the store, one ranged download at a time
+    async fn get_stream(&self, cid: &str) -> Result<Option<BlobStream>> {
+        let client = self
+            .client
+            .clone()
+            .ok_or(anyhow!("client not initialized"))?;
+
+        let mut pages = client
+            .blob_client(self.container.as_str(), cid)
+            .get()
+            .into_stream();
+
+        let first = match pages.next().await {
+            Some(Ok(first)) => first.data.collect().await?.to_vec(),
+            Some(Err(e)) => match e.kind() {
+                ErrorKind::HttpResponse { status, .. } if u16::from(*status) == 404 => {
+                    debug!("Blob '{cid}' not found in Azure Blob Storage.");
+                    return Ok(None);
+                }
+                _ => return Err(e.into()),
+            },
+            None => vec![],
+        };
+
+        let rest = pages
+            .map_err(anyhow::Error::from)
+            .and_then(|page| async move { Ok(page.data.collect().await?.to_vec()) });
+
+        Ok(Some(stream::once(async { Ok(first) }).chain(rest).boxed()))
+    }
+
+    /// Put a streamed blob into the store
+    ///
+    /// With a known CID the blocks are staged as they arrive and only committed if they
+    /// hash to it; uncommitted blocks are discarded by Azure. Otherwise the blob is
+    /// spooled to a temporary file first to compute its name.
+    async fn put_stream(
+        &self,
+        chunks: BlobStream,
+        multicodec_code: u64,
+        cid: Option<&str>,
+    ) -> Result<String> {
+        let client = self.client.clone().ok_or(anyhow!("client not init"))?;
+
+        let (cid, chunks, _spooled) = match cid {
+            Some(cid) => (cid.to_owned(), chunks, None),
+            None => {
+                let (cid, file) = spool(chunks, multicodec_code, None).await?;
+                let chunks = blob_stream_from_file(file.path())?;
+                (cid, chunks, Some(file))
+            }
+        };
+        let blob_client = client.blob_client(self.container.as_str(), &cid);
+
+        let mut hasher = CidHasher::new(multicodec_code);
+        let mut blocks = rechunk(chunks, BLOCK_SIZE);
+        let mut block_list = BlockList::default();
+        while let Some(block) = blocks.try_next().await? {
+            hasher.update(&block);
+            let block_id = BlockId::new(format!("{:08}", block_list.blocks.len()));
+            blob_client.put_block(block_id.clone(), block).await?;
+            block_list
+                .blocks
+                .push(BlobBlockType::new_uncommitted(block_id));
+        }
+        hasher.finalize_and_validate(Some(&cid))?;
+
+        trace!(
+            "put stream {cid}. blob size: {}, blocks: {}",
+            hasher.len(),
+            block_list.blocks.len()
+        );
+        blob_client
+            .put_block_list(block_list)
+            .content_type("text/plain")
+            .await?;
+
+        Ok(cid)
+    }
+
+    /// Delete a blob from the store
+    async fn delete(&self, cid: &str) -> Result<()> {
+        trace!("delete {cid}.");
+        let client = self.client.clone().ok_or(anyhow!("client not init"))?;
+
+        match client
+            .blob_client(self.container.as_str(), cid)
+            .delete()
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => match e.kind() {
+                ErrorKind::HttpResponse { status, .. } if u16::from(*status) == 404 => {
+                    debug!("Blob '{cid}' not found in Azure Blob Storage, nothing to delete.");
+                    Ok(())
+                }
+                _ => Err(e.into()),
+            },
+        }
+    }
+
+    /// List the CIDs in the container
+    fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>> {
+        let Some(client) = self.client.clone() else {
+            return stream::once(async { Err(anyhow!("client not init")) }).boxed();
+        };
+
+        let mut list_blobs = client
+            .container_client(self.container.as_str())
+            .list_blobs();
+        if let Some(prefix) = options.prefix.clone() {
+            list_blobs = list_blobs.prefix(prefix);
+        }
+
+        let names = list_blobs
+            .into_stream()
+            .map_err(anyhow::Error::from)
+            .map_ok(|page| {
+                let names = page
+                    .blobs
+                    .blobs()
+                    .map(|blob| Ok(blob.name.clone()))
+                    .collect::<Vec<_>>();
+                stream::iter(names)
+            })
+            .try_flatten();
+
+        filter_listing(names, options)
+    }
 }
@@ -0,0 +1,612 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use cid::Cid;
+use futures_util::stream::{BoxStream, StreamExt, TryStreamExt};
+use log::{debug, trace};
+
+use crate::blob_store::{chunks::BlobStream, BlobStore, ListOptions};
+
+/// How [`CachedBlobStore`] writes new blobs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WritePolicy {
+    /// Write to the remote store and the cache
+    #[default]
+    WriteThrough,
+    /// Write to the cache only, the remote store is written by [`CachedBlobStore::flush`]
+    WriteBack,
+    /// Write to the remote store only, blobs are cached when first read
+    WriteAround,
+}
+
+/// Options for [`CachedBlobStore`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CacheOptions {
+    /// Maximum bytes kept in the cache, unbounded if `None`
+    pub max_bytes: Option<u64>,
+    /// How new blobs are written
+    pub write_policy: WritePolicy,
+}
+
+/// Counters of a [`CachedBlobStore`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    /// Reads served by the cache
+    pub hits: u64,
+    /// Reads that went to the remote store
+    pub misses: u64,
+    /// Blobs evicted to stay within the byte budget
+    pub evictions: u64,
+    /// Bytes currently cached
+    pub cached_bytes: u64,
+    /// Blobs awaiting write-back to the remote store
+    pub pending_writes: u64,
+}
+
+/// Least recently used order of the cached blobs.
+#[derive(Default)]
+struct LruIndex {
+    /// Size and last use of each cached blob
+    entries: HashMap<String, (u64, u64)>,
+    /// Cached blobs by last use
+    order: BTreeMap<u64, String>,
+    clock: u64,
+    total_bytes: u64,
+}
+
+impl LruIndex {
+    fn touch(&mut self, cid: &str) {
+        if let Some((_, last_used)) = self.entries.get_mut(cid) {
+            self.order.remove(last_used);
+            self.clock += 1;
+            *last_used = self.clock;
+            self.order.insert(self.clock, cid.to_owned());
+        }
+    }
+
+    fn insert(&mut self, cid: &str, size: u64) {
+        self.remove(cid);
+        self.clock += 1;
+        self.entries.insert(cid.to_owned(), (size, self.clock));
+        self.order.insert(self.clock, cid.to_owned());
+        self.total_bytes += size;
+    }
+
+    fn remove(&mut self, cid: &str) {
+        if let Some((size, last_used)) = self.entries.remove(cid) {
+            self.order.remove(&last_used);
+            self.total_bytes -= size;
+        }
+    }
+
+    /// Removes least recently used blobs until `max_bytes` fit, skipping `pinned` ones.
+    fn evict(&mut self, max_bytes: u64, pinned: &HashSet<String>) -> Vec<String> {
+        let mut evicted = vec![];
+        let mut candidates = self.order.values();
+        let mut total_bytes = self.total_bytes;
+        while total_bytes > max_bytes {
+            let Some(cid) = candidates.next() else {
+                break;
+            };
+            if pinned.contains(cid) {
+                continue;
+            }
+            total_bytes -= self.entries[cid].0;
+            evicted.push(cid.clone());
+        }
+
+        for cid in &evicted {
+            self.remove(cid);
+        }
+        evicted
+    }
+}
+
+/// Blob store that layers a fast cache store in front of a remote store.
+///
+/// Reads are served from the cache when possible and otherwise fetched from the remote store
+/// and cached. Blobs are content-addressed, so cached blobs never go stale and the cache
+/// needs no invalidation. With a byte budget the least recently used blobs are evicted; blobs
+/// pending write-back are never evicted.
+///
+/// Any store can be the cache, e.g. a [`LocalFs`](crate::LocalFs) directory. With a byte
+/// budget the blobs already in the cache are read once by `init` to account for their size.
+/// With write-back, `init` marks cached blobs missing from the remote store, e.g. written
+/// back before a restart, as pending write-back again.
+pub struct CachedBlobStore<C, R> {
+    cache: C,
+    remote: R,
+    options: CacheOptions,
+    index: Mutex<LruIndex>,
+    /// Written back blobs that aren't in the remote store yet
+    pending: Mutex<HashSet<String>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl<C, R> CachedBlobStore<C, R>
+where
+    C: BlobStore + Send + Sync,
+    R: BlobStore + Send + Sync,
+{
+    /// Creates a cached blob store
+    ///
+    /// # Arguments
+    /// * `cache` - Fast store holding cached blobs
+    /// * `remote` - Store the cache is in front of
+    /// * `options` - Byte budget and write policy
+    pub fn new(cache: C, remote: R, options: CacheOptions) -> Self {
+        Self {
+            cache,
+            remote,
+            options,
+            index: Mutex::default(),
+            pending: Mutex::default(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cache store.
+    pub fn cache(&self) -> &C {
+        &self.cache
+    }
+
+    /// Returns the remote store.
+    pub fn remote(&self) -> &R {
+        &self.remote
+    }
+
+    /// Returns the hit, miss and eviction counters and the cache size.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            cached_bytes: self.index.lock().unwrap().total_bytes,
+            pending_writes: self.pending.lock().unwrap().len() as u64,
+        }
+    }
+
+    /// Writes the blobs pending write-back to the remote store.
+    ///
+    /// # Returns
+    /// * `Result<Vec<String>>` - CIDs of the blobs written
+    pub async fn flush(&self) -> Result<Vec<String>> {
+        let mut pending = self
+            .pending
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        pending.sort();
+
+        for cid in &pending {
+            let chunks =
+                self.cache.get_stream(cid).await?.ok_or_else(|| {
+                    anyhow!("Blob '{cid}' pending write-back is missing from cache.")
+                })?;
+            self.remote
+                .put_stream(chunks, Cid::try_from(cid.as_str())?.codec(), Some(cid))
+                .await?;
+            self.pending.lock().unwrap().remove(cid);
+            trace!("wrote back {cid}.");
+        }
+        self.evict().await?;
+
+        Ok(pending)
+    }
+
+    /// Records a blob added to the cache and evicts blobs over the byte budget.
+    async fn cached(&self, cid: &str, size: u64) -> Result<()> {
+        self.index.lock().unwrap().insert(cid, size);
+        self.evict().await
+    }
+
+    async fn evict(&self) -> Result<()> {
+        let Some(max_bytes) = self.options.max_bytes else {
+            return Ok(());
+        };
+
+        let evicted = {
+            let pending = self.pending.lock().unwrap();
+            self.index.lock().unwrap().evict(max_bytes, &pending)
+        };
+        if evicted.is_empty() {
+            return Ok(());
+        }
+
+        debug!("evicting {} blobs from cache.", evicted.len());
+        self.evictions
+            .fetch_add(evicted.len() as u64, Ordering::Relaxed);
+        self.cache.delete_many(evicted, None).await
+    }
+
+    fn hit(&self, cid: &str) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.index.lock().unwrap().touch(cid);
+    }
+}
+
+/// Counts the bytes passing through a stream.
+fn counted(chunks: BlobStream) -> (BlobStream, Arc<AtomicU64>) {
+    let size = Arc::new(AtomicU64::new(0));
+    let counter = size.clone();
+    let chunks = chunks
+        .inspect_ok(move |chunk| {
+            counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+        })
+        .boxed();
+    (chunks, size)
+}
+
+#[async_trait]
+impl<C, R> BlobStore for CachedBlobStore<C, R>
+where
+    C: BlobStore + Send + Sync,
+    R: BlobStore + Send + Sync,
+{
+    async fn init(&mut self) -> Result<()> {
+        self.cache.init().await?;
+        self.remote.init().await?;
+
+        let budgeted = self.options.max_bytes.is_some();
+        let write_back = self.options.write_policy == WritePolicy::WriteBack;
+        if !budgeted && !write_back {
+            return Ok(());
+        }
+
+        let cids = self
+            .cache
+            .list(ListOptions::default())
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        if budgeted {
+            for cid in &cids {
+                if let Some(chunks) = self.cache.get_stream(cid).await? {
+                    let (chunks, size) = counted(chunks);
+                    chunks.try_for_each(|_| async { Ok(()) }).await?;
+                    self.index
+                        .lock()
+                        .unwrap()
+                        .insert(cid, size.load(Ordering::Relaxed));
+                }
+            }
+        }
+
+        // blobs written back before a restart but not flushed are only in the cache
+        if write_back {
+            let unflushed = self
+                .remote
+                .exists_many(cids, None)
+                .await?
+                .into_iter()
+                .filter(|result| !result.exists)
+                .map(|result| result.cid);
+            self.pending.lock().unwrap().extend(unflushed);
+        }
+
+        if budgeted {
+            self.evict().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn exists(&self, cid: &str) -> Result<bool> {
+        Ok(self.cache.exists(cid).await? || self.remote.exists(cid).await?)
+    }
+
+    async fn get(&self, cid: &str) -> Result<Option<Vec<u8>>> {
+        if let Some(blob) = self.cache.get(cid).await? {
+            trace!("cache hit {cid}.");
+            self.hit(cid);
+            return Ok(Some(blob));
+        }
+
+        trace!("cache miss {cid}.");
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let Some(blob) = self.remote.get(cid).await? else {
+            return Ok(None);
+        };
+
+        let codec = Cid::try_from(cid)?.codec();
+        self.cache.put(blob.clone(), codec, Some(cid)).await?;
+        self.cached(cid, blob.len() as u64).await?;
+
+        Ok(Some(blob))
+    }
+
+    async fn put(&self, blob: Vec<u8>, multicodec_code: u64, cid: Option<&str>) -> Result<String> {
+        let size = blob.len() as u64;
+        let cid = match self.options.write_policy {
+            WritePolicy::WriteThrough => {
+                let cid = self.remote.put(blob.clone(), multicodec_code, cid).await?;
+                self.cache.put(blob, multicodec_code, Some(&cid)).await?
+            }
+            WritePolicy::WriteBack => {
+                let cid = self.cache.put(blob, multicodec_code, cid).await?;
+                if !self.remote.exists(&cid).await? {
+                    self.pending.lock().unwrap().insert(cid.clone());
+                }
+                cid
+            }
+            WritePolicy::WriteAround => {
+                return self.remote.put(blob, multicodec_code, cid).await;
+            }
+        };
+        self.cached(&cid, size).await?;
+
+        Ok(cid)
+    }
+
+    async fn get_stream(&self, cid: &str) -> Result<Option<BlobStream>> {
+        if let Some(chunks) = self.cache.get_stream(cid).await? {
+            trace!("cache hit {cid}.");
+            self.hit(cid);
+            return Ok(Some(chunks));
+        }
+
+        trace!("cache miss {cid}.");
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let Some(chunks) = self.remote.get_stream(cid).await? else {
+            return Ok(None);
+        };
+
+        // cache the whole blob, verifying it, before serving it from the cache
+        let (chunks, size) = counted(chunks);
+        let codec = Cid::try_from(cid)?.codec();
+        self.cache.put_stream(chunks, codec, Some(cid)).await?;
+        self.cached(cid, size.load(Ordering::Relaxed)).await?;
+
+        // a blob over the byte budget, or evicted meanwhile, is streamed from the remote
+        match self.cache.get_stream(cid).await? {
+            Some(chunks) => Ok(Some(chunks)),
+            None => {
+                debug!("{cid} was evicted before it was read, streaming from remote.");
+                self.remote.get_stream(cid).await
+            }
+        }
+    }
+
+    async fn put_stream(
+        &self,
+        chunks: BlobStream,
+        multicodec_code: u64,
+        cid: Option<&str>,
+    ) -> Result<String> {
+        if self.options.write_policy == WritePolicy::WriteAround {
+            return self.remote.put_stream(chunks, multicodec_code, cid).await;
+        }
+
+        let (chunks, size) = counted(chunks);
+        let cid = self.cache.put_stream(chunks, multicodec_code, cid).await?;
+
+        if self.options.write_policy == WritePolicy::WriteThrough {
+            let chunks =
+                self.cache.get_stream(&cid).await?.ok_or_else(|| {
+                    anyhow!("Blob '{cid}' is missing from cache after writing it.")
+                })?;
+            self.remote
+                .put_stream(chunks, multicodec_code, Some(&cid))
+                .await?;
+        } else if !self.remote.exists(&cid).await? {
+            self.pending.lock().unwrap().insert(cid.clone());
+        }
+        self.cached(&cid, size.load(Ordering::Relaxed)).await?;
+
+        Ok(cid)
+    }
+
+    async fn delete(&self, cid: &str) -> Result<()> {
+        self.pending.lock().unwrap().remove(cid);
+        self.index.lock().unwrap().remove(cid);
+        self.cache.delete(cid).await?;
+        self.remote.delete(cid).await
+    }
+
+    /// Lists the remote store; blobs pending write-back are listed once flushed.
+    fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>> {
+        self.remote.list(options)
+    }
+
+    fn batch_concurrency_limit(&self) -> usize {
+        self.remote.batch_concurrency_limit()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "blob-local")]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+    use crate::blob_store::LocalFs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "integrity-blob-cached-{name}-{}",
+            std::process::id()
+        ))
+    }
+
+    #[tokio::test]
+    async fn reads_through_and_evicts_least_recently_used() {
+        let mut remote = LocalFs::new(temp_dir("remote"));
+        remote.init().await.unwrap();
+        let mut cids = vec![];
+        for blob in ["blob one..", "blob two..", "blob three"] {
+            cids.push(remote.put(blob.into(), 0x55, None).await.unwrap());
+        }
+
+        let options = CacheOptions {
+            max_bytes: Some(25),
+            write_policy: WritePolicy::WriteBack,
+        };
+        let mut store = CachedBlobStore::new(LocalFs::new(temp_dir("cache")), remote, options);
+        store.init().await.unwrap();
+
+        assert_eq!(store.get(&cids[0]).await.unwrap().unwrap(), b"blob one..");
+        assert_eq!(store.get(&cids[0]).await.unwrap().unwrap(), b"blob one..");
+        store.get(&cids[1]).await.unwrap();
+        // reading the first blob makes the second least recently used
+        store.get(&cids[0]).await.unwrap();
+        let chunks = store.get_stream(&cids[2]).await.unwrap().unwrap();
+        assert_eq!(chunks.try_concat().await.unwrap(), b"blob three");
+
+        assert_eq!(
+            store.stats(),
+            CacheStats {
+                hits: 2,
+                misses: 3,
+                evictions: 1,
+                cached_bytes: 20,
+                pending_writes: 0,
+            }
+        );
+        assert!(store.cache().exists(&cids[0]).await.unwrap());
+        assert!(!store.cache().exists(&cids[1]).await.unwrap());
+        assert!(store.get(&cids[1]).await.unwrap().is_some());
+
+        let written = store.put(b"written".to_vec(), 0x55, None).await.unwrap();
+        assert!(!store.remote().exists(&written).await.unwrap());
+        assert!(store.exists(&written).await.unwrap());
+        assert_eq!(store.stats().pending_writes, 1);
+
+        assert_eq!(store.flush().await.unwrap(), vec![written.clone()]);
+        assert!(store.remote().exists(&written).await.unwrap());
+        assert_eq!(store.stats().pending_writes, 0);
+        assert!(store.stats().cached_bytes <= 25);
+
+        // a new store accounts for the blobs already cached
+        let mut reopened = CachedBlobStore::new(
+            LocalFs::new(temp_dir("cache")),
+            LocalFs::new(temp_dir("remote")),
+            CacheOptions {
+                max_bytes: Some(10),
+                ..Default::default()
+            },
+        );
+        reopened.init().await.unwrap();
+        assert!(reopened.stats().cached_bytes <= 10);
+
+        fs::remove_dir_all(temp_dir("remote")).unwrap();
+        fs::remove_dir_all(temp_dir("cache")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn streams_blobs_larger_than_the_budget() {
+        let mut remote = LocalFs::new(temp_dir("large-remote"));
+        remote.init().await.unwrap();
+        let cid = remote
+            .put(b"larger than the budget".to_vec(), 0x55, None)
+            .await
+            .unwrap();
+
+        let options = CacheOptions {
+            max_bytes: Some(5),
+            ..Default::default()
+        };
+        let mut store =
+            CachedBlobStore::new(LocalFs::new(temp_dir("large-cache")), remote, options);
+        store.init().await.unwrap();
+
+        let chunks = store.get_stream(&cid).await.unwrap().unwrap();
+        assert_eq!(
+            chunks.try_concat().await.unwrap(),
+            b"larger than the budget"
+        );
+        assert_eq!(
+            store.get(&cid).await.unwrap().unwrap(),
+            b"larger than the budget"
+        );
+        assert!(!store.cache().exists(&cid).await.unwrap());
+        assert_eq!(store.stats().cached_bytes, 0);
+
+        fs::remove_dir_all(temp_dir("large-remote")).unwrap();
+        fs::remove_dir_all(temp_dir("large-cache")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn keeps_blobs_pending_write_back_across_restarts() {
+        let options = CacheOptions {
+            max_bytes: Some(10),
+            write_policy: WritePolicy::WriteBack,
+        };
+        let open = || {
+            CachedBlobStore::new(
+                LocalFs::new(temp_dir("restart-cache")),
+                LocalFs::new(temp_dir("restart-remote")),
+                options.clone(),
+            )
+        };
+
+        let mut store = open();
+        store.init().await.unwrap();
+        let first = store.put(b"first blob".to_vec(), 0x55, None).await.unwrap();
+        let second = store
+            .put(b"second blob".to_vec(), 0x55, None)
+            .await
+            .unwrap();
+        drop(store);
+
+        // the remote store only has what's been flushed, so nothing can be evicted
+        let mut reopened = open();
+        reopened.init().await.unwrap();
+        assert_eq!(reopened.stats().pending_writes, 2);
+        assert!(reopened.cache().exists(&first).await.unwrap());
+        assert!(reopened.cache().exists(&second).await.unwrap());
+
+        let mut flushed = reopened.flush().await.unwrap();
+        flushed.sort();
+        let mut expected = vec![first.clone(), second.clone()];
+        expected.sort();
+        assert_eq!(flushed, expected);
+        assert!(reopened.remote().exists(&first).await.unwrap());
+        assert!(reopened.remote().exists(&second).await.unwrap());
+        assert!(reopened.stats().cached_bytes <= 10);
+
+        fs::remove_dir_all(temp_dir("restart-remote")).unwrap();
+        fs::remove_dir_all(temp_dir("restart-cache")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn flushes_unbudgeted_write_back_blobs_after_restart() {
+        let options = CacheOptions {
+            max_bytes: None,
+            write_policy: WritePolicy::WriteBack,
+        };
+        let open = || {
+            CachedBlobStore::new(
+                LocalFs::new(temp_dir("unbudgeted-cache")),
+                LocalFs::new(temp_dir("unbudgeted-remote")),
+                options.clone(),
+            )
+        };
+
+        let mut store = open();
+        store.init().await.unwrap();
+        let cid = store.put(b"unflushed".to_vec(), 0x55, None).await.unwrap();
+        drop(store);
+
+        let mut reopened = open();
+        reopened.init().await.unwrap();
+        assert_eq!(reopened.stats().pending_writes, 1);
+        assert_eq!(reopened.flush().await.unwrap(), vec![cid.clone()]);
+        assert!(reopened.remote().exists(&cid).await.unwrap());
+
+        fs::remove_dir_all(temp_dir("unbudgeted-remote")).unwrap();
+        fs::remove_dir_all(temp_dir("unbudgeted-cache")).unwrap();
+    }
+}
@@ -0,0 +1,159 @@
+//! Chunked blob streams and incremental CID computation for large blobs.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use cid::{multihash::MultihashGeneric, Cid};
+use futures_util::stream::{self, BoxStream, StreamExt};
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    any(feature = "blob-azure", feature = "blob-s3")
+))]
+use futures_util::TryStreamExt;
+
+/// A blob as a stream of byte chunks.
+pub type BlobStream = BoxStream<'static, Result<Vec<u8>>>;
+
+/// Size of the chunks read from files by [`blob_stream_from_file`].
+pub const FILE_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Computes the BLAKE3 CID of a blob fed in chunks.
+///
+/// Produces the same CIDs as the stores' `put`, without holding the blob in memory.
+#[derive(Clone)]
+pub struct CidHasher {
+    multicodec_code: u64,
+    #[cfg(not(target_arch = "wasm32"))]
+    hasher: iroh_blake3::Hasher,
+    #[cfg(target_arch = "wasm32")]
+    hasher: blake3::Hasher,
+    len: u64,
+}
+
+impl CidHasher {
+    /// Creates a hasher for a blob with the given multicodec.
+    pub fn new(multicodec_code: u64) -> Self {
+        Self {
+            multicodec_code,
+            #[cfg(not(target_arch = "wasm32"))]
+            hasher: iroh_blake3::Hasher::new(),
+            #[cfg(target_arch = "wasm32")]
+            hasher: blake3::Hasher::new(),
+            len: 0,
+        }
+    }
+
+    /// Feeds the next chunk of the blob.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+        self.len += chunk.len() as u64;
+    }
+
+    /// Returns the number of bytes hashed so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns whether no bytes have been hashed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the CID of the bytes hashed so far.
+    pub fn finalize(&self) -> Result<String> {
+        let hash = self.hasher.finalize();
+        let multihash = MultihashGeneric::<64>::wrap(0x1e, hash.as_bytes())?;
+        Ok(Cid::new_v1(self.multicodec_code, multihash).to_string())
+    }
+
+    /// Returns the CID of the bytes hashed so far, or error if it isn't `expected_cid`.
+    pub fn finalize_and_validate(&self, expected_cid: Option<&str>) -> Result<String> {
+        let computed_cid = self.finalize()?;
+
+        if let Some(cid) = expected_cid {
+            if cid != computed_cid {
+                return Err(anyhow!(
+                    "Computed CID '{computed_cid}' doesn't match provided CID '{cid}'.",
+                ));
+            }
+        }
+
+        Ok(computed_cid)
+    }
+}
+
+/// Streams a file in chunks of [`FILE_CHUNK_SIZE`] bytes.
+///
+/// On native targets the file is read with `tokio::fs`, off the async executor, so the
+/// stream must be polled within a tokio runtime.
+///
+/// # Arguments
+/// * `path` - File to stream
+///
+/// # Returns
+/// * `Result<BlobStream>` - The file's contents, or error if it can't be opened
+pub fn blob_stream_from_file(path: impl AsRef<Path>) -> Result<BlobStream> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let file = tokio::fs::File::from_std(std::fs::File::open(path)?);
+    #[cfg(target_arch = "wasm32")]
+    let file = std::fs::File::open(path)?;
+
+    Ok(stream::try_unfold(file, |mut file| async move {
+        let mut chunk = vec![0; FILE_CHUNK_SIZE];
+        #[cfg(not(target_arch = "wasm32"))]
+        let read = tokio::io::AsyncReadExt::read(&mut file, &mut chunk).await?;
+        #[cfg(target_arch = "wasm32")]
+        let read = std::io::Read::read(&mut file, &mut chunk)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        chunk.truncate(read);
+        Ok(Some((chunk, file)))
+    })
+    .boxed())
+}
+
+/// Regroups a stream into chunks of at least `size` bytes, except for the last one.
+///
+/// Used to build multipart upload parts, which have a minimum size.
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    any(feature = "blob-azure", feature = "blob-s3")
+))]
+pub(crate) fn rechunk(chunks: BlobStream, size: usize) -> BlobStream {
+    stream::try_unfold(Some(chunks), move |chunks| async move {
+        let Some(mut chunks) = chunks else {
+            return Ok(None);
+        };
+
+        let mut part = Vec::with_capacity(size);
+        while part.len() < size {
+            match chunks.try_next().await? {
+                Some(chunk) => part.extend_from_slice(&chunk),
+                None if part.is_empty() => return Ok(None),
+                None => return Ok(Some((part, None))),
+            }
+        }
+        Ok(Some((part, Some(chunks))))
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hasher_matches_whole_blob_cid() {
+        let mut hasher = CidHasher::new(0x55);
+        hasher.update(b"Hello ");
+        hasher.update(b"World");
+
+        assert_eq!(hasher.len(), 11);
+        assert_eq!(
+            hasher.finalize().unwrap(),
+            "bafkr4icb7a4uceploe5cefs4i3eqvohq7wjztsjafd6w2kejiszd75n7oy"
+        );
+        assert!(hasher.finalize_and_validate(Some("bafkr4")).is_err());
+    }
+}
@@ -6,10 +6,14 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use bytes::Bytes;
-use google_cloud_storage::client::Storage;
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use google_cloud_storage::client::{Storage, StorageControl};
 use log::{debug, trace};
 
-use crate::blob_store::{calc_and_validate_cid, BlobStore};
+use crate::blob_store::{
+    calc_and_validate_cid, chunks::BlobStream, filter_listing, temp_file::spool, BlobStore,
+    ListOptions,
+};
 
 /// A blob store implementation backed by Google Cloud Storage.
 ///
@@ -22,6 +26,8 @@ pub struct GCS {
     folder: String,
     /// The GCS client, initialized via [`GCS::init`].
     client: Option<Storage>,
+    /// The GCS control plane client used for listing, initialized via [`GCS::init`].
+    control: Option<StorageControl>,
 }
 
 impl GCS {
@@ -47,6 +53,7 @@ impl GCS {
             bucket,
             folder,
             client: None,
+            control: None,
         }
     }
 
@@ -65,16 +72,18 @@ impl GCS {
 impl BlobStore for GCS {
     /// Initializes the GCS client for this blob store.
     ///
-    /// This must be called before any other operations. The client is built
+    /// This must be called before any other operations. The clients are built
     /// using default credentials from the environment.
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - Client initialized successfully.
-    /// * `Err(_)` - Failed to build the GCS client.
+    /// * `Ok(())` - Clients initialized successfully.
+    /// * `Err(_)` - Failed to build the GCS clients.
     async fn init(&mut self) -> Result<()> {
         let client = Storage::builder().build().await?;
         self.client = Some(client);
+        let control = StorageControl::builder().build().await?;
+        self.control = Some(control);
         Ok(())
     }
 
@@ -163,6 +172,147 @@ impl BlobStore for GCS {
         trace!("Upload to GCS complete");
         Ok(cid)
     }
+
+    /// Streams a blob from GCS as it is downloaded.
+    async fn get_stream(&self, cid: &str) -> Result<Option<BlobStream>> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Client not initialized"))?;
+
+        let object_name = self.object_name(cid);
+
+        match client
+            .read_object(self.bucket_path(), &object_name)
+            .send()
+            .await
+        {
+            Ok(reader) => Ok(Some(
+                stream::try_unfold(reader, |mut reader| async move {
+                    match reader.next().await {
+                        Some(data) => Ok(Some((data?.to_vec(), reader))),
+                        None => Ok(None),
+                    }
+                })
+                .boxed(),
+            )),
+            Err(e) => {
+                let err_str = format!("{e}");
+                if err_str.contains("404") || err_str.contains("Not Found") {
+                    debug!("Object not found in GCS: {}", object_name);
+                    Ok(None)
+                } else {
+                    Err(e.into())
+                }
+            }
+        }
+    }
+
+    /// Stores a streamed blob in GCS.
+    ///
+    /// The blob is spooled to a temporary file to compute its CID, then uploaded from the
+    /// file with a resumable upload.
+    async fn put_stream(
+        &self,
+        chunks: BlobStream,
+        multicodec_code: u64,
+        cid: Option<&str>,
+    ) -> Result<String> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Client not initialized"))?;
+
+        let (cid, file) = spool(chunks, multicodec_code, cid).await?;
+        debug!("calculated cid: {}", cid);
+
+        let object_name = self.object_name(&cid);
+
+        let payload = tokio::fs::File::open(file.path()).await?;
+        client
+            .write_object(self.bucket_path(), &object_name, payload)
+            .send_buffered()
+            .await?;
+
+        trace!("Streamed upload to GCS complete");
+        Ok(cid)
+    }
+
+    /// Deletes a blob from GCS. Missing objects are not an error.
+    async fn delete(&self, cid: &str) -> Result<()> {
+        let control = self
+            .control
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Client not initialized"))?;
+
+        let object_name = self.object_name(cid);
+
+        match control
+            .delete_object()
+            .set_bucket(self.bucket_path())
+            .set_object(&object_name)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                let err_str = format!("{e}");
+                if err_str.contains("404") || err_str.contains("Not Found") {
+                    debug!("Object not found in GCS: {}", object_name);
+                    Ok(())
+                } else {
+                    Err(e.into())
+                }
+            }
+        }
+    }
+
+    /// Lists the CIDs stored in the bucket folder, one page of objects at a time.
+    fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>> {
+        let Some(control) = self.control.as_ref() else {
+            return stream::once(async { Err(anyhow::anyhow!("Client not initialized")) }).boxed();
+        };
+
+        let prefix = self.object_name(options.prefix.as_deref().unwrap_or_default());
+        let start = options
+            .start_after
+            .as_deref()
+            .map(|start_after| self.object_name(start_after))
+            .unwrap_or_default();
+
+        // `None` once the last page has been read
+        let names = stream::try_unfold(Some(String::new()), move |page_token| {
+            let (prefix, start) = (prefix.clone(), start.clone());
+            async move {
+                let Some(page_token) = page_token else {
+                    return Ok(None);
+                };
+
+                let page = control
+                    .list_objects()
+                    .set_parent(self.bucket_path())
+                    .set_prefix(prefix)
+                    .set_lexicographic_start(start)
+                    .set_page_token(page_token)
+                    .send()
+                    .await?;
+
+                let names = page
+                    .objects
+                    .into_iter()
+                    .filter_map(|object| object.name.strip_prefix(&self.folder).map(str::to_owned))
+                    .map(Ok)
+                    .collect::<Vec<_>>();
+                let next_page_token =
+                    (!page.next_page_token.is_empty()).then_some(page.next_page_token);
+
+                Ok::<_, anyhow::Error>(Some((stream::iter(names), next_page_token)))
+            }
+        })
+        .try_flatten();
+
+        filter_listing(names, options)
+    }
 }
 
 #[cfg(test)]
@@ -1,21 +1,84 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
 
 use anyhow::Result;
 use async_trait::async_trait;
+use futures_util::stream::{self, BoxStream};
 
-use crate::blob_store::BlobStore;
+use crate::blob_store::{calc_and_validate_cid, filter_listing, BlobStore, ListOptions};
 
 /// In-memory blob storage for testing
 ///
 /// Stores blobs in a HashMap. Not persistent. Used for testing and development.
-#[cfg(not(target_arch = "wasm32"))]
+///
+/// The store is thread-safe and clones share the same blobs, so a clone can be handed out as
+/// an `Arc<dyn BlobStore + Send + Sync>` while the original is used to inspect what was
+/// written. Available on wasm32.
+#[derive(Clone, Debug, Default)]
 pub struct InMemoryStore {
+    inner: Arc<RwLock<Blobs>>,
+}
+
+#[derive(Debug, Default)]
+struct Blobs {
     /// Map of CIDs to blob data
-    pub blobs: HashMap<String, Vec<u8>>,
+    blobs: HashMap<String, Vec<u8>>,
+    /// Total size of the stored blobs
+    total_bytes: u64,
+}
+
+impl InMemoryStore {
+    /// Creates an empty in-memory blob store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored blobs.
+    pub fn len(&self) -> usize {
+        self.read().blobs.len()
+    }
+
+    /// Returns whether no blobs are stored.
+    pub fn is_empty(&self) -> bool {
+        self.read().blobs.is_empty()
+    }
+
+    /// Returns the total size in bytes of the stored blobs.
+    pub fn total_bytes(&self) -> u64 {
+        self.read().total_bytes
+    }
+
+    /// Removes every blob.
+    pub fn clear(&self) {
+        let mut blobs = self.write();
+        blobs.blobs.clear();
+        blobs.total_bytes = 0;
+    }
+
+    // a panic while holding the lock can't leave the map half-updated
+    fn read(&self) -> RwLockReadGuard<'_, Blobs> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Blobs> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Creates a store holding `blobs`, keyed by CID. The CIDs aren't checked.
+impl From<HashMap<String, Vec<u8>>> for InMemoryStore {
+    fn from(blobs: HashMap<String, Vec<u8>>) -> Self {
+        let total_bytes = blobs.values().map(|blob| blob.len() as u64).sum();
+
+        Self {
+            inner: Arc::new(RwLock::new(Blobs { blobs, total_bytes })),
+        }
+    }
 }
 
 #[async_trait]
-#[cfg(not(target_arch = "wasm32"))]
 impl BlobStore for InMemoryStore {
     async fn init(&mut self) -> Result<()> {
         Ok(())
@@ -24,7 +87,7 @@ impl BlobStore for InMemoryStore {
     async fn exists(&self, cid: &str) -> Result<bool> {
         log::trace!("check exists {cid}.");
 
-        let exists = self.blobs.contains_key(cid);
+        let exists = self.read().blobs.contains_key(cid);
 
         Ok(exists)
     }
@@ -32,17 +95,106 @@ impl BlobStore for InMemoryStore {
     async fn get(&self, cid: &str) -> Result<Option<Vec<u8>>> {
         log::trace!("get {cid}.");
 
-        let blob = self.blobs.get(cid).map(ToOwned::to_owned);
+        let blob = self.read().blobs.get(cid).map(ToOwned::to_owned);
 
         Ok(blob)
     }
 
-    async fn put(
-        &self,
-        _blob: Vec<u8>,
-        _multicodec_code: u64,
-        _cid: Option<&str>,
-    ) -> Result<String> {
-        unimplemented!();
+    async fn put(&self, blob: Vec<u8>, multicodec_code: u64, cid: Option<&str>) -> Result<String> {
+        let cid = calc_and_validate_cid(&blob, multicodec_code, cid)?;
+
+        log::trace!("put {cid}. blob size: {}", blob.len());
+
+        let mut blobs = self.write();
+        if blobs.blobs.contains_key(&cid) {
+            log::debug!("blob with cid {cid} already exists.");
+        } else {
+            blobs.total_bytes += blob.len() as u64;
+            blobs.blobs.insert(cid.clone(), blob);
+        }
+
+        Ok(cid)
+    }
+
+    async fn delete(&self, cid: &str) -> Result<()> {
+        log::trace!("delete {cid}.");
+
+        let mut blobs = self.write();
+        if let Some(blob) = blobs.blobs.remove(cid) {
+            blobs.total_bytes -= blob.len() as u64;
+        }
+
+        Ok(())
+    }
+
+    fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>> {
+        log::trace!("list {options:?}.");
+
+        let mut cids = self.read().blobs.keys().cloned().collect::<Vec<_>>();
+        cids.sort();
+
+        filter_listing(stream::iter(cids.into_iter().map(Ok)), options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+
+    use super::*;
+
+    #[test]
+    fn stores_blobs_shared_between_clones() {
+        futures_executor::block_on(async {
+            let store = InMemoryStore::new();
+            let shared: Arc<dyn BlobStore + Send + Sync> = Arc::new(store.clone());
+
+            let cid = shared
+                .put(b"Hello World".to_vec(), 0x55, None)
+                .await
+                .unwrap();
+            assert_eq!(
+                cid,
+                "bafkr4icb7a4uceploe5cefs4i3eqvohq7wjztsjafd6w2kejiszd75n7oy"
+            );
+            assert!(shared
+                .put(b"Hello".to_vec(), 0x55, Some(&cid))
+                .await
+                .is_err());
+            shared
+                .put(b"Hello World".to_vec(), 0x55, None)
+                .await
+                .unwrap();
+            let json = shared.put(b"{}".to_vec(), 0x0129, None).await.unwrap();
+
+            assert_eq!(store.len(), 2);
+            assert_eq!(store.total_bytes(), 13);
+            assert_eq!(store.get(&cid).await.unwrap().unwrap(), b"Hello World");
+
+            let raw = ListOptions {
+                multicodec_code: Some(0x55),
+                ..Default::default()
+            };
+            assert_eq!(
+                store.list(raw).try_collect::<Vec<_>>().await.unwrap(),
+                vec![cid.clone()]
+            );
+
+            store.delete(&cid).await.unwrap();
+            store.delete(&cid).await.unwrap();
+            assert!(!shared.exists(&cid).await.unwrap());
+            assert_eq!(store.total_bytes(), 2);
+
+            let copy = InMemoryStore::from(HashMap::from([(json.clone(), b"{}".to_vec())]));
+            assert_eq!(copy.total_bytes(), 2);
+            store.clear();
+            assert!(shared
+                .list(ListOptions::default())
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap()
+                .is_empty());
+            assert!(copy.exists(&json).await.unwrap());
+        });
     }
 }
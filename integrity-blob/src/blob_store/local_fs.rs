@@ -1,14 +1,23 @@
 use std::{fs, path::PathBuf};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use cid::Cid;
+use futures_util::stream::{self, BoxStream, StreamExt};
 use log::{debug, trace};
 
-use crate::blob_store::{calc_and_validate_cid, BlobStore};
+use crate::blob_store::{
+    calc_and_validate_cid,
+    chunks::{blob_stream_from_file, BlobStream},
+    filter_listing,
+    temp_file::TempFile,
+    BlobStore, ListOptions,
+};
 
 /// Local filesystem blob storage
 ///
-/// Stores blobs as files in a directory, with CIDs as filenames.
+/// Stores blobs as files in a directory, with CIDs as filenames. Blobs are written to a
+/// temporary file first and renamed into place, so a blob is never partially visible.
 #[derive(Clone)]
 pub struct LocalFs {
     path: PathBuf,
@@ -61,9 +70,199 @@ impl BlobStore for LocalFs {
         if path.exists() {
             debug!("blob with cid {cid} already exists.");
         } else {
-            fs::write(path, &blob)?;
+            let mut file = TempFile::create_in(&self.path)?;
+            file.write_all(&blob).await?;
+            file.persist(&path)?;
         }
 
         Ok(cid)
     }
+
+    async fn get_stream(&self, cid: &str) -> Result<Option<BlobStream>> {
+        trace!("get stream {cid}.");
+
+        let path = self.path.join(cid);
+        if path.exists() {
+            Ok(Some(blob_stream_from_file(path)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn put_stream(
+        &self,
+        chunks: BlobStream,
+        multicodec_code: u64,
+        cid: Option<&str>,
+    ) -> Result<String> {
+        let mut file = TempFile::create_in(&self.path)?;
+        let cid = file.write_stream(chunks, multicodec_code, cid).await?;
+
+        trace!("put stream {cid}.");
+
+        let path = self.path.join(&cid);
+        if path.exists() {
+            debug!("blob with cid {cid} already exists.");
+        } else {
+            file.persist(&path)?;
+        }
+
+        Ok(cid)
+    }
+
+    /// Deletes a blob. Names that aren't canonical CIDs are rejected, so paths such as
+    /// `../file` can't reach outside the store directory.
+    async fn delete(&self, cid: &str) -> Result<()> {
+        trace!("delete {cid}.");
+
+        if Cid::try_from(cid).map(|parsed| parsed.to_string()).ok() != Some(cid.to_owned()) {
+            return Err(anyhow!("Refusing to delete '{cid}', it isn't a CID."));
+        }
+
+        match fs::remove_file(self.path.join(cid)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>> {
+        trace!("list {options:?}.");
+
+        let names = || -> Result<Vec<String>> {
+            let mut names = vec![];
+            for entry in fs::read_dir(&self.path)? {
+                let entry = entry?;
+                if entry.file_type()?.is_file() {
+                    names.extend(entry.file_name().to_str().map(str::to_owned));
+                }
+            }
+            names.sort();
+            Ok(names)
+        };
+
+        match names() {
+            Ok(names) => filter_listing(stream::iter(names.into_iter().map(Ok)), options),
+            Err(e) => stream::once(async { Err(e) }).boxed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn list_filters_and_paginates() {
+        let dir = std::env::temp_dir().join(format!(
+            "integrity-blob-local-fs-list-{}",
+            std::process::id()
+        ));
+        let mut store = LocalFs::new(dir.clone());
+        store.init().await.unwrap();
+
+        let mut raw = vec![];
+        for blob in ["one", "two", "three"] {
+            raw.push(store.put(blob.into(), 0x55, None).await.unwrap());
+        }
+        raw.sort();
+        let json = store.put(b"{}".to_vec(), 0x0129, None).await.unwrap();
+        fs::write(dir.join("not-a-cid"), b"scratch").unwrap();
+
+        let list = |options| store.list(options).try_collect::<Vec<_>>();
+
+        let mut all = [raw.clone(), vec![json.clone()]].concat();
+        all.sort();
+        assert_eq!(list(ListOptions::default()).await.unwrap(), all);
+
+        let raw_only = ListOptions {
+            multicodec_code: Some(0x55),
+            ..Default::default()
+        };
+        assert_eq!(list(raw_only.clone()).await.unwrap(), raw);
+
+        let first_page = list(ListOptions {
+            limit: Some(2),
+            ..raw_only.clone()
+        })
+        .await
+        .unwrap();
+        let second_page = list(ListOptions {
+            start_after: first_page.last().cloned(),
+            ..raw_only
+        })
+        .await
+        .unwrap();
+        assert_eq!([first_page, second_page].concat(), raw);
+
+        let prefix = ListOptions {
+            prefix: Some(json[..12].to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(list(prefix.clone()).await.unwrap(), vec![json.clone()]);
+
+        store.delete(&json).await.unwrap();
+        store.delete(&json).await.unwrap();
+        assert!(!store.exists(&json).await.unwrap());
+        assert!(list(prefix).await.unwrap().is_empty());
+
+        // only blobs inside the store can be deleted
+        let outside = dir.with_extension("outside");
+        fs::write(&outside, b"keep").unwrap();
+        let name = outside.file_name().unwrap().to_str().unwrap();
+        for path in [format!("../{name}"), outside.display().to_string()] {
+            assert!(store.delete(&path).await.is_err(), "{path}");
+        }
+        assert!(store.delete("not-a-cid").await.is_err());
+        assert!(outside.exists() && dir.join("not-a-cid").exists());
+        fs::remove_file(&outside).unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn streams_blobs_in_and_out() {
+        let dir = std::env::temp_dir().join(format!(
+            "integrity-blob-local-fs-stream-{}",
+            std::process::id()
+        ));
+        let mut store = LocalFs::new(dir.clone());
+        store.init().await.unwrap();
+
+        let chunks = || stream::iter([b"Hello ".to_vec(), b"World".to_vec()].map(Ok)).boxed();
+        let expected = "bafkr4icb7a4uceploe5cefs4i3eqvohq7wjztsjafd6w2kejiszd75n7oy";
+
+        assert!(store
+            .put_stream(chunks(), 0x55, Some("bafkr4"))
+            .await
+            .is_err());
+        assert_eq!(store.list(ListOptions::default()).count().await, 0);
+
+        let cid = store.put_stream(chunks(), 0x55, None).await.unwrap();
+        assert_eq!(cid, expected);
+        assert_eq!(
+            store
+                .put(b"Hello World".to_vec(), 0x55, None)
+                .await
+                .unwrap(),
+            expected
+        );
+
+        let blob = store
+            .get_stream(&cid)
+            .await
+            .unwrap()
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+        assert_eq!(blob, b"Hello World");
+        assert!(store.get_stream("missing").await.unwrap().is_none());
+
+        // only the blob is left, no temporary files
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
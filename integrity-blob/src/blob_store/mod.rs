@@ -1,52 +1,54 @@
 use std::collections::HashSet;
 
-#[cfg(any(
-    feature = "blob-local",
-    all(not(target_arch = "wasm32"), feature = "blob-azure"),
-    all(not(target_arch = "wasm32"), feature = "blob-gcs"),
-    all(not(target_arch = "wasm32"), feature = "blob-s3"),
-))]
-use anyhow::anyhow;
 use anyhow::Result;
 use async_trait::async_trait;
-#[cfg(any(
-    feature = "blob-local",
-    all(not(target_arch = "wasm32"), feature = "blob-azure"),
-    all(not(target_arch = "wasm32"), feature = "blob-gcs"),
-    all(not(target_arch = "wasm32"), feature = "blob-s3"),
-))]
-use cid::{multihash::MultihashGeneric, Cid};
-use futures_util::{stream, StreamExt, TryStreamExt};
+use futures_util::{future, stream, stream::BoxStream, StreamExt, TryStreamExt};
 
 #[cfg(all(not(target_arch = "wasm32"), feature = "blob-azure"))]
 pub mod azure_blob;
+/// Caching blob store composed of a cache store and a remote store
+pub mod cached;
+/// Chunked blob streams and incremental CIDs
+pub mod chunks;
 #[cfg(all(not(target_arch = "wasm32"), feature = "blob-gcs"))]
 pub mod gcs;
 #[cfg(feature = "blob-memory")]
 pub mod in_memory;
 #[cfg(feature = "blob-local")]
 pub mod local_fs;
+/// Blob store replicating to several stores
+pub mod replicated;
 #[cfg(all(not(target_arch = "wasm32"), feature = "blob-s3"))]
 pub mod s3;
+/// Copying blobs between stores
+pub mod sync;
+#[cfg(any(feature = "blob-local", not(target_arch = "wasm32")))]
+mod temp_file;
+/// Verify-on-read blob store wrapper
+pub mod verifying;
 
 #[cfg(all(not(target_arch = "wasm32"), feature = "blob-azure"))]
 pub use azure_blob::AzureBlob;
+/// Re-exported caching types for convenience.
+pub use cached::{CacheOptions, CacheStats, CachedBlobStore, WritePolicy};
+/// Re-exported streaming types for convenience.
+pub use chunks::{blob_stream_from_file, BlobStream, CidHasher};
 #[cfg(all(not(target_arch = "wasm32"), feature = "blob-gcs"))]
 pub use gcs::GCS;
 #[cfg(feature = "blob-memory")]
 pub use in_memory::InMemoryStore;
 #[cfg(feature = "blob-local")]
 pub use local_fs::LocalFs;
+/// Re-exported replication types for convenience.
+pub use replicated::{
+    RepairFailure, RepairReport, Replica, ReplicatedBlobStore, ReplicationOptions,
+};
 #[cfg(all(not(target_arch = "wasm32"), feature = "blob-s3"))]
 pub use s3::S3;
-
-#[cfg(any(
-    feature = "blob-local",
-    all(not(target_arch = "wasm32"), feature = "blob-azure"),
-    all(not(target_arch = "wasm32"), feature = "blob-gcs"),
-    all(not(target_arch = "wasm32"), feature = "blob-s3"),
-))]
-type Multihash = MultihashGeneric<64>;
+/// Re-exported sync types for convenience.
+pub use sync::{sync, SyncFailure, SyncOptions, SyncReport};
+/// Re-exported verifying wrapper for convenience.
+pub use verifying::{verify_blob, IntegrityError, VerifyingBlobStore};
 
 const DEFAULT_BATCH_CONCURRENCY_LIMIT: usize = 16;
 
@@ -74,6 +76,42 @@ pub struct BlobExistsResult {
     pub exists: bool,
 }
 
+/// Filters and pagination for [`BlobStore::list`].
+///
+/// CIDs are listed in lexicographic order, so a listing can be resumed by passing the
+/// last CID of the previous page as `start_after`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListOptions {
+    /// Only list CIDs starting with this prefix
+    pub prefix: Option<String>,
+    /// Only list CIDs with this multicodec
+    pub multicodec_code: Option<u64>,
+    /// Only list CIDs sorting after this one
+    pub start_after: Option<String>,
+    /// Maximum number of CIDs to list
+    pub limit: Option<usize>,
+}
+
+impl ListOptions {
+    /// Returns whether a stored name is a CID selected by these options.
+    pub fn matches(&self, name: &str) -> bool {
+        let Ok(cid) = cid::Cid::try_from(name) else {
+            return false;
+        };
+
+        self.prefix
+            .as_deref()
+            .is_none_or(|prefix| name.starts_with(prefix))
+            && self
+                .start_after
+                .as_deref()
+                .is_none_or(|start_after| name > start_after)
+            && self
+                .multicodec_code
+                .is_none_or(|multicodec_code| cid.codec() == multicodec_code)
+    }
+}
+
 #[async_trait]
 pub trait BlobStore {
     async fn init(&mut self) -> Result<()>;
@@ -81,6 +119,37 @@ pub trait BlobStore {
     async fn get(&self, cid: &str) -> Result<Option<Vec<u8>>>;
     async fn put(&self, blob: Vec<u8>, multicodec_code: u64, cid: Option<&str>) -> Result<String>;
 
+    /// Streams a blob in chunks, or `None` if it isn't stored.
+    ///
+    /// The default reads the whole blob with `get`; backends override it to avoid
+    /// holding large blobs in memory.
+    async fn get_stream(&self, cid: &str) -> Result<Option<BlobStream>> {
+        Ok(self
+            .get(cid)
+            .await?
+            .map(|blob| stream::once(future::ready(Ok(blob))).boxed()))
+    }
+
+    /// Stores a blob read as a stream of chunks, returning its CID.
+    ///
+    /// The CID is computed incrementally as chunks arrive. If `cid` is given and doesn't
+    /// match, nothing is stored. The default collects the chunks and calls `put`.
+    async fn put_stream(
+        &self,
+        chunks: BlobStream,
+        multicodec_code: u64,
+        cid: Option<&str>,
+    ) -> Result<String> {
+        let blob = chunks.try_concat().await?;
+        self.put(blob, multicodec_code, cid).await
+    }
+
+    /// Deletes a blob. Deleting a blob that isn't stored succeeds.
+    async fn delete(&self, cid: &str) -> Result<()>;
+
+    /// Lists the CIDs of stored blobs in lexicographic order.
+    fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>>;
+
     fn batch_concurrency_limit(&self) -> usize {
         DEFAULT_BATCH_CONCURRENCY_LIMIT
     }
@@ -163,47 +232,58 @@ pub trait BlobStore {
         results.sort_by_key(|(index, _)| *index);
         Ok(results.into_iter().map(|(_, result)| result).collect())
     }
+
+    async fn delete_many(&self, cids: Vec<String>, concurrency_limit: Option<usize>) -> Result<()> {
+        let concurrency_limit = concurrency_limit
+            .unwrap_or_else(|| self.batch_concurrency_limit())
+            .max(1);
+        stream::iter(cids)
+            .map(|cid| async move { self.delete(&cid).await })
+            .buffer_unordered(concurrency_limit)
+            .try_collect::<()>()
+            .await
+    }
 }
 
+/// Applies `options` to a lexicographically ordered listing of stored names.
+///
+/// Backends may already narrow the listing by prefix or start position; names that aren't
+/// CIDs are skipped.
 #[cfg(any(
+    test,
     feature = "blob-local",
+    feature = "blob-memory",
     all(not(target_arch = "wasm32"), feature = "blob-azure"),
     all(not(target_arch = "wasm32"), feature = "blob-gcs"),
     all(not(target_arch = "wasm32"), feature = "blob-s3"),
 ))]
-pub(crate) fn calc_and_validate_cid(
-    blob: &[u8],
-    multicodec_code: u64,
-    expected_cid: Option<&str>,
-) -> Result<String> {
-    let computed_cid = blake3_cid(multicodec_code, blob)?;
-
-    if let Some(cid) = expected_cid {
-        if cid != computed_cid {
-            return Err(anyhow!(
-                "Computed CID '{computed_cid}' doesn't match provided CID '{cid}'.",
-            ));
-        }
-    }
-
-    Ok(computed_cid)
+pub(crate) fn filter_listing<'a>(
+    names: impl futures_util::Stream<Item = Result<String>> + Send + 'a,
+    options: ListOptions,
+) -> BoxStream<'a, Result<String>> {
+    let limit = options.limit.unwrap_or(usize::MAX);
+
+    names
+        .try_filter(move |name| future::ready(options.matches(name)))
+        .take(limit)
+        .boxed()
 }
 
 #[cfg(any(
     feature = "blob-local",
+    feature = "blob-memory",
     all(not(target_arch = "wasm32"), feature = "blob-azure"),
     all(not(target_arch = "wasm32"), feature = "blob-gcs"),
     all(not(target_arch = "wasm32"), feature = "blob-s3"),
 ))]
-fn blake3_cid(codec: u64, data: &[u8]) -> Result<String> {
-    #[cfg(not(target_arch = "wasm32"))]
-    let hash = iroh_blake3::hash(data);
-
-    #[cfg(target_arch = "wasm32")]
-    let hash = blake3::hash(data);
-
-    let multihash = Multihash::wrap(0x1e, hash.as_bytes())?;
-    Ok(Cid::new_v1(codec, multihash).to_string())
+pub(crate) fn calc_and_validate_cid(
+    blob: &[u8],
+    multicodec_code: u64,
+    expected_cid: Option<&str>,
+) -> Result<String> {
+    let mut hasher = CidHasher::new(multicodec_code);
+    hasher.update(blob);
+    hasher.finalize_and_validate(expected_cid)
 }
 
 #[cfg(test)]
@@ -244,6 +324,23 @@ mod tests {
             blobs.insert(cid.clone(), blob);
             Ok(cid)
         }
+
+        async fn delete(&self, cid: &str) -> Result<()> {
+            self.blobs.lock().unwrap().remove(cid);
+            Ok(())
+        }
+
+        fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>> {
+            let mut cids = self
+                .blobs
+                .lock()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>();
+            cids.sort();
+            filter_listing(stream::iter(cids.into_iter().map(Ok)), options)
+        }
     }
 
     #[test]
@@ -341,4 +438,29 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn default_delete_many_deletes_every_cid() {
+        futures_executor::block_on(async {
+            let store = TestBlobStore::default();
+            for cid in ["cid-one", "cid-two", "cid-three"] {
+                store.put(cid.into(), 0x55, Some(cid)).await.unwrap();
+            }
+
+            store
+                .delete_many(
+                    vec![
+                        "cid-one".to_owned(),
+                        "missing".to_owned(),
+                        "cid-three".to_owned(),
+                    ],
+                    Some(1),
+                )
+                .await
+                .unwrap();
+            assert!(!store.exists("cid-one").await.unwrap());
+            assert!(store.exists("cid-two").await.unwrap());
+            assert!(!store.exists("cid-three").await.unwrap());
+        });
+    }
 }
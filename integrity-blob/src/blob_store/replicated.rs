@@ -0,0 +1,522 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::{
+    future,
+    stream::{self, BoxStream, StreamExt, TryStreamExt},
+};
+use log::{debug, warn};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::blob_store::{chunks::blob_stream_from_file, temp_file::spool};
+use crate::blob_store::{BlobStore, BlobStream, ListOptions};
+
+/// A replica of a [`ReplicatedBlobStore`].
+pub type Replica = Box<dyn BlobStore + Send + Sync>;
+
+/// Options for [`ReplicatedBlobStore`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReplicationOptions {
+    /// Replicas that must accept a write for it to succeed, all of them if `None`
+    pub write_quorum: Option<usize>,
+}
+
+/// Outcome of [`ReplicatedBlobStore::repair`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RepairReport {
+    /// CIDs copied to each replica, by replica index, sorted
+    pub copied: Vec<Vec<String>>,
+    /// CIDs missing from some replica that no replica has, sorted
+    pub unrecoverable: Vec<String>,
+    /// Blobs that other replicas have but couldn't be copied
+    pub failed: Vec<RepairFailure>,
+}
+
+/// A blob that [`ReplicatedBlobStore::repair`] couldn't copy to a replica.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RepairFailure {
+    /// Index of the replica missing the blob
+    pub replica: usize,
+    /// CID of the blob
+    pub cid: String,
+    /// Why the copy failed
+    pub error: String,
+}
+
+/// Blob store that keeps a copy of every blob in several stores.
+///
+/// Writes go to all replicas concurrently and succeed once `write_quorum` of them accept the
+/// blob; replicas that failed can be brought back in line with [`ReplicatedBlobStore::repair`].
+/// Reads try the replicas in order and fall back to the next one when a blob is missing or a
+/// replica fails, so the fastest or cheapest store should come first. Streamed writes are
+/// spooled to a temporary file once and streamed from it to every replica.
+pub struct ReplicatedBlobStore {
+    replicas: Vec<Replica>,
+    write_quorum: usize,
+}
+
+impl ReplicatedBlobStore {
+    /// Creates a replicated blob store
+    ///
+    /// # Arguments
+    /// * `replicas` - Stores to replicate to, in read order
+    /// * `options` - Write quorum
+    ///
+    /// # Returns
+    /// * `Result<Self>` - Error if there are no replicas or the quorum can't be met
+    pub fn new(replicas: Vec<Replica>, options: ReplicationOptions) -> Result<Self> {
+        let write_quorum = options.write_quorum.unwrap_or(replicas.len());
+        if replicas.is_empty() {
+            return Err(anyhow!(
+                "A replicated blob store needs at least one replica."
+            ));
+        }
+        if write_quorum == 0 || write_quorum > replicas.len() {
+            return Err(anyhow!(
+                "Write quorum {write_quorum} must be between 1 and the {} replicas.",
+                replicas.len()
+            ));
+        }
+
+        Ok(Self {
+            replicas,
+            write_quorum,
+        })
+    }
+
+    /// Returns the replicas in read order.
+    pub fn replicas(&self) -> &[Replica] {
+        &self.replicas
+    }
+
+    /// Copies blobs missing from any replica from the replicas that have them.
+    ///
+    /// Every replica is listed and its missing blobs are found with `exists_many`. Each one is
+    /// fetched from the other replicas in read order and written to the replica, which verifies
+    /// it against its CID; a copy that is corrupt or can't be written is skipped for the next
+    /// replica's. Blobs that can't be copied are recorded and the repair carries on.
+    ///
+    /// # Arguments
+    /// * `concurrency_limit` - Maximum concurrent copies per replica, defaults to each store's
+    ///   batch limit
+    ///
+    /// # Returns
+    /// * `Result<RepairReport>` - The blobs copied to each replica and those that couldn't be,
+    ///   or error if a replica can't be listed or queried
+    pub async fn repair(&self, concurrency_limit: Option<usize>) -> Result<RepairReport> {
+        let mut all = BTreeSet::new();
+        for replica in &self.replicas {
+            let cids = replica
+                .list(ListOptions::default())
+                .try_collect::<Vec<_>>()
+                .await?;
+            all.extend(cids);
+        }
+        let all = all.into_iter().collect::<Vec<_>>();
+
+        let mut report = RepairReport::default();
+        let mut unrecoverable = BTreeSet::new();
+        for (index, replica) in self.replicas.iter().enumerate() {
+            let missing = replica
+                .exists_many(all.clone(), concurrency_limit)
+                .await?
+                .into_iter()
+                .filter(|result| !result.exists)
+                .map(|result| result.cid)
+                .collect::<Vec<_>>();
+
+            let results = stream::iter(missing)
+                .map(|cid| async move {
+                    let result = self.copy_blob(index, &cid).await;
+                    (cid, result)
+                })
+                .buffer_unordered(
+                    concurrency_limit
+                        .unwrap_or_else(|| replica.batch_concurrency_limit())
+                        .max(1),
+                )
+                .collect::<Vec<_>>()
+                .await;
+
+            let mut copied = vec![];
+            for (cid, result) in results {
+                match result {
+                    Ok(true) => copied.push(cid),
+                    Ok(false) => {
+                        unrecoverable.insert(cid);
+                    }
+                    Err(e) => {
+                        warn!("Failed to repair blob '{cid}' in replica {index}: {e}");
+                        report.failed.push(RepairFailure {
+                            replica: index,
+                            cid,
+                            error: e.to_string(),
+                        });
+                    }
+                }
+            }
+            copied.sort();
+
+            if !copied.is_empty() {
+                debug!("Copied {} blobs to replica {index}.", copied.len());
+            }
+            report.copied.push(copied);
+        }
+        report.unrecoverable = unrecoverable.into_iter().collect();
+
+        Ok(report)
+    }
+
+    /// Copies one blob to replica `target` from the first other replica whose copy it accepts.
+    ///
+    /// # Returns
+    /// * `Result<bool>` - Whether the blob was copied, false if no replica has it, or the last
+    ///   error if every copy failed
+    async fn copy_blob(&self, target: usize, cid: &str) -> Result<bool> {
+        let multicodec_code = cid::Cid::try_from(cid)?.codec();
+
+        let mut error = None;
+        for (index, replica) in self.replicas.iter().enumerate() {
+            if index == target {
+                continue;
+            }
+
+            let result = match replica.get(cid).await {
+                Ok(Some(blob)) => {
+                    self.replicas[target]
+                        .put(blob, multicodec_code, Some(cid))
+                        .await
+                }
+                Ok(None) => continue,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(_) => return Ok(true),
+                Err(e) => {
+                    warn!("Replica {index} couldn't provide '{cid}' for repair: {e}");
+                    error = Some(e);
+                }
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(false),
+        }
+    }
+
+    /// Checks that enough replicas accepted a write and agree on its CID.
+    fn quorum_cid(&self, results: Vec<Result<String>>) -> Result<String> {
+        let mut cids = BTreeSet::new();
+        let mut errors = vec![];
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(cid) => {
+                    cids.insert(cid);
+                }
+                Err(e) => {
+                    warn!("Replica {index} failed to put blob: {e}");
+                    errors.push(format!("replica {index}: {e}"));
+                }
+            }
+        }
+
+        let written = self.replicas.len() - errors.len();
+        if written < self.write_quorum {
+            return Err(anyhow!(
+                "Blob was written to {written} of {} replicas, {} required. {}",
+                self.replicas.len(),
+                self.write_quorum,
+                errors.join("; ")
+            ));
+        }
+        if cids.len() > 1 {
+            return Err(anyhow!("Replicas computed different CIDs: {cids:?}."));
+        }
+
+        cids.pop_first()
+            .ok_or_else(|| anyhow!("No replica returned a CID."))
+    }
+}
+
+#[async_trait]
+impl BlobStore for ReplicatedBlobStore {
+    async fn init(&mut self) -> Result<()> {
+        for replica in &mut self.replicas {
+            replica.init().await?;
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, cid: &str) -> Result<bool> {
+        let mut error = None;
+        for (index, replica) in self.replicas.iter().enumerate() {
+            match replica.exists(cid).await {
+                Ok(true) => return Ok(true),
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("Replica {index} failed to check '{cid}': {e}");
+                    error = Some(e);
+                }
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(false),
+        }
+    }
+
+    async fn get(&self, cid: &str) -> Result<Option<Vec<u8>>> {
+        let mut error = None;
+        for (index, replica) in self.replicas.iter().enumerate() {
+            match replica.get(cid).await {
+                Ok(Some(blob)) => return Ok(Some(blob)),
+                Ok(None) => debug!("Blob '{cid}' not found in replica {index}."),
+                Err(e) => {
+                    warn!("Replica {index} failed to get '{cid}': {e}");
+                    error = Some(e);
+                }
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    }
+
+    async fn put(&self, blob: Vec<u8>, multicodec_code: u64, cid: Option<&str>) -> Result<String> {
+        let results = future::join_all(
+            self.replicas
+                .iter()
+                .map(|replica| replica.put(blob.clone(), multicodec_code, cid)),
+        )
+        .await;
+
+        self.quorum_cid(results)
+    }
+
+    /// Streams a blob from the first replica that has it.
+    ///
+    /// Replicas are tried in read order as with `get`. Only opening the stream falls back; an
+    /// error while reading it is returned to the caller.
+    async fn get_stream(&self, cid: &str) -> Result<Option<BlobStream>> {
+        let mut error = None;
+        for (index, replica) in self.replicas.iter().enumerate() {
+            match replica.get_stream(cid).await {
+                Ok(Some(chunks)) => return Ok(Some(chunks)),
+                Ok(None) => debug!("Blob '{cid}' not found in replica {index}."),
+                Err(e) => {
+                    warn!("Replica {index} failed to get '{cid}': {e}");
+                    error = Some(e);
+                }
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    }
+
+    /// Spools the blob to a temporary file once and streams it to every replica.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn put_stream(
+        &self,
+        chunks: BlobStream,
+        multicodec_code: u64,
+        cid: Option<&str>,
+    ) -> Result<String> {
+        let (cid, file) = spool(chunks, multicodec_code, cid).await?;
+        let results = future::join_all(self.replicas.iter().map(|replica| async {
+            let chunks = blob_stream_from_file(file.path())?;
+            replica
+                .put_stream(chunks, multicodec_code, Some(&cid))
+                .await
+        }))
+        .await;
+
+        self.quorum_cid(results)
+    }
+
+    async fn delete(&self, cid: &str) -> Result<()> {
+        future::try_join_all(self.replicas.iter().map(|replica| replica.delete(cid))).await?;
+        Ok(())
+    }
+
+    /// Lists the CIDs stored in any replica.
+    fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>> {
+        let limit = options.limit.unwrap_or(usize::MAX);
+        let listings = self
+            .replicas
+            .iter()
+            .map(|replica| replica.list(options.clone()).try_collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        stream::once(async move {
+            let mut cids = BTreeSet::new();
+            for listing in future::try_join_all(listings).await? {
+                cids.extend(listing);
+            }
+            Ok::<_, anyhow::Error>(stream::iter(cids.into_iter().take(limit).map(Ok)))
+        })
+        .try_flatten()
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "blob-local")]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+    use crate::blob_store::LocalFs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "integrity-blob-replicated-{name}-{}",
+            std::process::id()
+        ))
+    }
+
+    #[tokio::test]
+    async fn writes_with_quorum_and_repairs_replicas() {
+        let dirs = ["a", "b", "c"].map(temp_dir);
+        let replicas = dirs
+            .iter()
+            .map(|dir| Box::new(LocalFs::new(dir.clone())) as Replica)
+            .collect();
+        let options = ReplicationOptions {
+            write_quorum: Some(2),
+        };
+        let mut store = ReplicatedBlobStore::new(replicas, options).unwrap();
+        store.init().await.unwrap();
+
+        let first = store.put(b"first".to_vec(), 0x55, None).await.unwrap();
+        for dir in &dirs {
+            assert!(dir.join(&first).exists());
+        }
+
+        // reads fall back to the next replica
+        fs::remove_file(dirs[0].join(&first)).unwrap();
+        assert_eq!(store.get(&first).await.unwrap().unwrap(), b"first");
+
+        // one broken replica still meets the quorum
+        fs::remove_dir_all(&dirs[2]).unwrap();
+        fs::write(&dirs[2], b"not a directory").unwrap();
+        let second = store.put(b"second".to_vec(), 0x55, None).await.unwrap();
+        fs::remove_file(&dirs[2]).unwrap();
+        fs::create_dir_all(&dirs[2]).unwrap();
+        assert_eq!(
+            store.list(ListOptions::default()).count().await,
+            2,
+            "listing is the union of the replicas"
+        );
+
+        let report = store.repair(None).await.unwrap();
+        assert_eq!(report.copied[0], vec![first.clone()]);
+        assert!(report.copied[1].is_empty());
+        let mut copied = report.copied[2].clone();
+        copied.sort();
+        let mut expected = vec![first.clone(), second.clone()];
+        expected.sort();
+        assert_eq!(copied, expected);
+        assert!(report.unrecoverable.is_empty());
+        for dir in &dirs {
+            assert!(dir.join(&first).exists() && dir.join(&second).exists());
+        }
+
+        // two broken replicas don't
+        for dir in &dirs[1..] {
+            fs::remove_dir_all(dir).unwrap();
+            fs::write(dir, b"not a directory").unwrap();
+        }
+        assert!(store.put(b"third".to_vec(), 0x55, None).await.is_err());
+
+        fs::remove_dir_all(&dirs[0]).unwrap();
+        for dir in &dirs[1..] {
+            fs::remove_file(dir).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_to_and_from_replicas() {
+        let dirs = ["stream-a", "stream-b"].map(temp_dir);
+        let replicas = dirs
+            .iter()
+            .map(|dir| Box::new(LocalFs::new(dir.clone())) as Replica)
+            .collect();
+        let mut store = ReplicatedBlobStore::new(replicas, Default::default()).unwrap();
+        store.init().await.unwrap();
+
+        let chunks = stream::iter([Ok(b"Hello ".to_vec()), Ok(b"World".to_vec())]).boxed();
+        let cid = store.put_stream(chunks, 0x55, None).await.unwrap();
+        assert_eq!(
+            cid,
+            "bafkr4icb7a4uceploe5cefs4i3eqvohq7wjztsjafd6w2kejiszd75n7oy"
+        );
+        for dir in &dirs {
+            assert_eq!(fs::read(dir.join(&cid)).unwrap(), b"Hello World");
+        }
+
+        // reads fall back to the next replica
+        fs::remove_file(dirs[0].join(&cid)).unwrap();
+        let chunks = store.get_stream(&cid).await.unwrap().unwrap();
+        assert_eq!(chunks.try_concat().await.unwrap(), b"Hello World");
+
+        // a mismatched CID is rejected before any replica is written
+        let chunks = stream::iter([Ok(b"other".to_vec())]).boxed();
+        assert!(store.put_stream(chunks, 0x55, Some(&cid)).await.is_err());
+        assert!(store.get_stream(&cid).await.unwrap().is_some());
+        assert!(!dirs[0].join(&cid).exists());
+
+        for dir in &dirs {
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn repairs_past_corrupt_copies() {
+        let dirs = ["corrupt-a", "corrupt-b", "corrupt-c"].map(temp_dir);
+        let replicas = dirs
+            .iter()
+            .map(|dir| Box::new(LocalFs::new(dir.clone())) as Replica)
+            .collect();
+        let mut store = ReplicatedBlobStore::new(replicas, Default::default()).unwrap();
+        store.init().await.unwrap();
+
+        // the first replica's copy is corrupt, the second one's is fine
+        let shared = store.put(b"shared".to_vec(), 0x55, None).await.unwrap();
+        fs::write(dirs[0].join(&shared), b"corrupt").unwrap();
+        fs::remove_file(dirs[2].join(&shared)).unwrap();
+        // the only copy left is corrupt
+        let lost = store.put(b"lost".to_vec(), 0x55, None).await.unwrap();
+        fs::write(dirs[0].join(&lost), b"corrupt").unwrap();
+        for dir in &dirs[1..] {
+            fs::remove_file(dir.join(&lost)).unwrap();
+        }
+        let other = store.put(b"other".to_vec(), 0x55, None).await.unwrap();
+        fs::remove_file(dirs[2].join(&other)).unwrap();
+
+        let report = store.repair(None).await.unwrap();
+        let mut copied = vec![shared.clone(), other.clone()];
+        copied.sort();
+        assert_eq!(report.copied[2], copied);
+        assert!(report.unrecoverable.is_empty());
+        let mut failed = report
+            .failed
+            .iter()
+            .map(|failure| (failure.replica, failure.cid.clone()))
+            .collect::<Vec<_>>();
+        failed.sort();
+        assert_eq!(failed, vec![(1, lost.clone()), (2, lost.clone())]);
+        assert_eq!(fs::read(dirs[2].join(&shared)).unwrap(), b"shared");
+
+        for dir in &dirs {
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
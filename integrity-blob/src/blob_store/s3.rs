@@ -1,14 +1,29 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use aws_config::meta::region::RegionProviderChain;
-use aws_sdk_s3::{config::Region, Client};
-use log::{debug, trace};
+use aws_sdk_s3::{
+    config::Region,
+    types::{CompletedMultipartUpload, CompletedPart},
+    Client,
+};
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use log::{debug, trace, warn};
 
-use crate::blob_store::{calc_and_validate_cid, BlobStore};
+use crate::blob_store::{
+    calc_and_validate_cid,
+    chunks::{blob_stream_from_file, rechunk, BlobStream, CidHasher},
+    filter_listing,
+    temp_file::spool,
+    BlobStore, ListOptions,
+};
+
+/// Size of the parts of multipart uploads, above the S3 minimum of 5 MiB.
+const PART_SIZE: usize = 8 * 1024 * 1024;
 
 /// AWS S3 blob storage backend
 ///
-/// Stores blobs in an S3 bucket under a specified folder prefix.
+/// Stores blobs in an S3 bucket under a specified folder prefix. Streamed blobs larger
+/// than one part are written with multipart uploads.
 pub struct S3 {
     region: String,
     bucket: String,
@@ -36,6 +51,47 @@ impl S3 {
             client: None,
         }
     }
+
+    /// Uploads `parts` to a started multipart upload, hashing them as they are sent.
+    ///
+    /// # Returns
+    /// * `Result<Vec<CompletedPart>>` - The uploaded parts, or error if the parts don't
+    ///   hash to `cid`
+    async fn upload_parts(
+        &self,
+        client: &Client,
+        key: &str,
+        upload_id: &str,
+        mut parts: BlobStream,
+        multicodec_code: u64,
+        cid: &str,
+    ) -> Result<Vec<CompletedPart>> {
+        let mut hasher = CidHasher::new(multicodec_code);
+        let mut completed = vec![];
+        while let Some(part) = parts.try_next().await? {
+            hasher.update(&part);
+            let part_number = i32::try_from(completed.len() + 1)?;
+            let uploaded = client
+                .upload_part()
+                .bucket(&self.bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(part.into())
+                .send()
+                .await?;
+            trace!("Uploaded part {part_number} of {key}");
+            completed.push(
+                CompletedPart::builder()
+                    .set_e_tag(uploaded.e_tag)
+                    .part_number(part_number)
+                    .build(),
+            );
+        }
+        hasher.finalize_and_validate(Some(cid))?;
+
+        Ok(completed)
+    }
 }
 
 #[async_trait]
@@ -105,9 +161,13 @@ impl BlobStore for S3 {
 
                 Ok(Some(bytes_vec))
             }
-            Err(e) => {
-                println!("Error: {:?}", e);
-                Ok(None)
+            Err(err) => {
+                let err = err.into_service_error();
+                if err.is_no_such_key() {
+                    return Ok(None);
+                }
+
+                Err(err.into())
             }
         }
     }
@@ -135,6 +195,190 @@ impl BlobStore for S3 {
         trace!("Upload complete");
         Ok(cid)
     }
+
+    /// Stream a blob from the store
+    async fn get_stream(&self, cid: &str) -> Result<Option<BlobStream>> {
+        let client = self
+            .client
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Client not initialized"))?;
+
+        let object = client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(format!("{folder:}{cid:}", folder = self.folder))
+            .send()
+            .await;
+
+        match object {
+            Ok(object) => Ok(Some(
+                stream::try_unfold(object.body, |mut body| async move {
+                    let chunk = body.try_next().await?;
+                    Ok(chunk.map(|bytes| (bytes.to_vec(), body)))
+                })
+                .boxed(),
+            )),
+            Err(err) => {
+                let err = err.into_service_error();
+                if err.is_no_such_key() {
+                    return Ok(None);
+                }
+
+                Err(err.into())
+            }
+        }
+    }
+
+    /// Put a streamed blob into the store
+    ///
+    /// With a known CID the parts are uploaded as they arrive and the upload is only
+    /// completed if they hash to it. Otherwise the blob is spooled to a temporary file
+    /// first to compute the key.
+    async fn put_stream(
+        &self,
+        chunks: BlobStream,
+        multicodec_code: u64,
+        cid: Option<&str>,
+    ) -> Result<String> {
+        let client = self
+            .client
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Client not initialized"))?;
+
+        let (cid, chunks, _spooled) = match cid {
+            Some(cid) => (cid.to_owned(), chunks, None),
+            None => {
+                let (cid, file) = spool(chunks, multicodec_code, None).await?;
+                let chunks = blob_stream_from_file(file.path())?;
+                (cid, chunks, Some(file))
+            }
+        };
+        let key = format!("{folder:}{cid:}", folder = self.folder);
+
+        let mut parts = rechunk(chunks, PART_SIZE);
+        let first = parts.try_next().await?.unwrap_or_default();
+        let Some(second) = parts.try_next().await? else {
+            calc_and_validate_cid(&first, multicodec_code, Some(&cid))?;
+            client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(&key)
+                .body(first.into())
+                .send()
+                .await?;
+
+            trace!("Upload complete");
+            return Ok(cid);
+        };
+
+        let upload = client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await?;
+        let upload_id = upload
+            .upload_id
+            .ok_or_else(|| anyhow::anyhow!("S3 didn't return a multipart upload id"))?;
+
+        let parts = stream::iter([Ok(first), Ok(second)]).chain(parts).boxed();
+        match self
+            .upload_parts(&client, &key, &upload_id, parts, multicodec_code, &cid)
+            .await
+        {
+            Ok(parts) => {
+                client
+                    .complete_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(&key)
+                    .upload_id(&upload_id)
+                    .multipart_upload(
+                        CompletedMultipartUpload::builder()
+                            .set_parts(Some(parts))
+                            .build(),
+                    )
+                    .send()
+                    .await?;
+
+                trace!("Multipart upload complete");
+                Ok(cid)
+            }
+            Err(e) => {
+                if let Err(abort_err) = client
+                    .abort_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(&key)
+                    .upload_id(&upload_id)
+                    .send()
+                    .await
+                {
+                    warn!("Failed to abort multipart upload of {key}: {abort_err}");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Delete a blob from the store
+    async fn delete(&self, cid: &str) -> Result<()> {
+        let client = self
+            .client
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Client not initialized"))?;
+
+        // S3 reports success for keys that don't exist
+        client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(format!("{folder:}{cid:}", folder = self.folder))
+            .send()
+            .await?;
+
+        trace!("Deleted {cid}");
+        Ok(())
+    }
+
+    /// List the CIDs in the store's folder
+    fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>> {
+        let Some(client) = self.client.clone() else {
+            return stream::once(async { Err(anyhow::anyhow!("Client not initialized")) }).boxed();
+        };
+
+        let folder = self.folder.as_str();
+        let pages = client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(format!(
+                "{folder}{}",
+                options.prefix.as_deref().unwrap_or_default()
+            ))
+            .set_start_after(
+                options
+                    .start_after
+                    .as_ref()
+                    .map(|start_after| format!("{folder}{start_after}")),
+            )
+            .into_paginator()
+            .send();
+
+        let keys = stream::unfold(pages, |mut pages| async move {
+            let page = pages.next().await?;
+            Some((page, pages))
+        })
+        .map_err(anyhow::Error::from)
+        .map_ok(move |page| {
+            let keys = page
+                .contents
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(move |object| object.key?.strip_prefix(folder).map(str::to_owned))
+                .map(Ok);
+            stream::iter(keys)
+        })
+        .try_flatten();
+
+        filter_listing(keys, options)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,316 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use anyhow::{anyhow, Result};
+use cid::Cid;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use log::{debug, warn};
+
+use crate::blob_store::{BlobStore, ListOptions};
+
+/// Number of CIDs compared and copied per batch by default.
+pub const DEFAULT_SYNC_BATCH_SIZE: usize = 256;
+
+/// Options for [`sync`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SyncOptions {
+    /// Selects the source blobs to copy. Set `start_after` to a report's `resume_after` to
+    /// resume an interrupted sync or retry its failures.
+    pub list: ListOptions,
+    /// Maximum number of concurrent copies, defaults to the destination's batch limit
+    pub concurrency_limit: Option<usize>,
+    /// Number of CIDs compared and copied per batch, defaults to
+    /// [`DEFAULT_SYNC_BATCH_SIZE`]
+    pub batch_size: Option<usize>,
+}
+
+/// A blob that couldn't be copied.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyncFailure {
+    /// CID of the blob
+    pub cid: String,
+    /// Why the copy failed
+    pub error: String,
+}
+
+/// Progress of a [`sync`], reported after every batch.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SyncReport {
+    /// Source blobs listed so far
+    pub listed: u64,
+    /// Blobs already in the destination
+    pub skipped: u64,
+    /// Blobs copied to the destination
+    pub copied: u64,
+    /// Bytes copied to the destination
+    pub bytes_copied: u64,
+    /// Blobs that couldn't be copied
+    pub failed: Vec<SyncFailure>,
+    /// Last CID up to which every source blob is in the destination. It stops before the
+    /// first failed blob, so a sync resumed from it retries the failures.
+    pub resume_after: Option<String>,
+}
+
+/// Copies the blobs missing from `dst` from `src`.
+///
+/// The source is listed in CID order and processed in batches: each batch is compared with
+/// the destination using `exists_many` and the missing blobs are streamed across with bounded
+/// concurrency. The destination verifies each blob against its CID, so corrupted source
+/// blobs are reported as failures and never written. Blobs that fail are recorded and the
+/// sync carries on, but `resume_after` stays before the first of them.
+///
+/// # Arguments
+/// * `src` - Store to copy from
+/// * `dst` - Store to copy to
+/// * `options` - Source selection, concurrency and batch size
+/// * `on_progress` - Called with the report so far after every batch
+///
+/// # Returns
+/// * `Result<SyncReport>` - The final report, or error if either store can't be listed or
+///   queried
+pub async fn sync<S, D>(
+    src: &S,
+    dst: &D,
+    options: &SyncOptions,
+    mut on_progress: impl FnMut(&SyncReport),
+) -> Result<SyncReport>
+where
+    S: BlobStore + Send + Sync + ?Sized,
+    D: BlobStore + Send + Sync + ?Sized,
+{
+    let batch_size = options.batch_size.unwrap_or(DEFAULT_SYNC_BATCH_SIZE).max(1);
+    let concurrency_limit = options
+        .concurrency_limit
+        .unwrap_or_else(|| dst.batch_concurrency_limit())
+        .max(1);
+
+    let mut report = SyncReport {
+        resume_after: options.list.start_after.clone(),
+        ..Default::default()
+    };
+    // set once a blob failed, `resume_after` no longer moves past it
+    let mut stalled = false;
+    let mut batches = src.list(options.list.clone()).try_chunks(batch_size);
+    while let Some(batch) = batches.try_next().await.map_err(|e| e.1)? {
+        report.listed += batch.len() as u64;
+
+        let missing = dst
+            .exists_many(batch.clone(), options.concurrency_limit)
+            .await?
+            .into_iter()
+            .filter(|result| !result.exists)
+            .map(|result| result.cid)
+            .collect::<Vec<_>>();
+        report.skipped += (batch.len() - missing.len()) as u64;
+
+        let results = stream::iter(missing)
+            .map(|cid| async move {
+                let result = copy_blob(src, dst, &cid).await;
+                (cid, result)
+            })
+            .buffer_unordered(concurrency_limit)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut first_failed: Option<String> = None;
+        for (cid, result) in results {
+            match result {
+                Ok(bytes) => {
+                    report.copied += 1;
+                    report.bytes_copied += bytes;
+                }
+                Err(e) => {
+                    warn!("Failed to sync blob '{cid}': {e}");
+                    if first_failed.as_ref().is_none_or(|first| cid < *first) {
+                        first_failed = Some(cid.clone());
+                    }
+                    report.failed.push(SyncFailure {
+                        cid,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        if !stalled {
+            report.resume_after = match first_failed {
+                Some(failed) => {
+                    stalled = true;
+                    batch
+                        .iter()
+                        .take_while(|cid| **cid < failed)
+                        .last()
+                        .cloned()
+                        .or(report.resume_after.take())
+                }
+                None => batch.last().cloned(),
+            };
+        }
+        debug!(
+            "Synced {} blobs, {} copied, up to {:?}.",
+            report.listed, report.copied, report.resume_after
+        );
+        on_progress(&report);
+    }
+
+    Ok(report)
+}
+
+/// Streams one blob from `src` to `dst`, returning its size.
+async fn copy_blob<S, D>(src: &S, dst: &D, cid: &str) -> Result<u64>
+where
+    S: BlobStore + Send + Sync + ?Sized,
+    D: BlobStore + Send + Sync + ?Sized,
+{
+    let chunks = src
+        .get_stream(cid)
+        .await?
+        .ok_or_else(|| anyhow!("Blob '{cid}' is missing from the source store."))?;
+
+    let size = Arc::new(AtomicU64::new(0));
+    let counter = size.clone();
+    let chunks = chunks
+        .inspect_ok(move |chunk| {
+            counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+        })
+        .boxed();
+
+    let codec = Cid::try_from(cid)?.codec();
+    dst.put_stream(chunks, codec, Some(cid)).await?;
+
+    Ok(size.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+#[cfg(feature = "blob-local")]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+    use crate::blob_store::LocalFs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("integrity-blob-sync-{name}-{}", std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn copies_missing_blobs_and_resumes() {
+        let mut src = LocalFs::new(temp_dir("src"));
+        let mut dst = LocalFs::new(temp_dir("dst"));
+        src.init().await.unwrap();
+        dst.init().await.unwrap();
+
+        let mut cids = vec![];
+        for blob in ["one", "two", "three", "four", "five"] {
+            cids.push(src.put(blob.into(), 0x55, None).await.unwrap());
+        }
+        cids.sort();
+        dst.put(b"two".to_vec(), 0x55, None).await.unwrap();
+        let corrupt = cids[3].clone();
+        fs::write(temp_dir("src").join(&corrupt), b"corrupt").unwrap();
+
+        // an interrupted sync stops after its first batch
+        let options = SyncOptions {
+            batch_size: Some(2),
+            ..Default::default()
+        };
+        let first_batch = sync(
+            &src,
+            &dst,
+            &SyncOptions {
+                list: ListOptions {
+                    limit: Some(2),
+                    ..Default::default()
+                },
+                ..options.clone()
+            },
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(first_batch.listed, 2);
+        assert_eq!(first_batch.resume_after.as_ref(), Some(&cids[1]));
+
+        let mut progress = vec![];
+        let resumed_options = SyncOptions {
+            list: ListOptions {
+                start_after: first_batch.resume_after.clone(),
+                ..Default::default()
+            },
+            ..options
+        };
+        let report = sync(&src, &dst, &resumed_options, |report| {
+            progress.push(report.resume_after.clone())
+        })
+        .await
+        .unwrap();
+
+        // the corrupt blob holds `resume_after` back
+        assert_eq!(progress, vec![Some(cids[2].clone()), Some(cids[2].clone())]);
+        assert_eq!(report.listed, 3);
+        assert_eq!(
+            first_batch.copied + first_batch.skipped + report.copied + report.skipped,
+            4
+        );
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].cid, corrupt);
+        for cid in &cids {
+            assert_eq!(dst.exists(cid).await.unwrap(), *cid != corrupt, "{cid}");
+        }
+
+        let report = sync(&src, &dst, &SyncOptions::default(), |_| {})
+            .await
+            .unwrap();
+        assert_eq!((report.listed, report.skipped, report.copied), (5, 4, 0));
+        assert_eq!(report.resume_after.as_ref(), Some(&cids[2]));
+
+        fs::remove_dir_all(temp_dir("src")).unwrap();
+        fs::remove_dir_all(temp_dir("dst")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn retries_failed_blobs_when_resumed() {
+        let mut src = LocalFs::new(temp_dir("retry-src"));
+        let mut dst = LocalFs::new(temp_dir("retry-dst"));
+        src.init().await.unwrap();
+        dst.init().await.unwrap();
+
+        let mut blobs = vec![];
+        for blob in ["one", "two", "three", "four"] {
+            let cid = src.put(blob.into(), 0x55, None).await.unwrap();
+            blobs.push((cid, blob));
+        }
+        blobs.sort();
+        let (failing, content) = blobs[1].clone();
+        fs::write(temp_dir("retry-src").join(&failing), b"corrupt").unwrap();
+
+        let options = SyncOptions {
+            batch_size: Some(2),
+            ..Default::default()
+        };
+        let report = sync(&src, &dst, &options, |_| {}).await.unwrap();
+        assert_eq!(report.copied, 3);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.resume_after.as_ref(), Some(&blobs[0].0));
+
+        // once the source is repaired, resuming picks up the failed blob
+        fs::write(temp_dir("retry-src").join(&failing), content).unwrap();
+        let resumed = SyncOptions {
+            list: ListOptions {
+                start_after: report.resume_after,
+                ..Default::default()
+            },
+            ..options
+        };
+        let report = sync(&src, &dst, &resumed, |_| {}).await.unwrap();
+        assert_eq!((report.copied, report.skipped), (1, 2));
+        assert!(report.failed.is_empty());
+        assert_eq!(report.resume_after.as_ref(), Some(&blobs[3].0));
+        assert!(dst.exists(&failing).await.unwrap());
+
+        fs::remove_dir_all(temp_dir("retry-src")).unwrap();
+        fs::remove_dir_all(temp_dir("retry-dst")).unwrap();
+    }
+}
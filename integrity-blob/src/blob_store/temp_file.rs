@@ -0,0 +1,146 @@
+//! Temporary files holding blobs while they are written.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use anyhow::{anyhow, Result};
+use futures_util::TryStreamExt;
+
+use crate::blob_store::chunks::{BlobStream, CidHasher};
+
+#[cfg(not(target_arch = "wasm32"))]
+type File = tokio::fs::File;
+#[cfg(target_arch = "wasm32")]
+type File = std::fs::File;
+
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A file that is removed on drop unless persisted.
+///
+/// Temporary names start with `.` so they are never mistaken for CIDs. On native targets the
+/// file is written with `tokio::fs`, off the async executor, so writes must run within a
+/// tokio runtime.
+pub(crate) struct TempFile {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl TempFile {
+    /// Creates an empty temporary file in `dir`.
+    pub(crate) fn create_in(dir: &Path) -> Result<Self> {
+        let name = format!(
+            ".tmp-{}-{}",
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = dir.join(name);
+        let file = std::fs::File::create(&path)?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let file = File::from_std(file);
+
+        Ok(Self {
+            path,
+            file: Some(file),
+        })
+    }
+
+    /// Writes a whole blob to the file.
+    #[cfg(feature = "blob-local")]
+    pub(crate) async fn write_all(&mut self, blob: &[u8]) -> Result<()> {
+        let file = self.file()?;
+        write(file, blob).await?;
+        sync(file).await
+    }
+
+    /// Writes all of `chunks` to the file, returning its CID.
+    ///
+    /// # Arguments
+    /// * `chunks` - Blob contents
+    /// * `multicodec_code` - Multicodec of the blob's CID
+    /// * `expected_cid` - CID the contents must hash to, if known
+    pub(crate) async fn write_stream(
+        &mut self,
+        mut chunks: BlobStream,
+        multicodec_code: u64,
+        expected_cid: Option<&str>,
+    ) -> Result<String> {
+        let file = self.file()?;
+
+        let mut hasher = CidHasher::new(multicodec_code);
+        while let Some(chunk) = chunks.try_next().await? {
+            hasher.update(&chunk);
+            write(file, &chunk).await?;
+        }
+        sync(file).await?;
+
+        hasher.finalize_and_validate(expected_cid)
+    }
+
+    /// Returns the path of the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Atomically moves the file to `path`, replacing any existing file.
+    #[cfg(feature = "blob-local")]
+    pub(crate) fn persist(mut self, path: &Path) -> Result<()> {
+        drop(self.file.take());
+        fs::rename(&self.path, path)?;
+        // nothing left to clean up
+        self.path = PathBuf::new();
+        Ok(())
+    }
+
+    fn file(&mut self) -> Result<&mut File> {
+        self.file
+            .as_mut()
+            .ok_or_else(|| anyhow!("Temporary file is already closed."))
+    }
+}
+
+async fn write(file: &mut File, bytes: &[u8]) -> Result<()> {
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::io::AsyncWriteExt::write_all(file, bytes).await?;
+    #[cfg(target_arch = "wasm32")]
+    std::io::Write::write_all(file, bytes)?;
+    Ok(())
+}
+
+/// Flushes the file's contents to disk.
+async fn sync(file: &mut File) -> Result<()> {
+    #[cfg(not(target_arch = "wasm32"))]
+    file.sync_all().await?;
+    #[cfg(target_arch = "wasm32")]
+    file.sync_all()?;
+    Ok(())
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        drop(self.file.take());
+        if !self.path.as_os_str().is_empty() {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Spools a stream to a temporary file so its CID is known before uploading.
+///
+/// # Returns
+/// * `Result<(String, TempFile)>` - The blob's CID and the file holding it
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn spool(
+    chunks: BlobStream,
+    multicodec_code: u64,
+    expected_cid: Option<&str>,
+) -> Result<(String, TempFile)> {
+    let mut file = TempFile::create_in(&std::env::temp_dir())?;
+    let cid = file
+        .write_stream(chunks, multicodec_code, expected_cid)
+        .await?;
+    Ok((cid, file))
+}
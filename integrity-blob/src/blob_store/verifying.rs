@@ -0,0 +1,277 @@
+use std::fmt;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use cid::Cid;
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+
+use crate::blob_store::{
+    chunks::{BlobStream, CidHasher},
+    BlobExistsResult, BlobGetResult, BlobPut, BlobPutResult, BlobStore, ListOptions,
+};
+
+/// Multihash code of BLAKE3, the only hash blob store CIDs use.
+const BLAKE3_MULTIHASH: u64 = 0x1e;
+
+/// Reason a fetched blob couldn't be verified against the CID it was requested by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityError {
+    /// The blob hashes to a different CID than the one requested.
+    Mismatch { cid: String, computed_cid: String },
+    /// The requested CID isn't a BLAKE3 CID, so the blob can't be checked against it.
+    UnverifiableCid(String),
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::Mismatch { cid, computed_cid } => write!(
+                f,
+                "integrity check failed: blob requested as '{cid}' hashes to '{computed_cid}'"
+            ),
+            IntegrityError::UnverifiableCid(cid) => {
+                write!(f, "integrity check failed: '{cid}' isn't a BLAKE3 CID")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// Checks that a blob hashes to the CID it was fetched by.
+///
+/// # Arguments
+/// * `cid` - The requested CID
+/// * `blob` - The bytes returned for it
+///
+/// # Returns
+/// * `Result<()>` - Error wrapping an [`IntegrityError`] if the blob doesn't match
+pub fn verify_blob(cid: &str, blob: &[u8]) -> Result<()> {
+    let mut hasher = blob_hasher(cid)?;
+    hasher.update(blob);
+    check_hash(cid, &hasher)
+}
+
+fn blob_hasher(cid: &str) -> Result<CidHasher> {
+    let parsed = Cid::try_from(cid)
+        .ok()
+        .filter(|parsed| parsed.hash().code() == BLAKE3_MULTIHASH)
+        .ok_or_else(|| IntegrityError::UnverifiableCid(cid.to_owned()))?;
+
+    Ok(CidHasher::new(parsed.codec()))
+}
+
+fn check_hash(cid: &str, hasher: &CidHasher) -> Result<()> {
+    let computed_cid = hasher.finalize()?;
+    if computed_cid != cid {
+        return Err(IntegrityError::Mismatch {
+            cid: cid.to_owned(),
+            computed_cid,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Blob store wrapper that verifies every fetched blob against its CID.
+///
+/// Reads through `get`, `get_many` and `get_stream` fail with an [`IntegrityError`] when the
+/// backend returns bytes that don't hash to the requested CID, so a tampered or corrupted
+/// store is never trusted. Streams are checked once fully read, the error replacing the
+/// end of the stream. Writes and listings are passed through unchanged.
+pub struct VerifyingBlobStore<S> {
+    inner: S,
+}
+
+impl<S> VerifyingBlobStore<S> {
+    /// Wraps a blob store so its reads are verified
+    ///
+    /// # Arguments
+    /// * `inner` - The store to read from
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Unwraps the store.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<S: BlobStore + Send + Sync> BlobStore for VerifyingBlobStore<S> {
+    async fn init(&mut self) -> Result<()> {
+        self.inner.init().await
+    }
+
+    async fn exists(&self, cid: &str) -> Result<bool> {
+        self.inner.exists(cid).await
+    }
+
+    async fn get(&self, cid: &str) -> Result<Option<Vec<u8>>> {
+        let blob = self.inner.get(cid).await?;
+        if let Some(blob) = &blob {
+            verify_blob(cid, blob)?;
+        }
+
+        Ok(blob)
+    }
+
+    async fn put(&self, blob: Vec<u8>, multicodec_code: u64, cid: Option<&str>) -> Result<String> {
+        self.inner.put(blob, multicodec_code, cid).await
+    }
+
+    async fn get_stream(&self, cid: &str) -> Result<Option<BlobStream>> {
+        let Some(chunks) = self.inner.get_stream(cid).await? else {
+            return Ok(None);
+        };
+        let hasher = blob_hasher(cid)?;
+        let cid = cid.to_owned();
+
+        // `None` once the stream has ended and been verified
+        let verified = stream::try_unfold(Some((chunks, hasher)), move |state| {
+            let cid = cid.clone();
+            async move {
+                let Some((mut chunks, mut hasher)) = state else {
+                    return Ok(None);
+                };
+
+                match chunks.try_next().await? {
+                    Some(chunk) => {
+                        hasher.update(&chunk);
+                        Ok(Some((Some(chunk), Some((chunks, hasher)))))
+                    }
+                    None => {
+                        check_hash(&cid, &hasher)?;
+                        Ok(Some((None, None)))
+                    }
+                }
+            }
+        })
+        .try_filter_map(|chunk| async move { Ok(chunk) })
+        .boxed();
+
+        Ok(Some(verified))
+    }
+
+    async fn put_stream(
+        &self,
+        chunks: BlobStream,
+        multicodec_code: u64,
+        cid: Option<&str>,
+    ) -> Result<String> {
+        self.inner.put_stream(chunks, multicodec_code, cid).await
+    }
+
+    async fn delete(&self, cid: &str) -> Result<()> {
+        self.inner.delete(cid).await
+    }
+
+    fn list(&self, options: ListOptions) -> BoxStream<'_, Result<String>> {
+        self.inner.list(options)
+    }
+
+    fn batch_concurrency_limit(&self) -> usize {
+        self.inner.batch_concurrency_limit()
+    }
+
+    async fn exists_many(
+        &self,
+        cids: Vec<String>,
+        concurrency_limit: Option<usize>,
+    ) -> Result<Vec<BlobExistsResult>> {
+        self.inner.exists_many(cids, concurrency_limit).await
+    }
+
+    async fn get_many(
+        &self,
+        cids: Vec<String>,
+        concurrency_limit: Option<usize>,
+    ) -> Result<Vec<BlobGetResult>> {
+        let results = self.inner.get_many(cids, concurrency_limit).await?;
+        for result in &results {
+            if let Some(blob) = &result.blob {
+                verify_blob(&result.cid, blob)?;
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn put_many(
+        &self,
+        blobs: Vec<BlobPut>,
+        concurrency: Option<usize>,
+    ) -> Result<Vec<BlobPutResult>> {
+        self.inner.put_many(blobs, concurrency).await
+    }
+
+    async fn delete_many(&self, cids: Vec<String>, concurrency_limit: Option<usize>) -> Result<()> {
+        self.inner.delete_many(cids, concurrency_limit).await
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "blob-local")]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::blob_store::LocalFs;
+
+    fn integrity_error(err: anyhow::Error) -> IntegrityError {
+        err.downcast::<IntegrityError>()
+            .expect("expected an integrity error")
+    }
+
+    #[tokio::test]
+    async fn rejects_tampered_blobs() {
+        let dir =
+            std::env::temp_dir().join(format!("integrity-blob-verifying-{}", std::process::id()));
+        let mut store = VerifyingBlobStore::new(LocalFs::new(dir.clone()));
+        store.init().await.unwrap();
+
+        let intact = store.put(b"intact".to_vec(), 0x55, None).await.unwrap();
+        let tampered = store.put(b"original".to_vec(), 0x55, None).await.unwrap();
+        fs::write(dir.join(&tampered), b"tampered").unwrap();
+
+        assert_eq!(store.get(&intact).await.unwrap().unwrap(), b"intact");
+        assert!(store.get("bafkr4missing").await.unwrap().is_none());
+
+        let err = integrity_error(store.get(&tampered).await.unwrap_err());
+        assert!(matches!(err, IntegrityError::Mismatch { cid, .. } if cid == tampered));
+
+        let err = store
+            .get_many(vec![intact.clone(), tampered.clone()], None)
+            .await
+            .unwrap_err();
+        integrity_error(err);
+        assert_eq!(
+            store.get_many(vec![intact.clone()], None).await.unwrap()[0].blob,
+            Some(b"intact".to_vec())
+        );
+
+        let chunks = store.get_stream(&tampered).await.unwrap().unwrap();
+        integrity_error(chunks.try_concat().await.unwrap_err());
+        let chunks = store.get_stream(&intact).await.unwrap().unwrap();
+        assert_eq!(chunks.try_concat().await.unwrap(), b"intact");
+
+        // unverified reads still see the tampered bytes
+        assert_eq!(
+            store.inner().get(&tampered).await.unwrap().unwrap(),
+            b"tampered"
+        );
+
+        fs::write(dir.join("not-a-cid"), b"scratch").unwrap();
+        let err = integrity_error(store.get("not-a-cid").await.unwrap_err());
+        assert_eq!(err, IntegrityError::UnverifiableCid("not-a-cid".to_owned()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
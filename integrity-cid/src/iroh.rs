@@ -213,6 +213,35 @@ pub async fn compute_iroh_collection_cid(
     })
 }
 
+/// Gets the files hashed when computing a directory CID, in the order they are hashed.
+///
+/// # Arguments
+/// * `path` - Directory path to list
+/// * `cid_ignore` - Filter for which files in the directory are included
+///
+/// # Returns
+/// * `Result<Vec<(String, PathBuf)>>` - Relative file names and file paths, or error if the
+///   path isn't a directory or can't be walked
+pub fn get_files_for_dir_cid(
+    path: impl Into<PathBuf>,
+    cid_ignore: CidIgnoreConfig,
+) -> Result<Vec<(String, PathBuf)>> {
+    let path = path.into();
+    if !path.is_dir() {
+        bail!(
+            "The provided path ({:?}) is not a directory",
+            path.display()
+        );
+    };
+
+    let files = sort_data_sources(files_for_dir_cid(path.canonicalize()?, cid_ignore)?)
+        .into_iter()
+        .map(|d| (d.name, d.path))
+        .collect();
+
+    Ok(files)
+}
+
 /// Gets the list of files ignored when computing a directory CID.
 ///
 /// # Arguments
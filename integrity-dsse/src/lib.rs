@@ -1,9 +1,27 @@
+/// Threshold (k-of-n) signature policies for DSSE envelopes.
+pub mod policy;
+
+/// Key resolution and signature verification for DSSE envelopes.
+pub mod verify;
+
 use std::{convert::TryFrom, fmt, str::FromStr, sync::Arc};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use base64::engine::{general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Utc};
 use integrity_lineage_models::models;
 use integrity_signer::Signer;
+/// Re-exported DSSE pre-authentication encoding for convenience.
+pub use integrity_sigstore::pae;
+#[cfg(not(target_arch = "wasm32"))]
+use integrity_sigstore::TimestampClient;
+use integrity_sigstore::{trust::TrustedRoot, TimestampToken};
+/// Re-exported policy types for convenience.
+pub use policy::{PolicyVerification, ThresholdPolicy};
+/// Re-exported verification types for convenience.
+pub use verify::{
+    EnvelopeVerification, SignatureError, SignatureVerification, VerifyOptions, VerifyingKey,
+};
 
 /// Dead Simple Signing Envelope (DSSE) for secure payload signatures.
 ///
@@ -42,6 +60,92 @@ impl Envelope {
         let envelope: models::dsse::Envelope = serde_json::from_str(s)?;
         Self::try_from(envelope)
     }
+
+    /// Verifies every signature in the envelope over its pre-authentication encoding.
+    ///
+    /// Each signature's `keyid` is resolved as a `did:key`; a signature that can't be
+    /// resolved or doesn't match is reported in its result rather than as an error.
+    ///
+    /// # Returns
+    /// * `EnvelopeVerification` - Per-signature verification results
+    pub fn verify(&self) -> EnvelopeVerification {
+        self.verify_with_options(VerifyOptions::default())
+    }
+
+    /// Verifies every signature in the envelope using the given options.
+    ///
+    /// # Arguments
+    /// * `options` - Verification options, e.g. whether legacy raw-payload signatures are accepted
+    ///
+    /// # Returns
+    /// * `EnvelopeVerification` - Per-signature verification results
+    pub fn verify_with_options(&self, options: VerifyOptions) -> EnvelopeVerification {
+        let payload_type = self.payload_type.to_string();
+
+        let modes: &[SigningMode] = if options.allow_legacy {
+            &[SigningMode::PaeV1, SigningMode::Legacy]
+        } else {
+            &[SigningMode::PaeV1]
+        };
+
+        let signatures = self
+            .signatures
+            .iter()
+            .map(|signature| {
+                let result = VerifyingKey::from_did_key(&signature.keyid).and_then(|key| {
+                    let mut error = SignatureError::InvalidSignature;
+                    for mode in modes {
+                        let msg = mode.message(&payload_type, &self.payload);
+                        match key.verify(&msg, &signature.sig) {
+                            Ok(()) => return Ok(*mode),
+                            Err(e) => error = e,
+                        }
+                    }
+                    Err(error)
+                });
+
+                let (mode, error) = match result {
+                    Ok(mode) => (Some(mode), None),
+                    Err(e) => (None, Some(e)),
+                };
+
+                SignatureVerification {
+                    keyid: signature.keyid.clone(),
+                    mode,
+                    error,
+                }
+            })
+            .collect();
+
+        EnvelopeVerification { signatures }
+    }
+
+    /// Verifies the RFC 3161 timestamps attached to each signature against trusted
+    /// timestamp authorities.
+    ///
+    /// Timestamps only attest when a signature existed; the signatures themselves are
+    /// checked by [`Envelope::verify`].
+    ///
+    /// # Arguments
+    /// * `trusted_root` - Trusted root listing the timestamp authorities
+    ///
+    /// # Returns
+    /// * `Result<Vec<Vec<DateTime<Utc>>>>` - Verified timestamp times per signature, or error if
+    ///   any timestamp is malformed, doesn't cover its signature or isn't from a trusted authority
+    pub fn verify_timestamps(&self, trusted_root: &TrustedRoot) -> Result<Vec<Vec<DateTime<Utc>>>> {
+        self.signatures
+            .iter()
+            .map(|signature| {
+                signature
+                    .timestamps
+                    .iter()
+                    .map(|token| {
+                        TimestampToken::from_der(token)?.verify(&signature.sig, trusted_root)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
 }
 
 /// Supported payload types for DSSE envelopes.
@@ -84,6 +188,36 @@ impl FromStr for PayloadType {
     }
 }
 
+/// Message format covered by DSSE signatures.
+///
+/// Envelopes produced before PAE support signed the raw payload bytes; these can
+/// still be verified by opting into [`VerifyOptions::allow_legacy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SigningMode {
+    /// Signatures cover the raw payload bytes (pre-PAE envelopes)
+    Legacy,
+    /// Signatures cover the DSSEv1 pre-authentication encoding, per the DSSE spec
+    #[default]
+    PaeV1,
+}
+
+impl SigningMode {
+    /// Returns the message that is signed for a payload under this mode.
+    ///
+    /// # Arguments
+    /// * `payload_type` - Payload type string of the envelope
+    /// * `payload` - Raw (not base64-encoded) payload bytes
+    ///
+    /// # Returns
+    /// * `Vec<u8>` - Bytes passed to the signer
+    pub fn message(&self, payload_type: &str, payload: &[u8]) -> Vec<u8> {
+        match self {
+            SigningMode::Legacy => payload.to_vec(),
+            SigningMode::PaeV1 => pae(payload_type, payload),
+        }
+    }
+}
+
 /// Digital signature within a DSSE envelope.
 ///
 /// Contains the signature bytes and key identifier for verification,
@@ -94,10 +228,14 @@ pub struct Signature {
     pub keyid: String,
     /// The actual signature bytes
     pub sig: Vec<u8>,
+    /// DER encoded RFC 3161 timestamp tokens over the signature bytes
+    pub timestamps: Vec<Vec<u8>>,
 }
 
 /// Signs a payload using DSSE (Dead Simple Signing Envelope) format.
 ///
+/// The signature covers the DSSEv1 pre-authentication encoding of the payload.
+///
 /// # Arguments
 /// * `payload` - Data bytes to sign
 /// * `payload_type` - Type/format of the payload
@@ -110,13 +248,30 @@ pub async fn sign_dsse(
     payload_type: PayloadType,
     signer: Arc<dyn Signer>,
 ) -> Result<Envelope> {
-    let keyid = signer
-        .get_did_doc()
-        .await?
-        .map(|d| d.id)
-        .ok_or_else(|| anyhow!("No DID Document for signer."))?;
-    let sig = signer.sign(&payload).await?.to_vec();
-    let signature = Signature { keyid, sig };
+    sign_dsse_with_mode(payload, payload_type, signer, SigningMode::default()).await
+}
+
+/// Signs a payload using DSSE format with an explicit signing mode.
+///
+/// [`SigningMode::Legacy`] only exists to reproduce pre-PAE envelopes; new
+/// envelopes should use [`sign_dsse`].
+///
+/// # Arguments
+/// * `payload` - Data bytes to sign
+/// * `payload_type` - Type/format of the payload
+/// * `signer` - Signer implementation used to produce the signature
+/// * `mode` - Message format the signature covers
+///
+/// # Returns
+/// * `Result<Envelope>` - Signed DSSE envelope, or error if signing fails
+pub async fn sign_dsse_with_mode(
+    payload: Vec<u8>,
+    payload_type: PayloadType,
+    signer: Arc<dyn Signer>,
+    mode: SigningMode,
+) -> Result<Envelope> {
+    let keyid = signer_keyid(signer.as_ref()).await?;
+    let signature = create_signature(&payload_type, &payload, keyid, signer, mode).await?;
 
     let signatures = vec![signature];
 
@@ -129,6 +284,119 @@ pub async fn sign_dsse(
     Ok(envelope)
 }
 
+/// Signs a payload using DSSE format with several signers at once.
+///
+/// # Arguments
+/// * `payload` - Data bytes to sign
+/// * `payload_type` - Type/format of the payload
+/// * `signers` - Signer implementations, one signature is produced per signer
+///
+/// # Returns
+/// * `Result<Envelope>` - Signed DSSE envelope with one signature per signer, or error if any signing fails
+pub async fn sign_dsse_multi(
+    payload: Vec<u8>,
+    payload_type: PayloadType,
+    signers: Vec<Arc<dyn Signer>>,
+) -> Result<Envelope> {
+    if signers.is_empty() {
+        bail!("At least one signer is required.");
+    }
+
+    let mut envelope = Envelope {
+        payload_type,
+        payload,
+        signatures: vec![],
+    };
+
+    for signer in signers {
+        envelope = cosign_dsse(envelope, signer).await?;
+    }
+
+    Ok(envelope)
+}
+
+/// Adds a signature to an existing DSSE envelope without modifying its payload.
+///
+/// # Arguments
+/// * `envelope` - Envelope to co-sign
+/// * `signer` - Signer implementation used to produce the additional signature
+///
+/// # Returns
+/// * `Result<Envelope>` - Envelope with the new signature appended, or error if signing fails
+///   or the signer has already signed the envelope
+pub async fn cosign_dsse(mut envelope: Envelope, signer: Arc<dyn Signer>) -> Result<Envelope> {
+    // checked before signing, so a hardware or remote signer isn't asked for a signature
+    // that would be thrown away
+    let keyid = signer_keyid(signer.as_ref()).await?;
+    if envelope
+        .signatures
+        .iter()
+        .any(|s| did_from_keyid(&s.keyid) == did_from_keyid(&keyid))
+    {
+        bail!("Envelope is already signed by '{keyid}'.");
+    }
+
+    let signature = create_signature(
+        &envelope.payload_type,
+        &envelope.payload,
+        keyid,
+        signer,
+        SigningMode::PaeV1,
+    )
+    .await?;
+
+    envelope.signatures.push(signature);
+
+    Ok(envelope)
+}
+
+/// Requests an RFC 3161 timestamp for every signature of a DSSE envelope.
+///
+/// Each token covers the signature bytes and is attached to its signature.
+///
+/// # Arguments
+/// * `envelope` - Signed envelope to timestamp
+/// * `client` - Client for the timestamp authority
+///
+/// # Returns
+/// * `Result<Envelope>` - Envelope with a timestamp appended to each signature, or error if
+///   a timestamp request fails
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn timestamp_dsse(mut envelope: Envelope, client: &TimestampClient) -> Result<Envelope> {
+    for signature in &mut envelope.signatures {
+        let token = client.timestamp(&signature.sig).await?;
+        signature.timestamps.push(token.as_der().to_vec());
+    }
+
+    Ok(envelope)
+}
+
+/// Returns the key id a signer's signatures are recorded under, its DID.
+async fn signer_keyid(signer: &dyn Signer) -> Result<String> {
+    signer
+        .get_did_doc()
+        .await?
+        .map(|d| d.id)
+        .ok_or_else(|| anyhow!("No DID Document for signer."))
+}
+
+async fn create_signature(
+    payload_type: &PayloadType,
+    payload: &[u8],
+    keyid: String,
+    signer: Arc<dyn Signer>,
+    mode: SigningMode,
+) -> Result<Signature> {
+    let msg = mode.message(&payload_type.to_string(), payload);
+    let sig = signer.sign(&msg).await?.to_vec();
+
+    Ok(Signature {
+        keyid,
+        sig,
+        timestamps: vec![],
+    })
+}
+
 /// Signs an integrity statement CID using DSSE format.
 ///
 /// # Arguments
@@ -152,16 +420,59 @@ pub async fn sign_integrity_statement_dsse(
     Ok(envelope)
 }
 
-/// Verifies a DSSE envelope signature (currently not implemented).
+/// Verifies the signatures of a DSSE envelope.
+///
+/// Signatures are checked over the DSSEv1 pre-authentication encoding of the payload,
+/// with each `keyid` resolved as a `did:key` (Ed25519, P-256 or secp256k1).
 ///
 /// # Arguments
-/// * `envelope` - DSSE envelope string to verify
+/// * `envelope` - DSSE envelope JSON string to verify
 ///
 /// # Returns
-/// * `Result<bool>` - True if signature is valid, false otherwise, or error if verification fails
-pub async fn verify_dsse(envelope: &str) -> Result<bool> {
-    let _ = envelope;
-    todo!("Implement verify_dsse()");
+/// * `Result<EnvelopeVerification>` - Per-signature verification results, or error if the envelope can't be parsed
+pub async fn verify_dsse(envelope: &str) -> Result<EnvelopeVerification> {
+    verify_dsse_with_options(envelope, VerifyOptions::default()).await
+}
+
+/// Verifies the signatures of a DSSE envelope using the given options.
+///
+/// # Arguments
+/// * `envelope` - DSSE envelope JSON string to verify
+/// * `options` - Verification options, e.g. whether legacy raw-payload signatures are accepted
+///
+/// # Returns
+/// * `Result<EnvelopeVerification>` - Per-signature verification results, or error if the envelope can't be parsed
+pub async fn verify_dsse_with_options(
+    envelope: &str,
+    options: VerifyOptions,
+) -> Result<EnvelopeVerification> {
+    let envelope = Envelope::try_from_json_string(envelope)?;
+
+    Ok(envelope.verify_with_options(options))
+}
+
+/// Verifies a DSSE envelope against a k-of-n threshold policy of trusted DIDs.
+///
+/// # Arguments
+/// * `envelope` - DSSE envelope JSON string to verify
+/// * `policy` - Trusted signer DIDs and the number of them that must have signed
+/// * `options` - Verification options, e.g. whether legacy raw-payload signatures are accepted
+///
+/// # Returns
+/// * `Result<PolicyVerification>` - Policy outcome with per-signature results, or error if the envelope can't be parsed
+pub async fn verify_dsse_threshold(
+    envelope: &str,
+    policy: &ThresholdPolicy,
+    options: VerifyOptions,
+) -> Result<PolicyVerification> {
+    let verification = verify_dsse_with_options(envelope, options).await?;
+
+    Ok(policy.evaluate(verification))
+}
+
+/// Returns the DID portion of a signature `keyid`, dropping any DID URL fragment.
+pub(crate) fn did_from_keyid(keyid: &str) -> &str {
+    keyid.split('#').next().unwrap_or(keyid)
 }
 
 impl TryFrom<models::dsse::Envelope> for Envelope {
@@ -215,20 +526,374 @@ impl TryFrom<models::dsse::Signature> for Signature {
     type Error = anyhow::Error;
 
     fn try_from(signature: models::dsse::Signature) -> Result<Self> {
-        let models::dsse::Signature { keyid, sig } = signature;
+        let models::dsse::Signature {
+            keyid,
+            sig,
+            timestamps,
+        } = signature;
 
         let sig = BASE64.decode(sig)?;
+        let timestamps = timestamps
+            .into_iter()
+            .map(|token| BASE64.decode(token))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(Self { keyid, sig })
+        Ok(Self {
+            keyid,
+            sig,
+            timestamps,
+        })
     }
 }
 
 impl From<Signature> for models::dsse::Signature {
     fn from(signature: Signature) -> Self {
-        let Signature { keyid, sig } = signature;
+        let Signature {
+            keyid,
+            sig,
+            timestamps,
+        } = signature;
 
         let sig = BASE64.encode(sig);
+        let timestamps = timestamps.into_iter().map(|t| BASE64.encode(t)).collect();
+
+        Self {
+            keyid,
+            sig,
+            timestamps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use integrity_signer::{Ed25519Signer, P256Signer, Secp256k1Signer, SignerType};
+    use integrity_sigstore::testing::{TimestampAuthority, TIMESTAMP_TIME};
+
+    use super::*;
+
+    async fn sign_pae(payload: &[u8], signer: &SignerType) -> Envelope {
+        sign_dsse(
+            payload.to_vec(),
+            PayloadType::InTotoJson,
+            Arc::new(signer.clone()),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn signers() -> Vec<SignerType> {
+        vec![
+            SignerType::ED25519(Ed25519Signer::create().unwrap()),
+            SignerType::P256(P256Signer::create().unwrap()),
+            SignerType::SECP256K1(Secp256k1Signer::create().unwrap()),
+        ]
+    }
+
+    #[test]
+    fn pae_matches_spec_example() {
+        assert_eq!(
+            pae("http://example.com/HelloWorld", b"hello world"),
+            b"DSSEv1 29 http://example.com/HelloWorld 11 hello world".to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_dsse_accepts_valid_signatures() {
+        for signer in signers() {
+            let envelope = sign_pae(b"{\"hello\":\"world\"}", &signer).await;
+            let envelope_json = envelope.into_json_string().unwrap();
+
+            let verification = verify_dsse(&envelope_json).await.unwrap();
+            assert!(verification.is_valid(), "{signer}: {verification:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_dsse_reports_signing_mode() {
+        let signer = SignerType::ED25519(Ed25519Signer::create().unwrap());
+        let envelope = sign_pae(b"payload", &signer).await;
+
+        let verification = envelope.verify();
+        assert_eq!(verification.signatures[0].mode, Some(SigningMode::PaeV1));
+    }
+
+    #[tokio::test]
+    async fn verify_dsse_legacy_envelopes_require_opt_in() {
+        for signer in signers() {
+            let envelope = sign_dsse_with_mode(
+                b"urn:cid:bafkr4ibthuzk3zug7ghmx63yjqaiu6rx4hhfdv3453j5bodskgw57bx2ya".to_vec(),
+                PayloadType::IntegrityStatementUrn,
+                Arc::new(signer.clone()),
+                SigningMode::Legacy,
+            )
+            .await
+            .unwrap();
+            let envelope_json = envelope.into_json_string().unwrap();
+
+            let strict = verify_dsse(&envelope_json).await.unwrap();
+            assert!(!strict.is_valid(), "{signer}");
+
+            let options = VerifyOptions { allow_legacy: true };
+            let compat = verify_dsse_with_options(&envelope_json, options)
+                .await
+                .unwrap();
+            assert!(compat.is_valid(), "{signer}: {compat:?}");
+            assert_eq!(compat.signatures[0].mode, Some(SigningMode::Legacy));
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_dsse_rejects_tampered_payload() {
+        for signer in signers() {
+            let mut envelope = sign_pae(b"{\"hello\":\"world\"}", &signer).await;
+            envelope.payload = b"{\"hello\":\"mallory\"}".to_vec();
+            let envelope_json = envelope.into_json_string().unwrap();
+
+            let verification = verify_dsse(&envelope_json).await.unwrap();
+            assert!(!verification.is_valid(), "{signer}");
+            assert_eq!(
+                verification.signatures[0].error,
+                Some(SignatureError::InvalidSignature)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_dsse_rejects_high_s_secp256k1_signatures() {
+        let signer = SignerType::SECP256K1(Secp256k1Signer::create().unwrap());
+        let mut envelope = sign_pae(b"payload", &signer).await;
+        assert!(envelope.verify().is_valid());
+
+        // negating S gives the other valid encoding of the same signature
+        let sig = k256::ecdsa::Signature::from_slice(&envelope.signatures[0].sig).unwrap();
+        let (r, s) = sig.split_scalars();
+        let high_s = k256::ecdsa::Signature::from_scalars(r, -*s).unwrap();
+        envelope.signatures[0].sig = high_s.to_bytes().to_vec();
+
+        let verification = envelope.verify();
+        assert!(!verification.is_valid());
+        assert!(matches!(
+            verification.signatures[0].error,
+            Some(SignatureError::MalformedSignature(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn verify_dsse_reports_unresolvable_keyid() {
+        let signer = SignerType::ED25519(Ed25519Signer::create().unwrap());
+        let mut envelope = sign_pae(b"payload", &signer).await;
+        envelope.signatures[0].keyid = "urn:uuid:not-a-did".to_owned();
+
+        let verification = envelope.verify();
+        assert!(!verification.is_valid());
+        assert!(matches!(
+            verification.signatures[0].error,
+            Some(SignatureError::UnresolvableKeyId(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn verify_dsse_rejects_unsigned_envelope() {
+        let envelope = Envelope {
+            payload_type: PayloadType::InTotoJson,
+            payload: b"payload".to_vec(),
+            signatures: vec![],
+        };
+
+        assert!(!envelope.verify().is_valid());
+    }
+
+    async fn did_of(signer: &SignerType) -> String {
+        Signer::get_did_doc(signer).await.unwrap().unwrap().id
+    }
+
+    #[tokio::test]
+    async fn cosign_dsse_appends_signature_without_touching_payload() {
+        let [first, second, ..] = &signers()[..] else {
+            unreachable!()
+        };
+        let envelope = sign_pae(b"{\"hello\":\"world\"}", first).await;
+        let original = envelope.clone();
+
+        let cosigned = cosign_dsse(envelope, Arc::new(second.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(cosigned.payload, original.payload);
+        assert_eq!(
+            cosigned.payload_type.to_string(),
+            original.payload_type.to_string()
+        );
+        assert_eq!(cosigned.signatures[0].sig, original.signatures[0].sig);
+        assert_eq!(cosigned.signatures.len(), 2);
+        assert!(cosigned.verify().is_valid());
+    }
+
+    /// Counts the signatures it's asked for.
+    struct CountingSigner {
+        signer: SignerType,
+        signs: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Signer for CountingSigner {
+        async fn sign(&self, data: &[u8]) -> Result<[u8; 64]> {
+            self.signs.fetch_add(1, Ordering::Relaxed);
+            Signer::sign(&self.signer, data).await
+        }
+
+        async fn get_did_doc(&self) -> Result<Option<did_key::Document>> {
+            Signer::get_did_doc(&self.signer).await
+        }
+    }
+
+    #[tokio::test]
+    async fn cosign_dsse_rejects_duplicate_signer() {
+        let signer = SignerType::ED25519(Ed25519Signer::create().unwrap());
+        let envelope = sign_pae(b"payload", &signer).await;
+
+        let counting = Arc::new(CountingSigner {
+            signer,
+            signs: AtomicUsize::new(0),
+        });
+        assert!(cosign_dsse(envelope, counting.clone()).await.is_err());
+        assert_eq!(counting.signs.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn sign_dsse_multi_signs_with_every_signer() {
+        let signers = signers();
+        let envelope = sign_dsse_multi(
+            b"payload".to_vec(),
+            PayloadType::InTotoJson,
+            signers
+                .iter()
+                .map(|s| Arc::new(s.clone()) as Arc<dyn Signer>)
+                .collect(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(envelope.signatures.len(), signers.len());
+        for (signature, signer) in envelope.signatures.iter().zip(&signers) {
+            assert_eq!(signature.keyid, did_of(signer).await);
+        }
+        assert!(envelope.verify().is_valid());
+
+        assert!(
+            sign_dsse_multi(b"payload".to_vec(), PayloadType::InTotoJson, vec![])
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_dsse_threshold_counts_distinct_trusted_signers() {
+        let signers = signers();
+        let untrusted = SignerType::ED25519(Ed25519Signer::create().unwrap());
+        let mut trusted_dids = vec![];
+        for signer in &signers {
+            trusted_dids.push(did_of(signer).await);
+        }
+
+        // two of three trusted signers plus an untrusted one
+        let envelope = sign_dsse_multi(
+            b"payload".to_vec(),
+            PayloadType::InTotoJson,
+            vec![
+                Arc::new(signers[0].clone()),
+                Arc::new(signers[1].clone()),
+                Arc::new(untrusted),
+            ],
+        )
+        .await
+        .unwrap();
+        let envelope_json = envelope.into_json_string().unwrap();
+
+        let two_of_three = ThresholdPolicy::new(2, trusted_dids.clone()).unwrap();
+        let result = verify_dsse_threshold(&envelope_json, &two_of_three, VerifyOptions::default())
+            .await
+            .unwrap();
+        assert!(result.satisfied, "{result:?}");
+        assert_eq!(result.trusted_signers.len(), 2);
+
+        let three_of_three = ThresholdPolicy::new(3, trusted_dids).unwrap();
+        let result =
+            verify_dsse_threshold(&envelope_json, &three_of_three, VerifyOptions::default())
+                .await
+                .unwrap();
+        assert!(!result.satisfied);
+    }
+
+    #[tokio::test]
+    async fn verify_dsse_threshold_ignores_repeated_and_invalid_signatures() {
+        let signer = SignerType::ED25519(Ed25519Signer::create().unwrap());
+        let other = SignerType::P256(P256Signer::create().unwrap());
+        let mut envelope = sign_pae(b"payload", &signer).await;
+        // the same DID signing twice, once via a DID URL keyid
+        let mut repeated = envelope.signatures[0].clone();
+        repeated.keyid = format!("{}#key-1", repeated.keyid);
+        envelope.signatures.push(repeated);
+        let mut envelope = cosign_dsse(envelope, Arc::new(other.clone()))
+            .await
+            .unwrap();
+        envelope.signatures[2].sig[0] ^= 0xff;
+
+        let policy =
+            ThresholdPolicy::new(2, vec![did_of(&signer).await, did_of(&other).await]).unwrap();
+        let result = policy.evaluate(envelope.verify());
+        assert!(!result.satisfied, "{result:?}");
+        assert_eq!(result.trusted_signers, vec![did_of(&signer).await]);
+    }
+
+    #[test]
+    fn threshold_policy_rejects_unsatisfiable_thresholds() {
+        let dids = vec!["did:key:a".to_owned(), "did:key:b".to_owned()];
+
+        assert!(ThresholdPolicy::new(0, dids.clone()).is_err());
+        assert!(ThresholdPolicy::new(3, dids.clone()).is_err());
+        // duplicates collapse to a single trusted DID
+        assert!(ThresholdPolicy::new(2, vec![dids[0].clone(), dids[0].clone()]).is_err());
+        assert_eq!(ThresholdPolicy::new(2, dids).unwrap().threshold(), 2);
+    }
 
-        Self { keyid, sig }
+    #[tokio::test]
+    async fn timestamp_dsse_attaches_verifiable_timestamps() {
+        let tsa = TimestampAuthority::new();
+        let trusted_root = tsa.trusted_root();
+        let server = tsa.serve().await;
+        let client = TimestampClient::new(server.uri());
+
+        let envelope = sign_dsse_multi(
+            b"payload".to_vec(),
+            PayloadType::InTotoJson,
+            signers()
+                .into_iter()
+                .map(|s| Arc::new(s) as Arc<dyn Signer>)
+                .collect(),
+        )
+        .await
+        .unwrap();
+        let envelope = timestamp_dsse(envelope, &client).await.unwrap();
+
+        // timestamps survive a JSON round trip
+        let envelope =
+            Envelope::try_from_json_string(&envelope.into_json_string().unwrap()).unwrap();
+        assert!(envelope.verify().is_valid());
+
+        let times = envelope.verify_timestamps(&trusted_root).unwrap();
+        assert_eq!(times.len(), 3);
+        let time = DateTime::from_timestamp(TIMESTAMP_TIME, 0).unwrap();
+        assert!(times.iter().all(|t| t == &vec![time]));
+
+        let mut tampered = envelope.clone();
+        tampered.signatures[1].sig[0] ^= 0xff;
+        assert!(tampered.verify_timestamps(&trusted_root).is_err());
+
+        let untrusted = TimestampAuthority::with_seed(9).trusted_root();
+        assert!(envelope.verify_timestamps(&untrusted).is_err());
     }
 }
@@ -0,0 +1,99 @@
+use std::collections::BTreeSet;
+
+use anyhow::{bail, Result};
+
+use crate::{did_from_keyid, EnvelopeVerification};
+
+/// A k-of-n signature policy over a set of trusted signer DIDs.
+///
+/// An envelope satisfies the policy when at least `threshold` distinct trusted
+/// DIDs have a valid signature on it. Signatures from untrusted keys and
+/// repeated signatures from the same DID don't count towards the threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThresholdPolicy {
+    threshold: usize,
+    trusted_dids: BTreeSet<String>,
+}
+
+impl ThresholdPolicy {
+    /// Creates a new threshold policy.
+    ///
+    /// # Arguments
+    /// * `threshold` - Number of distinct trusted DIDs that must have signed
+    /// * `trusted_dids` - DIDs whose signatures count towards the threshold
+    ///
+    /// # Returns
+    /// * `Result<Self>` - The policy, or error if the threshold is zero or exceeds the number of trusted DIDs
+    pub fn new(threshold: usize, trusted_dids: Vec<String>) -> Result<Self> {
+        let trusted_dids = trusted_dids
+            .iter()
+            .map(|did| did_from_keyid(did).to_owned())
+            .collect::<BTreeSet<_>>();
+
+        if threshold == 0 {
+            bail!("Threshold must be at least 1.");
+        }
+
+        if threshold > trusted_dids.len() {
+            bail!(
+                "Threshold {threshold} exceeds the number of trusted DIDs ({}).",
+                trusted_dids.len()
+            );
+        }
+
+        Ok(Self {
+            threshold,
+            trusted_dids,
+        })
+    }
+
+    /// Returns the number of trusted DIDs that must have signed.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the DIDs whose signatures count towards the threshold.
+    pub fn trusted_dids(&self) -> impl Iterator<Item = &str> {
+        self.trusted_dids.iter().map(String::as_str)
+    }
+
+    /// Evaluates envelope verification results against this policy.
+    ///
+    /// # Arguments
+    /// * `verification` - Per-signature results from verifying an envelope
+    ///
+    /// # Returns
+    /// * `PolicyVerification` - Whether the policy is satisfied and by which signers
+    pub fn evaluate(&self, verification: EnvelopeVerification) -> PolicyVerification {
+        let trusted_signers = verification
+            .signatures
+            .iter()
+            .filter(|s| s.is_valid())
+            .map(|s| did_from_keyid(&s.keyid))
+            .filter(|did| self.trusted_dids.contains(*did))
+            .map(ToOwned::to_owned)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        PolicyVerification {
+            satisfied: trusted_signers.len() >= self.threshold,
+            threshold: self.threshold,
+            trusted_signers,
+            verification,
+        }
+    }
+}
+
+/// Outcome of verifying a DSSE envelope against a [`ThresholdPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyVerification {
+    /// Whether enough trusted DIDs signed the envelope
+    pub satisfied: bool,
+    /// Number of trusted DIDs that were required
+    pub threshold: usize,
+    /// Distinct trusted DIDs with a valid signature, sorted
+    pub trusted_signers: Vec<String>,
+    /// Per-signature verification results
+    pub verification: EnvelopeVerification,
+}
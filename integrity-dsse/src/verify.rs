@@ -0,0 +1,210 @@
+use std::fmt;
+
+use p256::ecdsa::signature::Verifier;
+
+use crate::{did_from_keyid, SigningMode};
+
+const DID_KEY_PREFIX: &str = "did:key:";
+
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+const P256_MULTICODEC: [u8; 2] = [0x80, 0x24];
+const SECP256K1_MULTICODEC: [u8; 2] = [0xe7, 0x01];
+
+/// Reason a single DSSE signature failed to verify.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The key identifier is not a resolvable `did:key`.
+    UnresolvableKeyId(String),
+    /// The key identifier uses a key type that is not supported for verification.
+    UnsupportedKeyType(String),
+    /// The public key bytes encoded in the key identifier are malformed.
+    MalformedKey(String),
+    /// The signature bytes are not a valid encoding for the key type.
+    MalformedSignature(String),
+    /// The signature is well-formed but does not match the signed message.
+    InvalidSignature,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::UnresolvableKeyId(e) => write!(f, "unresolvable keyid: {e}"),
+            SignatureError::UnsupportedKeyType(e) => write!(f, "unsupported key type: {e}"),
+            SignatureError::MalformedKey(e) => write!(f, "malformed public key: {e}"),
+            SignatureError::MalformedSignature(e) => write!(f, "malformed signature: {e}"),
+            SignatureError::InvalidSignature => write!(f, "invalid signature"),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// Public key resolved from a DSSE signature `keyid`.
+#[derive(Debug, Clone)]
+pub enum VerifyingKey {
+    /// Ed25519 public key
+    Ed25519(ed25519_dalek::VerifyingKey),
+    /// P-256 (secp256r1) public key
+    P256(p256::ecdsa::VerifyingKey),
+    /// secp256k1 public key
+    Secp256k1(k256::ecdsa::VerifyingKey),
+}
+
+impl VerifyingKey {
+    /// Resolves a `did:key` identifier into a public key.
+    ///
+    /// Any DID URL fragment (e.g. `did:key:z6Mk...#z6Mk...`) is ignored.
+    ///
+    /// # Arguments
+    /// * `keyid` - `did:key` identifier of the signer
+    ///
+    /// # Returns
+    /// * `Result<Self, SignatureError>` - Resolved public key, or error if the identifier can't be resolved
+    pub fn from_did_key(keyid: &str) -> Result<Self, SignatureError> {
+        let did = did_from_keyid(keyid);
+
+        let multibase_str = did.strip_prefix(DID_KEY_PREFIX).ok_or_else(|| {
+            SignatureError::UnresolvableKeyId(format!("'{keyid}' is not a did:key"))
+        })?;
+
+        // 'z' prefix means base58btc
+        let b58_str = multibase_str.strip_prefix('z').ok_or_else(|| {
+            SignatureError::UnresolvableKeyId(format!(
+                "'{keyid}' is not base58btc (z-prefix) multibase encoded"
+            ))
+        })?;
+
+        let decoded = bs58::decode(b58_str)
+            .into_vec()
+            .map_err(|e| SignatureError::UnresolvableKeyId(format!("'{keyid}': {e}")))?;
+
+        if decoded.len() < 2 {
+            return Err(SignatureError::UnresolvableKeyId(format!(
+                "'{keyid}' is too short to contain a multicodec prefix"
+            )));
+        }
+
+        // first 2 bytes are multicodec for key type
+        let (multicodec, pub_key_bytes) = decoded.split_at(2);
+
+        match [multicodec[0], multicodec[1]] {
+            ED25519_MULTICODEC => {
+                let bytes: &[u8; 32] = pub_key_bytes.try_into().map_err(|_| {
+                    SignatureError::MalformedKey(format!(
+                        "Ed25519 public key must be 32 bytes, got {}",
+                        pub_key_bytes.len()
+                    ))
+                })?;
+                let key = ed25519_dalek::VerifyingKey::from_bytes(bytes)
+                    .map_err(|e| SignatureError::MalformedKey(e.to_string()))?;
+
+                Ok(VerifyingKey::Ed25519(key))
+            }
+            P256_MULTICODEC => {
+                let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(pub_key_bytes)
+                    .map_err(|e| SignatureError::MalformedKey(e.to_string()))?;
+
+                Ok(VerifyingKey::P256(key))
+            }
+            SECP256K1_MULTICODEC => {
+                let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(pub_key_bytes)
+                    .map_err(|e| SignatureError::MalformedKey(e.to_string()))?;
+
+                Ok(VerifyingKey::Secp256k1(key))
+            }
+            [a, b] => Err(SignatureError::UnsupportedKeyType(format!(
+                "multicodec {a:x} {b:x}"
+            ))),
+        }
+    }
+
+    /// Verifies a signature over a message.
+    ///
+    /// ECDSA signatures (P-256, secp256k1) are accepted both as raw 64-byte `r || s`
+    /// and as ASN.1 DER. Ed25519 signatures must be the raw 64-byte encoding.
+    ///
+    /// # Arguments
+    /// * `msg` - Message bytes that were signed
+    /// * `sig` - Signature bytes
+    ///
+    /// # Returns
+    /// * `Result<(), SignatureError>` - Ok if the signature is valid, otherwise the reason it isn't
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> Result<(), SignatureError> {
+        match self {
+            VerifyingKey::Ed25519(key) => {
+                let sig = ed25519_dalek::Signature::from_slice(sig)
+                    .map_err(|e| SignatureError::MalformedSignature(e.to_string()))?;
+
+                key.verify(msg, &sig)
+                    .map_err(|_| SignatureError::InvalidSignature)
+            }
+            VerifyingKey::P256(key) => {
+                let sig = match sig.len() {
+                    64 => p256::ecdsa::Signature::from_slice(sig),
+                    _ => p256::ecdsa::Signature::from_der(sig),
+                }
+                .map_err(|e| SignatureError::MalformedSignature(e.to_string()))?;
+
+                key.verify(msg, &sig)
+                    .map_err(|_| SignatureError::InvalidSignature)
+            }
+            VerifyingKey::Secp256k1(key) => {
+                let sig = match sig.len() {
+                    64 => k256::ecdsa::Signature::from_slice(sig),
+                    _ => k256::ecdsa::Signature::from_der(sig),
+                }
+                .map_err(|e| SignatureError::MalformedSignature(e.to_string()))?;
+                // a high-S signature is a second valid encoding of a low-S one, accepting it
+                // would make envelopes malleable
+                if sig.normalize_s().is_some() {
+                    return Err(SignatureError::MalformedSignature(
+                        "secp256k1 signature has a high S value".to_owned(),
+                    ));
+                }
+
+                key.verify(msg, &sig)
+                    .map_err(|_| SignatureError::InvalidSignature)
+            }
+        }
+    }
+}
+
+/// Options controlling how DSSE envelopes are verified.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// Also accept signatures over the raw payload ([`SigningMode::Legacy`]),
+    /// as produced by `sign_dsse` before it signed the pre-authentication encoding
+    pub allow_legacy: bool,
+}
+
+/// Result of verifying a single signature within a DSSE envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureVerification {
+    /// Key identifier of the signature
+    pub keyid: String,
+    /// Signing mode the signature verified under, or `None` if it is invalid
+    pub mode: Option<SigningMode>,
+    /// Reason the signature failed to verify, or `None` if it is valid
+    pub error: Option<SignatureError>,
+}
+
+impl SignatureVerification {
+    /// Returns true if the signature verified successfully.
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Result of verifying every signature within a DSSE envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeVerification {
+    /// Per-signature results, in envelope order
+    pub signatures: Vec<SignatureVerification>,
+}
+
+impl EnvelopeVerification {
+    /// Returns true if the envelope has at least one signature and all of them are valid.
+    pub fn is_valid(&self) -> bool {
+        !self.signatures.is_empty() && self.signatures.iter().all(|s| s.is_valid())
+    }
+}
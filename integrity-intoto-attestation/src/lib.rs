@@ -4,20 +4,36 @@ pub mod models;
 /// Predicate types and handling for attestation claims.
 pub mod predicate;
 
+/// Registry of typed predicate parsers and validators.
+pub mod registry;
+
+/// SLSA Provenance v1 predicate.
+pub mod slsa;
+
 /// Statement structures for in-toto attestations.
 pub mod statement;
 
 /// Subject definitions for attested artifacts.
 pub mod subject;
 
+/// in-toto Test Result predicate.
+pub mod test_result;
+
+/// SLSA Verification Summary Attestation (VSA) predicate.
+pub mod vsa;
+
 use std::{collections::HashMap, sync::Arc};
 
 use anyhow::{anyhow, Result};
 use base64::engine::{general_purpose::STANDARD as BASE64, Engine};
-use did_key::CoreSign;
+/// Re-exported signature error type for convenience.
+pub use integrity_dsse::SignatureError;
+use integrity_dsse::{PayloadType, SigningMode, VerifyingKey};
 use integrity_signer::Signer;
 /// Re-exported predicate types for convenience.
 pub use predicate::{Predicate, PredicateType};
+/// Re-exported registry types for convenience.
+pub use registry::{PredicateRegistry, TypedPredicate};
 use serde::{Deserialize, Serialize};
 /// Re-exported statement type for convenience.
 pub use statement::Statement;
@@ -61,22 +77,15 @@ pub async fn sign_intoto_attestation(
         .map(|d| d.id)
         .ok_or_else(|| anyhow!("No DID Document for signer."))?;
 
-    let media_type = "application/vnd.in-toto+json";
-    let msg = format!(
-        "DSSEv1 {} {} {} {}",
-        media_type.len(),
-        media_type,
-        payload.len(),
-        String::from_utf8(payload.clone())?
-    );
+    let media_type = PayloadType::InTotoJson.to_string();
+    let msg = SigningMode::PaeV1.message(&media_type, &payload);
 
-    let sig = signer.sign(msg.as_bytes()).await?;
-    let sig = p256::ecdsa::Signature::from_slice(&sig)
-        .map_err(|e| anyhow!("Failed to parse signature: {e}"))?;
-    let sig = sig.to_der().as_bytes().to_vec();
+    let key = VerifyingKey::from_did_key(&keyid)?;
+    let sig = signer.sign(&msg).await?;
+    let sig = encode_signature(&key, &sig)?;
 
     let envelope = DsseEnvelope {
-        payload_type: media_type.to_owned(),
+        payload_type: media_type,
         payload: BASE64.encode(payload),
         signatures: vec![DsseSignature {
             keyid,
@@ -90,11 +99,14 @@ pub async fn sign_intoto_attestation(
 
 /// Verifies an in-toto attestation DSSE envelope.
 ///
+/// Signatures are verified over the DSSE pre-authentication encoding of the payload.
+///
 /// # Arguments
 /// * `envelope` - JSON string of DSSE envelope to verify
 ///
 /// # Returns
-/// * `Result<bool>` - True if all signatures are valid, false otherwise, or error if verification fails
+/// * `Result<bool>` - True if all signatures are valid, false otherwise, or error if the
+///   envelope is malformed or a signer's key can't be resolved (see [`SignatureError`])
 pub async fn verify_intoto_attestation(envelope: &str) -> Result<bool> {
     let envelope: DsseEnvelope = serde_json::from_str(envelope)?;
 
@@ -103,19 +115,45 @@ pub async fn verify_intoto_attestation(envelope: &str) -> Result<bool> {
     }
 
     let payload = BASE64.decode(envelope.payload)?;
+    let msg = SigningMode::PaeV1.message(&envelope.payload_type, &payload);
 
     for signature in envelope.signatures {
-        let did = did_key::PatchedKeyPair::try_from(signature.keyid.as_str()).expect("TODO");
+        let key = VerifyingKey::from_did_key(&signature.keyid)?;
         let sig = BASE64.decode(signature.sig)?;
 
-        if let Err(_err) = did.verify(&payload, &sig) {
-            return Ok(false);
+        match key.verify(&msg, &sig) {
+            Ok(()) => {}
+            Err(SignatureError::InvalidSignature | SignatureError::MalformedSignature(_)) => {
+                return Ok(false)
+            }
+            Err(e) => return Err(e.into()),
         }
     }
 
     Ok(true)
 }
 
+/// Encodes a raw 64-byte signer output for the signer's key type.
+///
+/// Ed25519 signatures are kept raw, ECDSA signatures are ASN.1 DER encoded.
+fn encode_signature(key: &VerifyingKey, sig: &[u8; 64]) -> Result<Vec<u8>, SignatureError> {
+    match key {
+        VerifyingKey::Ed25519(_) => Ok(sig.to_vec()),
+        VerifyingKey::P256(_) => {
+            let sig = p256::ecdsa::Signature::from_slice(sig)
+                .map_err(|e| SignatureError::MalformedSignature(e.to_string()))?;
+
+            Ok(sig.to_der().as_bytes().to_vec())
+        }
+        VerifyingKey::Secp256k1(_) => {
+            let sig = k256::ecdsa::Signature::from_slice(sig)
+                .map_err(|e| SignatureError::MalformedSignature(e.to_string()))?;
+
+            Ok(sig.to_der().as_bytes().to_vec())
+        }
+    }
+}
+
 /// Creates a digest map from a CID string, extracting hash information.
 ///
 /// # Arguments
@@ -142,3 +180,106 @@ pub fn digest_from_cid(cid_str: &str) -> Result<HashMap<String, String>> {
 
     Ok(digest)
 }
+
+#[cfg(test)]
+mod tests {
+    use integrity_signer::{Ed25519Signer, KeyType, P256Signer, Secp256k1Signer, SignerType};
+    use serde_json::Value;
+
+    use super::*;
+
+    fn signer_for(key_type: &KeyType) -> SignerType {
+        match key_type {
+            KeyType::ED25519 => SignerType::ED25519(Ed25519Signer::create().unwrap()),
+            KeyType::SECP256R1 => SignerType::P256(P256Signer::create().unwrap()),
+            KeyType::SECP256K1 => SignerType::SECP256K1(Secp256k1Signer::create().unwrap()),
+        }
+    }
+
+    fn key_types() -> Vec<KeyType> {
+        vec![KeyType::ED25519, KeyType::SECP256R1, KeyType::SECP256K1]
+    }
+
+    fn statement() -> Statement {
+        Statement {
+            subject: vec![Subject::new(
+                "model.safetensors",
+                HashMap::from([("sha256".to_owned(), "ab".repeat(32))]),
+            )],
+            predicate: Predicate {
+                predicate_type: PredicateType::Other("https://example.com/predicate/v1".to_owned()),
+                predicate: serde_json::json!({ "hello": "world" }),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn sign_and_verify_round_trip_for_every_key_type() {
+        for key_type in key_types() {
+            let signer = signer_for(&key_type);
+            let envelope = sign_intoto_attestation(statement(), Arc::new(signer))
+                .await
+                .unwrap();
+
+            assert!(
+                verify_intoto_attestation(&envelope).await.unwrap(),
+                "{key_type:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn signature_encoding_matches_key_type() {
+        for key_type in key_types() {
+            let signer = signer_for(&key_type);
+            let envelope = sign_intoto_attestation(statement(), Arc::new(signer))
+                .await
+                .unwrap();
+            let envelope: DsseEnvelope = serde_json::from_str(&envelope).unwrap();
+            let sig = BASE64.decode(&envelope.signatures[0].sig).unwrap();
+
+            match key_type {
+                KeyType::ED25519 => assert_eq!(sig.len(), 64),
+                // DER SEQUENCE tag
+                KeyType::SECP256R1 | KeyType::SECP256K1 => assert_eq!(sig[0], 0x30),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_tampered_payload() {
+        for key_type in key_types() {
+            let signer = signer_for(&key_type);
+            let envelope = sign_intoto_attestation(statement(), Arc::new(signer))
+                .await
+                .unwrap();
+            let mut envelope: Value = serde_json::from_str(&envelope).unwrap();
+            envelope["payload"] = Value::String(BASE64.encode(b"{}"));
+
+            assert!(
+                !verify_intoto_attestation(&envelope.to_string())
+                    .await
+                    .unwrap(),
+                "{key_type:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_returns_typed_error_for_unresolvable_keyid() {
+        let signer = signer_for(&KeyType::ED25519);
+        let envelope = sign_intoto_attestation(statement(), Arc::new(signer))
+            .await
+            .unwrap();
+        let mut envelope: Value = serde_json::from_str(&envelope).unwrap();
+        envelope["signatures"][0]["keyid"] = Value::String("not-a-did".to_owned());
+
+        let err = verify_intoto_attestation(&envelope.to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<SignatureError>(),
+            Some(SignatureError::UnresolvableKeyId(_))
+        ));
+    }
+}
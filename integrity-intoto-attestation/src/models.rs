@@ -31,6 +31,21 @@ pub struct Subject {
     pub name: String,
     /// Map of digest algorithm names to their digest values
     pub digest: HashMap<String, String>,
+    /// URI identifying the artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// URI the artifact can be downloaded from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_location: Option<String>,
+    /// Media type of the artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    /// Base64 encoded contents of the artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Additional arbitrary information about the artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
 }
 
 /// Represents a predicate (claim) in an in-toto statement.
@@ -45,3 +60,33 @@ pub struct Predicate {
     /// The predicate content as arbitrary JSON
     pub predicate: Value,
 }
+
+/// Serializable representation of an in-toto resource descriptor.
+///
+/// Describes an artifact or resource referenced by an attestation, such as a
+/// build dependency, by one or more of its URI, digests and content.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDescriptor {
+    /// URI identifying the resource
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// Map of digest algorithm names to their digest values
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub digest: HashMap<String, String>,
+    /// Machine-readable name of the resource
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// URI the resource can be downloaded from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_location: Option<String>,
+    /// Media type of the resource
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    /// Base64 encoded contents of the resource
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Additional arbitrary information about the resource
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
@@ -1,12 +1,16 @@
 use std::{convert::TryFrom, fmt, str::FromStr};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use serde_json::Value;
 
-use super::models;
+use super::{models, registry::TypedPredicate};
 
 const SPDX_PREDICATE_URI: &str = "https://spdx.dev/Document";
+const CYCLONEDX_PREDICATE_URI: &str = "https://cyclonedx.org/bom";
 const MODEL_SIGNING_SIGNATURE_PREDICATE_URI: &str = "https://model_signing/signature/v1.0";
+const SLSA_PROVENANCE_V1_PREDICATE_URI: &str = "https://slsa.dev/provenance/v1";
+const TEST_RESULT_PREDICATE_URI: &str = "https://in-toto.io/attestation/test-result/v0.1";
+const VERIFICATION_SUMMARY_V1_PREDICATE_URI: &str = "https://slsa.dev/verification_summary/v1";
 
 /// A predicate (claim) containing type information and data.
 ///
@@ -24,12 +28,20 @@ pub struct Predicate {
 ///
 /// Defines the schema and interpretation of predicate data,
 /// with built-in support for SPDX and extensibility for custom types.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PredicateType {
     /// SPDX document predicate
     Spdx,
+    /// CycloneDX BOM predicate
+    CycloneDx,
     /// Model signing signature predicate
     ModelSigningSignature,
+    /// SLSA Provenance v1 predicate, see [`crate::slsa::SlsaProvenanceV1`]
+    SlsaProvenanceV1,
+    /// in-toto test result predicate, see [`crate::test_result::TestResult`]
+    TestResult,
+    /// SLSA Verification Summary v1 predicate, see [`crate::vsa::VerificationSummary`]
+    VerificationSummaryV1,
     /// Any other custom predicate type
     Other(String),
 }
@@ -38,9 +50,15 @@ impl fmt::Display for PredicateType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PredicateType::Spdx => write!(f, "{}", SPDX_PREDICATE_URI),
+            PredicateType::CycloneDx => write!(f, "{}", CYCLONEDX_PREDICATE_URI),
             PredicateType::ModelSigningSignature => {
                 write!(f, "{}", MODEL_SIGNING_SIGNATURE_PREDICATE_URI)
             }
+            PredicateType::SlsaProvenanceV1 => write!(f, "{}", SLSA_PROVENANCE_V1_PREDICATE_URI),
+            PredicateType::TestResult => write!(f, "{}", TEST_RESULT_PREDICATE_URI),
+            PredicateType::VerificationSummaryV1 => {
+                write!(f, "{}", VERIFICATION_SUMMARY_V1_PREDICATE_URI)
+            }
             PredicateType::Other(s) => write!(f, "{}", s),
         }
     }
@@ -52,11 +70,53 @@ impl FromStr for PredicateType {
     fn from_str(s: &str) -> Result<Self> {
         match s {
             SPDX_PREDICATE_URI => Ok(PredicateType::Spdx),
+            CYCLONEDX_PREDICATE_URI => Ok(PredicateType::CycloneDx),
+            MODEL_SIGNING_SIGNATURE_PREDICATE_URI => Ok(PredicateType::ModelSigningSignature),
+            SLSA_PROVENANCE_V1_PREDICATE_URI => Ok(PredicateType::SlsaProvenanceV1),
+            TEST_RESULT_PREDICATE_URI => Ok(PredicateType::TestResult),
+            VERIFICATION_SUMMARY_V1_PREDICATE_URI => Ok(PredicateType::VerificationSummaryV1),
             _ => Ok(PredicateType::Other(s.to_owned())),
         }
     }
 }
 
+impl Predicate {
+    /// Creates a predicate from a typed predicate body.
+    ///
+    /// # Arguments
+    /// * `predicate` - Typed predicate body
+    ///
+    /// # Returns
+    /// * `Result<Self>` - Predicate, or error if the body fails validation or serialization
+    pub fn from_typed<T: TypedPredicate>(predicate: T) -> Result<Self> {
+        predicate.validate()?;
+
+        Ok(Self {
+            predicate_type: T::predicate_type(),
+            predicate: serde_json::to_value(predicate)?,
+        })
+    }
+
+    /// Parses the predicate body as a typed predicate.
+    ///
+    /// # Returns
+    /// * `Result<T>` - Typed predicate body, or error if the predicate type doesn't match
+    ///   or the body fails parsing or validation
+    pub fn parse<T: TypedPredicate>(&self) -> Result<T> {
+        let expected = T::predicate_type().to_string();
+        let actual = self.predicate_type.to_string();
+
+        if expected != actual {
+            bail!("Expected predicate type '{expected}', got '{actual}'.");
+        }
+
+        let predicate = serde_json::from_value::<T>(self.predicate.clone())?;
+        predicate.validate()?;
+
+        Ok(predicate)
+    }
+}
+
 impl TryFrom<models::Predicate> for Predicate {
     type Error = anyhow::Error;
 
@@ -90,3 +150,24 @@ impl From<Predicate> for models::Predicate {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predicate_types_round_trip_through_from_str() {
+        for predicate_type in [
+            PredicateType::Spdx,
+            PredicateType::CycloneDx,
+            PredicateType::ModelSigningSignature,
+            PredicateType::SlsaProvenanceV1,
+            PredicateType::TestResult,
+            PredicateType::VerificationSummaryV1,
+            PredicateType::Other("https://example.com/custom/v1".to_owned()),
+        ] {
+            let parsed = PredicateType::from_str(&predicate_type.to_string()).unwrap();
+            assert_eq!(parsed, predicate_type);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use anyhow::{anyhow, bail, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    predicate::{Predicate, PredicateType},
+    slsa::SlsaProvenanceV1,
+    test_result::TestResult,
+    vsa::VerificationSummary,
+};
+
+/// A predicate body with a known schema.
+///
+/// Implementors can be registered with a [`PredicateRegistry`] so that statements
+/// carrying their predicate type are checked by [`crate::Statement::validate`].
+pub trait TypedPredicate: Serialize + DeserializeOwned {
+    /// The predicate type this body is attested under.
+    fn predicate_type() -> PredicateType;
+
+    /// Checks constraints that can't be expressed by deserialization alone.
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+type Validator = Arc<dyn Fn(&Value) -> Result<()> + Send + Sync>;
+
+/// Registry of validators for predicate bodies, keyed by predicate type URI.
+///
+/// Validation is opt-in: statements parse with any predicate body, and are only checked
+/// against a registry passed to [`crate::Statement::validate`]. Predicates whose type has
+/// no registered validator are accepted as-is. The [`Default`] registry knows SPDX, CycloneDX, SLSA Provenance v1,
+/// SLSA Verification Summary v1, model signing and test result predicates.
+#[derive(Clone)]
+pub struct PredicateRegistry {
+    validators: HashMap<String, Validator>,
+}
+
+impl PredicateRegistry {
+    /// Creates a registry with no validators.
+    pub fn empty() -> Self {
+        Self {
+            validators: HashMap::new(),
+        }
+    }
+
+    /// Registers a typed predicate, validating bodies by parsing them as `T`.
+    pub fn register<T: TypedPredicate>(&mut self) {
+        self.register_validator(T::predicate_type(), |predicate| {
+            serde_json::from_value::<T>(predicate.clone())?.validate()
+        });
+    }
+
+    /// Registers a validator for a predicate type, replacing any existing one.
+    pub fn register_validator(
+        &mut self,
+        predicate_type: PredicateType,
+        validator: impl Fn(&Value) -> Result<()> + Send + Sync + 'static,
+    ) {
+        self.validators
+            .insert(predicate_type.to_string(), Arc::new(validator));
+    }
+
+    /// Returns true if a validator is registered for the predicate type.
+    pub fn is_registered(&self, predicate_type: &PredicateType) -> bool {
+        self.validators.contains_key(&predicate_type.to_string())
+    }
+
+    /// Validates a predicate body against the validator registered for its type.
+    ///
+    /// # Arguments
+    /// * `predicate` - Predicate to validate
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if valid or no validator is registered, otherwise the validation error
+    pub fn validate(&self, predicate: &Predicate) -> Result<()> {
+        let predicate_type = predicate.predicate_type.to_string();
+
+        match self.validators.get(&predicate_type) {
+            Some(validator) => validator(&predicate.predicate)
+                .map_err(|e| anyhow!("Invalid '{predicate_type}' predicate: {e}")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for PredicateRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        registry.register::<SlsaProvenanceV1>();
+        registry.register::<TestResult>();
+        registry.register::<VerificationSummary>();
+        registry.register_validator(PredicateType::Spdx, validate_spdx);
+        registry.register_validator(PredicateType::CycloneDx, validate_cyclonedx);
+        registry.register_validator(PredicateType::ModelSigningSignature, validate_model_signing);
+
+        registry
+    }
+}
+
+impl fmt::Debug for PredicateRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut types = self.validators.keys().collect::<Vec<_>>();
+        types.sort();
+
+        f.debug_struct("PredicateRegistry")
+            .field("predicate_types", &types)
+            .finish()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpdxDocumentHeader {
+    spdx_version: String,
+    #[serde(rename = "SPDXID")]
+    _spdx_id: String,
+}
+
+fn validate_spdx(predicate: &Value) -> Result<()> {
+    let header = SpdxDocumentHeader::deserialize(predicate)?;
+
+    if !header.spdx_version.starts_with("SPDX-") {
+        bail!("unexpected spdxVersion '{}'", header.spdx_version);
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CycloneDxBomHeader {
+    bom_format: String,
+    #[serde(rename = "specVersion")]
+    _spec_version: String,
+}
+
+fn validate_cyclonedx(predicate: &Value) -> Result<()> {
+    let header = CycloneDxBomHeader::deserialize(predicate)?;
+
+    if header.bom_format != "CycloneDX" {
+        bail!("unexpected bomFormat '{}'", header.bom_format);
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ModelSigningPredicate {
+    serialization: ModelSigningSerialization,
+    resources: Vec<ModelSigningResource>,
+}
+
+#[derive(Deserialize)]
+struct ModelSigningSerialization {
+    method: String,
+}
+
+#[derive(Deserialize)]
+struct ModelSigningResource {
+    algorithm: String,
+    digest: String,
+    name: String,
+}
+
+fn validate_model_signing(predicate: &Value) -> Result<()> {
+    let predicate = ModelSigningPredicate::deserialize(predicate)?;
+
+    if predicate.serialization.method.is_empty() {
+        bail!("serialization method is empty");
+    }
+
+    for resource in predicate.resources {
+        if resource.name.is_empty() || resource.algorithm.is_empty() {
+            bail!("resource is missing a name or algorithm");
+        }
+        hex::decode(&resource.digest)
+            .map_err(|e| anyhow!("resource '{}' digest: {e}", resource.name))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn predicate(predicate_type: PredicateType, predicate: Value) -> Predicate {
+        Predicate {
+            predicate_type,
+            predicate,
+        }
+    }
+
+    #[test]
+    fn default_registry_validates_builtin_predicates() {
+        let registry = PredicateRegistry::default();
+
+        let spdx = json!({ "spdxVersion": "SPDX-2.3", "SPDXID": "SPDXRef-DOCUMENT" });
+        assert!(registry
+            .validate(&predicate(PredicateType::Spdx, spdx))
+            .is_ok());
+        assert!(registry
+            .validate(&predicate(PredicateType::Spdx, json!({})))
+            .is_err());
+
+        let bom = json!({ "bomFormat": "CycloneDX", "specVersion": "1.5" });
+        assert!(registry
+            .validate(&predicate(PredicateType::CycloneDx, bom))
+            .is_ok());
+        let bom = json!({ "bomFormat": "SPDX", "specVersion": "1.5" });
+        assert!(registry
+            .validate(&predicate(PredicateType::CycloneDx, bom))
+            .is_err());
+
+        let test_result = json!({ "result": "PASSED", "configuration": [] });
+        assert!(registry
+            .validate(&predicate(PredicateType::TestResult, test_result))
+            .is_ok());
+        let test_result = json!({ "result": "MAYBE", "configuration": [] });
+        assert!(registry
+            .validate(&predicate(PredicateType::TestResult, test_result))
+            .is_err());
+
+        let model_signing = json!({
+            "serialization": { "method": "files", "hash_type": "blake3" },
+            "resources": [{ "algorithm": "blake3", "digest": "abcd", "name": "a.bin" }]
+        });
+        assert!(registry
+            .validate(&predicate(
+                PredicateType::ModelSigningSignature,
+                model_signing
+            ))
+            .is_ok());
+        let model_signing = json!({ "serialization": { "method": "files" } });
+        assert!(registry
+            .validate(&predicate(
+                PredicateType::ModelSigningSignature,
+                model_signing
+            ))
+            .is_err());
+
+        assert!(registry
+            .validate(&predicate(PredicateType::SlsaProvenanceV1, json!({})))
+            .is_err());
+    }
+
+    #[test]
+    fn unregistered_predicates_are_accepted() {
+        let registry = PredicateRegistry::empty();
+
+        assert!(registry
+            .validate(&predicate(PredicateType::Spdx, json!(null)))
+            .is_ok());
+    }
+
+    #[test]
+    fn custom_validators_can_be_registered() {
+        let custom = PredicateType::Other("https://example.com/custom/v1".to_owned());
+        let mut registry = PredicateRegistry::empty();
+        registry.register_validator(custom.clone(), |p| match p.get("ok") {
+            Some(Value::Bool(true)) => Ok(()),
+            _ => bail!("not ok"),
+        });
+
+        assert!(registry.is_registered(&custom));
+        assert!(registry
+            .validate(&predicate(custom.clone(), json!({ "ok": true })))
+            .is_ok());
+        assert!(registry
+            .validate(&predicate(custom, json!({ "ok": false })))
+            .is_err());
+    }
+}
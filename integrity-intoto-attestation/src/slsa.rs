@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use integrity_lineage_models::models::statements::{
+    computation_statement::ComputationStatement, StatementTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{
+    digest_from_cid,
+    models::ResourceDescriptor,
+    predicate::{Predicate, PredicateType},
+    registry::TypedPredicate,
+    statement::Statement,
+    subject::Subject,
+};
+
+/// Build type used for provenance derived from lineage computation statements.
+pub const COMPUTATION_BUILD_TYPE: &str =
+    "https://github.com/eqtylab/integrity/ComputationRegistration/v1";
+
+/// SLSA Provenance v1 predicate.
+///
+/// Describes how the subjects of a statement were produced: the inputs and
+/// parameters of the build, and who ran it and when.
+/// See <https://slsa.dev/spec/v1.0/provenance>.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlsaProvenanceV1 {
+    /// Inputs and parameters of the build
+    pub build_definition: BuildDefinition,
+    /// Details about the particular execution of the build
+    pub run_details: RunDetails,
+}
+
+/// The inputs that define a SLSA build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDefinition {
+    /// URI identifying the template for how the build was performed
+    pub build_type: String,
+    /// Parameters under the control of the build's requester
+    pub external_parameters: Value,
+    /// Parameters under the control of the builder
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal_parameters: Option<Value>,
+    /// Artifacts the build consumed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resolved_dependencies: Vec<ResourceDescriptor>,
+}
+
+/// Details about a particular execution of a SLSA build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunDetails {
+    /// The entity that executed the build
+    pub builder: Builder,
+    /// Metadata about the build invocation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BuildMetadata>,
+    /// Additional artifacts produced by the build that aren't subjects
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub byproducts: Vec<ResourceDescriptor>,
+}
+
+/// The entity that executed a SLSA build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Builder {
+    /// URI identifying the builder
+    pub id: String,
+    /// Map of builder component names to their versions
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub version: HashMap<String, String>,
+    /// Dependencies used by the builder itself
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub builder_dependencies: Vec<ResourceDescriptor>,
+}
+
+/// Metadata about a SLSA build invocation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildMetadata {
+    /// Identifier of this particular build invocation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invocation_id: Option<String>,
+    /// RFC 3339 timestamp of when the build started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_on: Option<String>,
+    /// RFC 3339 timestamp of when the build finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_on: Option<String>,
+}
+
+impl SlsaProvenanceV1 {
+    /// Creates a new SLSA provenance predicate.
+    pub fn new(build_definition: BuildDefinition, run_details: RunDetails) -> Self {
+        Self {
+            build_definition,
+            run_details,
+        }
+    }
+
+    /// Derives SLSA provenance from a lineage computation statement.
+    ///
+    /// The computation's inputs become resolved dependencies and the entity it was
+    /// executed on (or operated by) becomes the builder.
+    ///
+    /// # Arguments
+    /// * `statement` - Computation statement to derive provenance from
+    ///
+    /// # Returns
+    /// * `Result<Self>` - Provenance predicate, or error if an input isn't a valid CID
+    pub fn from_computation_statement(statement: &ComputationStatement) -> Result<Self> {
+        let external_parameters = match &statement.computation {
+            Some(computation) => json!({ "computation": computation }),
+            None => json!({}),
+        };
+
+        let resolved_dependencies = statement
+            .input
+            .to_vec_string()
+            .iter()
+            .map(|cid| resource_descriptor_from_cid(cid))
+            .collect::<Result<Vec<_>>>()?;
+
+        let builder_id = statement
+            .executed_on
+            .clone()
+            .unwrap_or_else(|| statement.operated_by.clone());
+
+        let build_definition = BuildDefinition::new(COMPUTATION_BUILD_TYPE, external_parameters)
+            .with_resolved_dependencies(resolved_dependencies);
+        let run_details = RunDetails::new(builder_id).with_metadata(
+            BuildMetadata::default()
+                .with_invocation_id(statement.get_id())
+                .with_finished_on(statement.timestamp.clone()),
+        );
+
+        Ok(Self::new(build_definition, run_details))
+    }
+}
+
+impl BuildDefinition {
+    /// Creates a new build definition.
+    pub fn new(build_type: impl Into<String>, external_parameters: Value) -> Self {
+        Self {
+            build_type: build_type.into(),
+            external_parameters,
+            internal_parameters: None,
+            resolved_dependencies: vec![],
+        }
+    }
+
+    /// Sets the builder-controlled parameters.
+    pub fn with_internal_parameters(mut self, internal_parameters: Value) -> Self {
+        self.internal_parameters = Some(internal_parameters);
+        self
+    }
+
+    /// Adds a resolved dependency.
+    pub fn with_resolved_dependency(mut self, dependency: ResourceDescriptor) -> Self {
+        self.resolved_dependencies.push(dependency);
+        self
+    }
+
+    /// Adds several resolved dependencies.
+    pub fn with_resolved_dependencies(
+        mut self,
+        dependencies: impl IntoIterator<Item = ResourceDescriptor>,
+    ) -> Self {
+        self.resolved_dependencies.extend(dependencies);
+        self
+    }
+}
+
+impl RunDetails {
+    /// Creates new run details for the builder with the given id.
+    pub fn new(builder_id: impl Into<String>) -> Self {
+        Self {
+            builder: Builder::new(builder_id),
+            metadata: None,
+            byproducts: vec![],
+        }
+    }
+
+    /// Replaces the builder.
+    pub fn with_builder(mut self, builder: Builder) -> Self {
+        self.builder = builder;
+        self
+    }
+
+    /// Sets the build invocation metadata.
+    pub fn with_metadata(mut self, metadata: BuildMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Adds a byproduct of the build.
+    pub fn with_byproduct(mut self, byproduct: ResourceDescriptor) -> Self {
+        self.byproducts.push(byproduct);
+        self
+    }
+}
+
+impl Builder {
+    /// Creates a new builder with the given id.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            version: HashMap::new(),
+            builder_dependencies: vec![],
+        }
+    }
+
+    /// Records the version of a builder component.
+    pub fn with_version(
+        mut self,
+        component: impl Into<String>,
+        version: impl Into<String>,
+    ) -> Self {
+        self.version.insert(component.into(), version.into());
+        self
+    }
+
+    /// Adds a dependency used by the builder itself.
+    pub fn with_builder_dependency(mut self, dependency: ResourceDescriptor) -> Self {
+        self.builder_dependencies.push(dependency);
+        self
+    }
+}
+
+impl BuildMetadata {
+    /// Sets the invocation identifier.
+    pub fn with_invocation_id(mut self, invocation_id: impl Into<String>) -> Self {
+        self.invocation_id = Some(invocation_id.into());
+        self
+    }
+
+    /// Sets the build start timestamp.
+    pub fn with_started_on(mut self, started_on: impl Into<String>) -> Self {
+        self.started_on = Some(started_on.into());
+        self
+    }
+
+    /// Sets the build finish timestamp.
+    pub fn with_finished_on(mut self, finished_on: impl Into<String>) -> Self {
+        self.finished_on = Some(finished_on.into());
+        self
+    }
+}
+
+impl TypedPredicate for SlsaProvenanceV1 {
+    fn predicate_type() -> PredicateType {
+        PredicateType::SlsaProvenanceV1
+    }
+}
+
+impl TryFrom<SlsaProvenanceV1> for Predicate {
+    type Error = anyhow::Error;
+
+    fn try_from(provenance: SlsaProvenanceV1) -> Result<Self> {
+        Predicate::from_typed(provenance)
+    }
+}
+
+impl TryFrom<&Predicate> for SlsaProvenanceV1 {
+    type Error = anyhow::Error;
+
+    fn try_from(predicate: &Predicate) -> Result<Self> {
+        predicate.parse()
+    }
+}
+
+/// Creates an in-toto statement carrying SLSA provenance for a lineage computation.
+///
+/// The computation's outputs become the statement's subjects, see
+/// [`SlsaProvenanceV1::from_computation_statement`] for the predicate.
+///
+/// # Arguments
+/// * `statement` - Computation statement to derive the attestation from
+///
+/// # Returns
+/// * `Result<Statement>` - in-toto statement, or error if an input or output isn't a valid CID
+pub fn provenance_statement_from_computation(
+    statement: &ComputationStatement,
+) -> Result<Statement> {
+    let subject = statement
+        .output
+        .to_vec_string()
+        .iter()
+        .map(|cid| {
+            let cid = strip_urn_cid(cid);
+
+            Ok(Subject::new(cid, cid_digest(cid)?).with_uri(format!("urn:cid:{cid}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let predicate = SlsaProvenanceV1::from_computation_statement(statement)?.try_into()?;
+
+    Ok(Statement { subject, predicate })
+}
+
+fn resource_descriptor_from_cid(cid: &str) -> Result<ResourceDescriptor> {
+    let cid = strip_urn_cid(cid);
+
+    Ok(ResourceDescriptor {
+        uri: Some(format!("urn:cid:{cid}")),
+        digest: cid_digest(cid)?,
+        ..Default::default()
+    })
+}
+
+/// Returns the digests of a CID, without the `cid` entry, which isn't a digest algorithm.
+/// The CID is kept in the descriptor's `uri`.
+fn cid_digest(cid: &str) -> Result<HashMap<String, String>> {
+    let mut digest = digest_from_cid(cid)?;
+    digest.remove("cid");
+    Ok(digest)
+}
+
+fn strip_urn_cid(cid: &str) -> &str {
+    cid.strip_prefix("urn:cid:").unwrap_or(cid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "urn:cid:bafkr4ia3wmrvedxwkjm6jfmtqy2bdcpi47hv5bni7twshohepck3gsgodi";
+    const OUTPUT: &str = "urn:cid:bafkr4ifqnz4knhvvgorsjl6hwhtmzfkfurebfslr3mwe2bqygcgu4bq3wi";
+    const COMPUTATION: &str = "urn:cid:bafkr4ifoun4lisqjjft75svkzewgwybr65arm5lc72hpzlgenqkfrcfanm";
+    const OPERATOR: &str = "did:key:z6Mkvt1grez4Avdvhqc196hTs6Lxb4qmu1NUdGk2An7QKqnT";
+
+    async fn computation() -> ComputationStatement {
+        ComputationStatement::create(
+            Some(COMPUTATION.to_owned()),
+            vec![INPUT.to_owned()],
+            vec![OUTPUT.to_owned()],
+            OPERATOR.to_owned(),
+            None,
+            OPERATOR.to_owned(),
+            Some("2024-06-27T14:36:35Z".to_owned()),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn provenance_from_computation_statement() {
+        let computation = computation().await;
+        let statement = provenance_statement_from_computation(&computation).unwrap();
+
+        assert_eq!(statement.subject.len(), 1);
+        assert_eq!(
+            statement.subject[0].name,
+            OUTPUT.strip_prefix("urn:cid:").unwrap()
+        );
+        assert_eq!(
+            statement.subject[0].digest.keys().collect::<Vec<_>>(),
+            vec!["blake3"]
+        );
+
+        let provenance = SlsaProvenanceV1::try_from(&statement.predicate).unwrap();
+        let dependencies = &provenance.build_definition.resolved_dependencies;
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].uri.as_deref(), Some(INPUT));
+        assert_eq!(
+            dependencies[0].digest.keys().collect::<Vec<_>>(),
+            vec!["blake3"]
+        );
+        assert_eq!(
+            provenance.build_definition.external_parameters["computation"],
+            COMPUTATION
+        );
+        assert_eq!(provenance.run_details.builder.id, OPERATOR);
+        assert_eq!(
+            provenance.run_details.metadata.unwrap().invocation_id,
+            Some(computation.get_id())
+        );
+    }
+
+    #[test]
+    fn provenance_serializes_with_slsa_field_names() {
+        let provenance = SlsaProvenanceV1::new(
+            BuildDefinition::new("https://example.com/build/v1", json!({ "ref": "main" }))
+                .with_resolved_dependency(ResourceDescriptor {
+                    uri: Some("git+https://example.com/repo".to_owned()),
+                    ..Default::default()
+                }),
+            RunDetails::new("https://example.com/builder")
+                .with_builder(Builder::new("https://example.com/builder").with_version("ci", "1"))
+                .with_metadata(BuildMetadata::default().with_started_on("2024-06-27T14:36:35Z")),
+        );
+
+        let predicate = Predicate::try_from(provenance.clone()).unwrap();
+        assert_eq!(
+            predicate.predicate,
+            json!({
+                "buildDefinition": {
+                    "buildType": "https://example.com/build/v1",
+                    "externalParameters": { "ref": "main" },
+                    "resolvedDependencies": [{ "uri": "git+https://example.com/repo" }]
+                },
+                "runDetails": {
+                    "builder": {
+                        "id": "https://example.com/builder",
+                        "version": { "ci": "1" }
+                    },
+                    "metadata": { "startedOn": "2024-06-27T14:36:35Z" }
+                }
+            })
+        );
+        assert_eq!(SlsaProvenanceV1::try_from(&predicate).unwrap(), provenance);
+    }
+
+    #[test]
+    fn provenance_rejects_other_predicate_types() {
+        let predicate = Predicate {
+            predicate_type: PredicateType::Spdx,
+            predicate: json!({}),
+        };
+
+        assert!(SlsaProvenanceV1::try_from(&predicate).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
 
-use super::{models, predicate::Predicate, subject::Subject};
+use super::{models, predicate::Predicate, registry::PredicateRegistry, subject::Subject};
 
 /// An in-toto statement representing an attestation about software artifacts.
 ///
@@ -25,8 +25,22 @@ impl Statement {
 
         Ok(s)
     }
+
+    /// Validates the predicate against the validator registered for its type.
+    ///
+    /// # Arguments
+    /// * `registry` - Predicate validators, e.g. [`PredicateRegistry::default`]
+    ///
+    /// # Returns
+    /// * `Result<()>` - Ok if valid or no validator is registered, otherwise the validation error
+    pub fn validate(&self, registry: &PredicateRegistry) -> Result<()> {
+        registry.validate(&self.predicate)
+    }
 }
 
+/// Parses a statement, keeping its predicate body as opaque JSON.
+///
+/// The predicate is not validated; use [`Statement::validate`] to check it.
 impl TryFrom<models::Statement> for Statement {
     type Error = anyhow::Error;
 
@@ -59,3 +73,54 @@ impl From<Statement> for models::Statement {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::predicate::PredicateType;
+
+    fn statement_json(predicate_type: &str, predicate: serde_json::Value) -> models::Statement {
+        serde_json::from_value(json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{ "name": "a.bin", "digest": { "sha256": "00" } }],
+            "predicateType": predicate_type,
+            "predicate": predicate,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn try_from_keeps_model_signing_predicate_type() {
+        let statement = statement_json(
+            "https://model_signing/signature/v1.0",
+            json!({ "serialization": { "method": "files" }, "resources": [] }),
+        );
+
+        let statement = Statement::try_from(statement).unwrap();
+        assert_eq!(
+            statement.predicate.predicate_type,
+            PredicateType::ModelSigningSignature
+        );
+    }
+
+    #[test]
+    fn try_from_keeps_predicates_as_json() {
+        let statement = statement_json("https://spdx.dev/Document", json!({ "name": "doc" }));
+        let statement = Statement::try_from(statement).unwrap();
+        assert_eq!(statement.predicate.predicate, json!({ "name": "doc" }));
+
+        let statement = statement_json("https://example.com/unknown/v1", json!([1, 2]));
+        assert!(Statement::try_from(statement).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_invalid_registered_predicate() {
+        let statement = statement_json("https://spdx.dev/Document", json!({ "name": "doc" }));
+        let statement = Statement::try_from(statement).unwrap();
+
+        assert!(statement.validate(&PredicateRegistry::default()).is_err());
+        assert!(statement.validate(&PredicateRegistry::empty()).is_ok());
+    }
+}
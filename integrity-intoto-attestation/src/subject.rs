@@ -1,35 +1,302 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use base64::engine::{general_purpose::STANDARD as BASE64, Engine};
+use integrity_cid::iroh::{get_files_for_dir_cid, CidIgnoreConfig, HashingConfig};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
 
 use super::models;
 
+/// Size of the chunks files are read in while hashed.
+const READ_CHUNK_SIZE: usize = 1024 * 1024;
+
 /// A subject (artifact) that attestations are made about.
 ///
 /// Subjects identify specific files, packages, or other artifacts
-/// with their names and cryptographic digests for integrity verification.
-#[derive(Debug, Clone)]
+/// with their names and cryptographic digests for integrity verification,
+/// following the in-toto v1 resource descriptor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Subject {
     /// Name or identifier of the artifact
     pub name: String,
     /// Map of digest algorithm names to their digest values
     pub digest: HashMap<String, String>,
+    /// URI identifying the artifact
+    pub uri: Option<String>,
+    /// URI the artifact can be downloaded from
+    pub download_location: Option<String>,
+    /// Media type of the artifact
+    pub media_type: Option<String>,
+    /// Raw contents of the artifact
+    pub content: Option<Vec<u8>>,
+    /// Additional arbitrary information about the artifact
+    pub annotations: Option<Value>,
+}
+
+impl Subject {
+    /// Creates a new subject from a name and digest map.
+    pub fn new(name: impl Into<String>, digest: HashMap<String, String>) -> Self {
+        Self {
+            name: name.into(),
+            digest,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the URI identifying the artifact.
+    pub fn with_uri(mut self, uri: impl Into<String>) -> Self {
+        self.uri = Some(uri.into());
+        self
+    }
+
+    /// Sets the URI the artifact can be downloaded from.
+    pub fn with_download_location(mut self, download_location: impl Into<String>) -> Self {
+        self.download_location = Some(download_location.into());
+        self
+    }
+
+    /// Sets the media type of the artifact.
+    pub fn with_media_type(mut self, media_type: impl Into<String>) -> Self {
+        self.media_type = Some(media_type.into());
+        self
+    }
+
+    /// Sets the raw contents of the artifact.
+    pub fn with_content(mut self, content: Vec<u8>) -> Self {
+        self.content = Some(content);
+        self
+    }
+
+    /// Sets additional arbitrary information about the artifact.
+    pub fn with_annotations(mut self, annotations: Value) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
+
+    /// Creates a subject for a file, named after the file, with `blake3` and `sha256` digests.
+    ///
+    /// # Arguments
+    /// * `path` - File path to create a subject for
+    /// * `hash_config` - Hashing options, `multithread` hashes BLAKE3 on several threads
+    ///
+    /// # Returns
+    /// * `Result<Self>` - The subject, or error if the path isn't a file or can't be read
+    pub async fn from_file(path: impl Into<PathBuf>, hash_config: HashingConfig) -> Result<Self> {
+        let path = path.into();
+        let name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("The provided path ({:?}) has no file name", path.display()))?
+            .to_string_lossy()
+            .into_owned();
+
+        if !path.is_file() {
+            return Err(anyhow!(
+                "The provided path ({:?}) is not a file",
+                path.display()
+            ));
+        }
+
+        Ok(Self::new(name, file_digest(path, hash_config).await?))
+    }
+
+    /// Creates one subject per file in a directory, named by path relative to the directory,
+    /// each with `blake3` and `sha256` digests.
+    ///
+    /// # Arguments
+    /// * `path` - Directory path to create subjects for
+    /// * `hash_config` - Hashing options, `multithread` hashes BLAKE3 on several threads
+    /// * `cid_ignore` - Filter for which files in the directory are included
+    ///
+    /// # Returns
+    /// * `Result<Vec<Self>>` - Subjects ordered by path, or error if the directory can't be read
+    pub async fn from_dir(
+        path: impl Into<PathBuf>,
+        hash_config: HashingConfig,
+        cid_ignore: CidIgnoreConfig,
+    ) -> Result<Vec<Self>> {
+        let files = get_files_for_dir_cid(path, cid_ignore)?;
+
+        let mut subjects = vec![];
+        for (name, path) in files {
+            let digest = file_digest(path, hash_config.clone()).await?;
+            subjects.push(Self::new(name, digest));
+        }
+
+        Ok(subjects)
+    }
+}
+
+/// Computes a file's `blake3` and `sha256` digests.
+///
+/// Both are computed in one pass over the file on the blocking thread pool, so they always
+/// describe the same contents and the async runtime isn't stalled.
+async fn file_digest(path: PathBuf, hash_config: HashingConfig) -> Result<HashMap<String, String>> {
+    tokio::task::spawn_blocking(move || hash_file(&path, hash_config.multithread)).await?
+}
+
+fn hash_file(path: &Path, multithread: bool) -> Result<HashMap<String, String>> {
+    let mut file = File::open(path)?;
+    let mut blake3 = blake3::Hasher::new();
+    let mut sha256 = Sha256::new();
+
+    let mut buf = vec![0; READ_CHUNK_SIZE];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        if multithread {
+            blake3.update_rayon(&buf[..n]);
+        } else {
+            blake3.update(&buf[..n]);
+        }
+        sha256.update(&buf[..n]);
+    }
+
+    Ok(HashMap::from([
+        ("blake3".to_owned(), blake3.finalize().to_hex().to_string()),
+        ("sha256".to_owned(), hex::encode(sha256.finalize())),
+    ]))
 }
 
 impl TryFrom<models::Subject> for Subject {
     type Error = anyhow::Error;
 
     fn try_from(subject: models::Subject) -> Result<Self> {
-        let models::Subject { name, digest } = subject;
+        let models::Subject {
+            name,
+            digest,
+            uri,
+            download_location,
+            media_type,
+            content,
+            annotations,
+        } = subject;
 
-        Ok(Self { name, digest })
+        let content = content.map(|c| BASE64.decode(c)).transpose()?;
+
+        Ok(Self {
+            name,
+            digest,
+            uri,
+            download_location,
+            media_type,
+            content,
+            annotations,
+        })
     }
 }
 
 impl From<Subject> for models::Subject {
     fn from(subject: Subject) -> Self {
-        let Subject { name, digest } = subject;
+        let Subject {
+            name,
+            digest,
+            uri,
+            download_location,
+            media_type,
+            content,
+            annotations,
+        } = subject;
+
+        let content = content.map(|c| BASE64.encode(c));
+
+        Self {
+            name,
+            digest,
+            uri,
+            download_location,
+            media_type,
+            content,
+            annotations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    const ABC_BLAKE3: &str = "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85";
+
+    fn fixture_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../fixtures/iroh-collection")
+    }
+
+    #[tokio::test]
+    async fn subject_from_file_has_blake3_and_sha256_digests() {
+        let subject = Subject::from_file(fixture_dir().join("abc.txt"), HashingConfig::default())
+            .await
+            .unwrap();
+
+        assert_eq!(subject.name, "abc.txt");
+        assert_eq!(
+            subject.digest,
+            HashMap::from([
+                ("blake3".to_owned(), ABC_BLAKE3.to_owned()),
+                ("sha256".to_owned(), ABC_SHA256.to_owned()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn subjects_from_dir_are_ordered_by_path() {
+        let subjects = Subject::from_dir(
+            fixture_dir(),
+            HashingConfig::default(),
+            CidIgnoreConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            subjects.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            vec!["abc.txt", "def.txt"]
+        );
+        assert_eq!(subjects[0].digest["sha256"], ABC_SHA256);
+    }
+
+    #[test]
+    fn subject_round_trips_resource_descriptor_fields() {
+        let subject = Subject::new(
+            "model.safetensors",
+            HashMap::from([("sha256".to_owned(), ABC_SHA256.to_owned())]),
+        )
+        .with_uri("https://example.com/model.safetensors")
+        .with_download_location("https://example.com/download/model.safetensors")
+        .with_media_type("application/octet-stream")
+        .with_content(b"abc".to_vec())
+        .with_annotations(serde_json::json!({ "license": "MIT" }));
+
+        let model = models::Subject::from(subject.clone());
+        let json = serde_json::to_value(&model).unwrap();
+        assert_eq!(
+            json["downloadLocation"],
+            "https://example.com/download/model.safetensors"
+        );
+        assert_eq!(json["mediaType"], "application/octet-stream");
+        assert_eq!(json["content"], "YWJj");
+
+        let model: models::Subject = serde_json::from_value(json).unwrap();
+        assert_eq!(Subject::try_from(model).unwrap(), subject);
+    }
+
+    #[test]
+    fn minimal_subject_serializes_name_and_digest_only() {
+        let subject = Subject::new("a", HashMap::from([("sha256".to_owned(), "00".to_owned())]));
 
-        Self { name, digest }
+        assert_eq!(
+            serde_json::to_value(models::Subject::from(subject)).unwrap(),
+            serde_json::json!({ "name": "a", "digest": { "sha256": "00" } })
+        );
     }
 }
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use super::{models::ResourceDescriptor, predicate::PredicateType, registry::TypedPredicate};
+
+/// in-toto Test Result v0.1 predicate.
+///
+/// Records the outcome of running a test suite against the subjects.
+/// See <https://github.com/in-toto/attestation/blob/main/spec/predicates/test-result.md>.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestResult {
+    /// Overall outcome of the tests
+    pub result: TestOutcome,
+    /// Configuration used to run the tests
+    pub configuration: Vec<ResourceDescriptor>,
+    /// URI of the test run, e.g. a CI job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Names of tests that passed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub passed_tests: Vec<String>,
+    /// Names of tests that passed with warnings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warned_tests: Vec<String>,
+    /// Names of tests that failed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_tests: Vec<String>,
+}
+
+/// Overall outcome of a test run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TestOutcome {
+    /// All tests passed
+    Passed,
+    /// All tests passed, some with warnings
+    Warned,
+    /// At least one test failed
+    Failed,
+}
+
+impl TypedPredicate for TestResult {
+    fn predicate_type() -> PredicateType {
+        PredicateType::TestResult
+    }
+}
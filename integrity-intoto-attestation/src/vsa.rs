@@ -0,0 +1,445 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use chrono::{SecondsFormat, Utc};
+use integrity_dsse::{Envelope, EnvelopeVerification};
+use integrity_signer::Signer;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{
+    models::{self, ResourceDescriptor},
+    predicate::{Predicate, PredicateType},
+    registry::TypedPredicate,
+    sign_intoto_attestation,
+    statement::Statement,
+    subject::Subject,
+};
+
+const VC_MEDIA_TYPE: &str = "application/vc+ld+json";
+const DSSE_MEDIA_TYPE: &str = "application/vnd.dsse.envelope.v1+json";
+
+/// SLSA Verification Summary Attestation (VSA) v1 predicate.
+///
+/// Records that a verifier checked an artifact against a policy, so consumers
+/// can trust the summary instead of repeating the verification.
+/// See <https://slsa.dev/spec/v1.0/verification_summary>.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationSummary {
+    /// The entity that performed the verification
+    pub verifier: Verifier,
+    /// RFC 3339 timestamp of when the verification happened
+    pub time_verified: String,
+    /// URI identifying the verified artifact
+    pub resource_uri: String,
+    /// Policy the artifact was verified against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<ResourceDescriptor>,
+    /// Attestations that were verified
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_attestations: Vec<ResourceDescriptor>,
+    /// Overall outcome of the verification
+    pub verification_result: VerificationResult,
+    /// SLSA levels (or other properties) the artifact was verified to meet
+    #[serde(default)]
+    pub verified_levels: Vec<String>,
+    /// Number of transitive dependencies verified at each level
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub dependency_levels: HashMap<String, u64>,
+    /// Version of the SLSA specification used for the levels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slsa_version: Option<String>,
+}
+
+/// The entity that performed a verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Verifier {
+    /// URI (e.g. a DID) identifying the verifier
+    pub id: String,
+    /// Map of verifier component names to their versions
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub version: HashMap<String, String>,
+}
+
+/// Overall outcome of a verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum VerificationResult {
+    /// The artifact passed verification
+    Passed,
+    /// The artifact failed verification
+    Failed,
+}
+
+impl VerificationSummary {
+    /// Creates a new verification summary, verified now.
+    pub fn new(
+        verifier: Verifier,
+        resource_uri: impl Into<String>,
+        verification_result: VerificationResult,
+    ) -> Self {
+        Self {
+            verifier,
+            time_verified: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            resource_uri: resource_uri.into(),
+            policy: None,
+            input_attestations: vec![],
+            verification_result,
+            verified_levels: vec![],
+            dependency_levels: HashMap::new(),
+            slsa_version: None,
+        }
+    }
+
+    /// Sets the verification timestamp.
+    pub fn with_time_verified(mut self, time_verified: impl Into<String>) -> Self {
+        self.time_verified = time_verified.into();
+        self
+    }
+
+    /// Sets the policy the artifact was verified against.
+    pub fn with_policy(mut self, policy: ResourceDescriptor) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Adds an attestation that was verified.
+    pub fn with_input_attestation(mut self, input_attestation: ResourceDescriptor) -> Self {
+        self.input_attestations.push(input_attestation);
+        self
+    }
+
+    /// Adds a level the artifact was verified to meet.
+    pub fn with_verified_level(mut self, level: impl Into<String>) -> Self {
+        self.verified_levels.push(level.into());
+        self
+    }
+
+    /// Records the number of transitive dependencies verified at a level.
+    pub fn with_dependency_level(mut self, level: impl Into<String>, count: u64) -> Self {
+        self.dependency_levels.insert(level.into(), count);
+        self
+    }
+
+    /// Sets the version of the SLSA specification used for the levels.
+    pub fn with_slsa_version(mut self, slsa_version: impl Into<String>) -> Self {
+        self.slsa_version = Some(slsa_version.into());
+        self
+    }
+}
+
+impl Verifier {
+    /// Creates a new verifier with the given id.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            version: HashMap::new(),
+        }
+    }
+
+    /// Records the version of a verifier component.
+    pub fn with_version(
+        mut self,
+        component: impl Into<String>,
+        version: impl Into<String>,
+    ) -> Self {
+        self.version.insert(component.into(), version.into());
+        self
+    }
+}
+
+impl TypedPredicate for VerificationSummary {
+    fn predicate_type() -> PredicateType {
+        PredicateType::VerificationSummaryV1
+    }
+}
+
+impl From<bool> for VerificationResult {
+    fn from(passed: bool) -> Self {
+        if passed {
+            VerificationResult::Passed
+        } else {
+            VerificationResult::Failed
+        }
+    }
+}
+
+/// Outcome of one of the verify functions, ready to be summarized in a VSA.
+#[derive(Debug, Clone)]
+pub struct VerificationOutcome {
+    /// Artifacts that were verified
+    pub subject: Vec<Subject>,
+    /// The attestation or credential that was verified
+    pub input_attestation: ResourceDescriptor,
+    /// Whether verification passed
+    pub result: VerificationResult,
+}
+
+impl VerificationOutcome {
+    /// Creates an outcome from the result of `integrity_vc::verify_vc`.
+    ///
+    /// The credential itself is the verified artifact.
+    ///
+    /// # Arguments
+    /// * `vc_json` - JSON string of the verified credential
+    /// * `result` - Result returned by `verify_vc`
+    pub fn from_vc_result(vc_json: &str, result: &Result<String>) -> Self {
+        let descriptor = descriptor_for(vc_json.as_bytes(), VC_MEDIA_TYPE);
+        let subject =
+            Subject::new("credential", descriptor.digest.clone()).with_media_type(VC_MEDIA_TYPE);
+
+        Self {
+            subject: vec![subject],
+            input_attestation: descriptor,
+            result: result.is_ok().into(),
+        }
+    }
+
+    /// Creates an outcome from the result of [`crate::verify_intoto_attestation`].
+    ///
+    /// The subjects of the attested statement are the verified artifacts.
+    ///
+    /// # Arguments
+    /// * `envelope` - JSON string of the verified DSSE envelope
+    /// * `result` - Result returned by `verify_intoto_attestation`
+    ///
+    /// # Returns
+    /// * `Result<Self>` - Outcome, or error if the envelope doesn't carry an in-toto statement
+    pub fn from_intoto_result(envelope: &str, result: &Result<bool>) -> Result<Self> {
+        let payload = Envelope::try_from_json_string(envelope)?.payload;
+        let statement: models::Statement = serde_json::from_slice(&payload)?;
+        let subject = statement
+            .subject
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            subject,
+            input_attestation: descriptor_for(envelope.as_bytes(), DSSE_MEDIA_TYPE),
+            result: matches!(result, Ok(true)).into(),
+        })
+    }
+
+    /// Creates an outcome from a DSSE envelope verification, e.g. `integrity_dsse::verify_dsse`.
+    ///
+    /// The envelope payload is the verified artifact.
+    ///
+    /// # Arguments
+    /// * `envelope` - JSON string of the verified DSSE envelope
+    /// * `verification` - Verification result for the envelope
+    ///
+    /// # Returns
+    /// * `Result<Self>` - Outcome, or error if the envelope can't be parsed
+    pub fn from_dsse_verification(
+        envelope: &str,
+        verification: &EnvelopeVerification,
+    ) -> Result<Self> {
+        let parsed = Envelope::try_from_json_string(envelope)?;
+        let payload = descriptor_for(&parsed.payload, &parsed.payload_type.to_string());
+        let subject = Subject::new("payload", payload.digest)
+            .with_media_type(parsed.payload_type.to_string());
+
+        Ok(Self {
+            subject: vec![subject],
+            input_attestation: descriptor_for(envelope.as_bytes(), DSSE_MEDIA_TYPE),
+            result: verification.is_valid().into(),
+        })
+    }
+
+    /// Builds an in-toto statement carrying a VSA for this outcome.
+    ///
+    /// # Arguments
+    /// * `verifier` - The entity that performed the verification
+    /// * `resource_uri` - URI identifying the verified artifact
+    /// * `policy` - Policy the artifact was verified against, if any
+    ///
+    /// # Returns
+    /// * `Result<Statement>` - in-toto statement, or error if the predicate can't be serialized
+    pub fn into_statement(
+        self,
+        verifier: Verifier,
+        resource_uri: impl Into<String>,
+        policy: Option<ResourceDescriptor>,
+    ) -> Result<Statement> {
+        let mut summary = VerificationSummary::new(verifier, resource_uri, self.result)
+            .with_input_attestation(self.input_attestation);
+        summary.policy = policy;
+
+        Ok(Statement {
+            subject: self.subject,
+            predicate: Predicate::from_typed(summary)?,
+        })
+    }
+}
+
+/// Creates and signs a VSA for a verification outcome.
+///
+/// # Arguments
+/// * `outcome` - Outcome of a verify function
+/// * `verifier` - The entity that performed the verification
+/// * `resource_uri` - URI identifying the verified artifact
+/// * `policy` - Policy the artifact was verified against, if any
+/// * `signer` - Signer implementation used to sign the VSA
+///
+/// # Returns
+/// * `Result<String>` - JSON string of the signed DSSE envelope, or error if signing fails
+pub async fn sign_verification_summary(
+    outcome: VerificationOutcome,
+    verifier: Verifier,
+    resource_uri: impl Into<String>,
+    policy: Option<ResourceDescriptor>,
+    signer: Arc<dyn Signer>,
+) -> Result<String> {
+    let statement = outcome.into_statement(verifier, resource_uri, policy)?;
+
+    sign_intoto_attestation(statement, signer).await
+}
+
+fn descriptor_for(bytes: &[u8], media_type: &str) -> ResourceDescriptor {
+    ResourceDescriptor {
+        digest: HashMap::from([("sha256".to_owned(), hex::encode(Sha256::digest(bytes)))]),
+        media_type: Some(media_type.to_owned()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use integrity_dsse::{sign_dsse, verify_dsse, PayloadType};
+    use integrity_signer::{Ed25519Signer, SignerType};
+
+    use super::*;
+    use crate::{verify_intoto_attestation, Predicate};
+
+    fn signer() -> Arc<dyn Signer> {
+        Arc::new(SignerType::ED25519(Ed25519Signer::create().unwrap()))
+    }
+
+    async fn verifier_id(signer: &Arc<dyn Signer>) -> String {
+        signer.get_did_doc().await.unwrap().unwrap().id
+    }
+
+    async fn parse_vsa(envelope: &str) -> (Statement, VerificationSummary) {
+        assert!(verify_intoto_attestation(envelope).await.unwrap());
+        let payload = Envelope::try_from_json_string(envelope).unwrap().payload;
+        let statement: models::Statement = serde_json::from_slice(&payload).unwrap();
+        let statement = Statement::try_from(statement).unwrap();
+        let summary = statement.predicate.parse::<VerificationSummary>().unwrap();
+
+        (statement, summary)
+    }
+
+    #[tokio::test]
+    async fn vsa_for_verified_intoto_attestation() {
+        let signer = signer();
+        let attestation = sign_intoto_attestation(
+            Statement {
+                subject: vec![Subject::new(
+                    "model.safetensors",
+                    HashMap::from([("sha256".to_owned(), "ab".repeat(32))]),
+                )],
+                predicate: Predicate {
+                    predicate_type: PredicateType::Other("https://example.com/p/v1".to_owned()),
+                    predicate: serde_json::json!({}),
+                },
+            },
+            signer.clone(),
+        )
+        .await
+        .unwrap();
+
+        let result = verify_intoto_attestation(&attestation).await;
+        let outcome = VerificationOutcome::from_intoto_result(&attestation, &result).unwrap();
+        let policy = ResourceDescriptor {
+            uri: Some("https://example.com/policy/v1".to_owned()),
+            ..Default::default()
+        };
+        let vsa = sign_verification_summary(
+            outcome,
+            Verifier::new(verifier_id(&signer).await),
+            "https://example.com/model.safetensors",
+            Some(policy.clone()),
+            signer.clone(),
+        )
+        .await
+        .unwrap();
+
+        let (statement, summary) = parse_vsa(&vsa).await;
+        assert_eq!(statement.subject[0].name, "model.safetensors");
+        assert_eq!(summary.verification_result, VerificationResult::Passed);
+        assert_eq!(summary.policy, Some(policy));
+        assert_eq!(summary.verifier.id, verifier_id(&signer).await);
+        assert_eq!(
+            summary.input_attestations[0].digest["sha256"],
+            hex::encode(Sha256::digest(attestation.as_bytes()))
+        );
+    }
+
+    #[tokio::test]
+    async fn vsa_records_failed_dsse_verification() {
+        let signer = signer();
+        let mut envelope = sign_dsse(b"payload".to_vec(), PayloadType::InTotoJson, signer.clone())
+            .await
+            .unwrap();
+        envelope.payload = b"tampered".to_vec();
+        let envelope = envelope.into_json_string().unwrap();
+
+        let verification = verify_dsse(&envelope).await.unwrap();
+        let outcome =
+            VerificationOutcome::from_dsse_verification(&envelope, &verification).unwrap();
+        let vsa = sign_verification_summary(
+            outcome,
+            Verifier::new(verifier_id(&signer).await),
+            "urn:example:payload",
+            None,
+            signer,
+        )
+        .await
+        .unwrap();
+
+        let (statement, summary) = parse_vsa(&vsa).await;
+        assert_eq!(summary.verification_result, VerificationResult::Failed);
+        assert_eq!(
+            statement.subject[0].digest["sha256"],
+            hex::encode(Sha256::digest(b"tampered"))
+        );
+    }
+
+    #[test]
+    fn vsa_for_vc_result() {
+        let vc = r#"{"id":"urn:uuid:1"}"#;
+        let passed = VerificationOutcome::from_vc_result(vc, &Ok("ok".to_owned()));
+        let failed = VerificationOutcome::from_vc_result(vc, &Err(anyhow::anyhow!("bad proof")));
+
+        assert_eq!(passed.result, VerificationResult::Passed);
+        assert_eq!(failed.result, VerificationResult::Failed);
+        assert_eq!(passed.subject[0].digest, passed.input_attestation.digest);
+    }
+
+    #[test]
+    fn vsa_serializes_with_slsa_field_names() {
+        let summary = VerificationSummary::new(
+            Verifier::new("https://example.com/verifier"),
+            "https://example.com/artifact",
+            VerificationResult::Passed,
+        )
+        .with_time_verified("2024-06-27T14:36:35Z")
+        .with_verified_level("SLSA_BUILD_LEVEL_3")
+        .with_slsa_version("1.0");
+
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "verifier": { "id": "https://example.com/verifier" },
+                "timeVerified": "2024-06-27T14:36:35Z",
+                "resourceUri": "https://example.com/artifact",
+                "verificationResult": "PASSED",
+                "verifiedLevels": ["SLSA_BUILD_LEVEL_3"],
+                "slsaVersion": "1.0"
+            })
+        );
+    }
+}
@@ -25,4 +25,7 @@ pub struct Signature {
     pub keyid: String,
     /// The base64-encoded signature bytes
     pub sig: String,
+    /// Base64-encoded DER RFC 3161 timestamp tokens over the signature bytes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timestamps: Vec<String>,
 }
@@ -0,0 +1,267 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Result};
+use cid::{multihash::MultihashGeneric, Cid};
+use futures::TryStreamExt;
+use integrity_blob::{BlobStore, ListOptions};
+use integrity_cid::collection::hashmap_for_iroh_collection;
+
+use crate::{
+    cid::{
+        multicodec::{BLAKE3_HASHSEQ, RAW_BINARY},
+        multihash::BLAKE3,
+        strip_urn_cid,
+    },
+    models::statements::{Statement, StatementTrait},
+};
+
+/// Options for [`collect_garbage`].
+#[derive(Debug, Clone, Default)]
+pub struct GcOptions {
+    /// CIDs that are never collected, in any text form, with or without the `urn:cid:`
+    /// prefix
+    pub pinned: HashSet<String>,
+    /// Report unreachable blobs without deleting them
+    pub dry_run: bool,
+    /// Maximum number of concurrent deletes, defaults to the store's batch limit
+    pub concurrency_limit: Option<usize>,
+}
+
+/// Outcome of a garbage collection run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcReport {
+    /// CIDs reachable from the roots
+    pub reachable: HashSet<String>,
+    /// Unreachable blobs that were kept because they are pinned, sorted
+    pub pinned: Vec<String>,
+    /// Unreachable blobs that were deleted, or would be in a dry run, sorted
+    pub collected: Vec<String>,
+}
+
+/// Computes the CIDs reachable from a set of root statements.
+///
+/// A statement reaches its own CID and its `referenced_cids`. An iroh collection also
+/// reaches its meta blob and every file blob it lists. References that aren't CIDs, e.g.
+/// DIDs, are ignored.
+///
+/// # Arguments
+/// * `statements` - Root statements, e.g. `manifest.statements.values()` for a manifest
+/// * `blob_store` - Store holding the iroh collections to expand
+///
+/// # Returns
+/// * `Result<HashSet<String>>` - The reachable CIDs in canonical form, or error if a
+///   reachable collection can't be read, as the blobs it lists would be unaccounted for
+pub async fn reachable_cids<'a>(
+    statements: impl IntoIterator<Item = &'a Statement>,
+    blob_store: Arc<dyn BlobStore + Send + Sync>,
+) -> Result<HashSet<String>> {
+    let mut pending = statements
+        .into_iter()
+        .flat_map(|statement| {
+            let mut cids = statement.referenced_cids();
+            cids.push(statement.get_id());
+            cids
+        })
+        .map(|cid| strip_urn_cid(&cid).to_owned())
+        .collect::<VecDeque<_>>();
+
+    let mut reachable = HashSet::new();
+    while let Some(cid) = pending.pop_front() {
+        let Ok(parsed) = Cid::try_from(cid.as_str()) else {
+            continue;
+        };
+        // stores list CIDs in their canonical form, whatever form they were referenced in
+        let cid = parsed.to_string();
+        if !reachable.insert(cid.clone()) || parsed.codec() != BLAKE3_HASHSEQ {
+            continue;
+        }
+
+        let collection_blob = blob_store
+            .get(&cid)
+            .await?
+            .ok_or_else(|| anyhow!("Iroh collection '{cid}' was not found in blob store"))?;
+        if let Some(meta_hash) = collection_blob.get(0..32) {
+            let multihash = MultihashGeneric::<64>::wrap(BLAKE3, meta_hash)?;
+            pending.push_back(Cid::new_v1(RAW_BINARY, multihash).to_string());
+        }
+
+        let files = hashmap_for_iroh_collection(&cid, blob_store.clone()).await?;
+        pending.extend(files.into_values());
+    }
+
+    Ok(reachable)
+}
+
+/// Deletes every blob in the store that isn't reachable from the root statements.
+///
+/// Blobs listed in `options.pinned` are never deleted. With `options.dry_run` the store
+/// is left untouched and the report lists what would be deleted. Nothing is deleted if a
+/// reachable iroh collection can't be read.
+///
+/// The store is listed before the reachability walk and only blobs in that listing can be
+/// collected, so blobs put while the collection runs are kept.
+///
+/// # Arguments
+/// * `statements` - Root statements, e.g. `manifest.statements.values()` for a manifest
+/// * `blob_store` - Store to collect
+/// * `options` - Pinned CIDs, dry run and delete concurrency
+///
+/// # Returns
+/// * `Result<GcReport>` - The reachable set and the collected blobs
+pub async fn collect_garbage<'a>(
+    statements: impl IntoIterator<Item = &'a Statement>,
+    blob_store: Arc<dyn BlobStore + Send + Sync>,
+    options: &GcOptions,
+) -> Result<GcReport> {
+    let stored = blob_store
+        .list(ListOptions::default())
+        .try_collect::<Vec<_>>()
+        .await?;
+    let reachable = reachable_cids(statements, blob_store.clone()).await?;
+    let pinned_cids = options
+        .pinned
+        .iter()
+        .filter_map(|cid| Cid::try_from(strip_urn_cid(cid)).ok())
+        .map(|cid| cid.to_string())
+        .collect::<HashSet<_>>();
+
+    let mut pinned = vec![];
+    let mut collected = vec![];
+    for cid in stored {
+        if reachable.contains(&cid) {
+            continue;
+        }
+        if pinned_cids.contains(cid.as_str()) {
+            pinned.push(cid);
+        } else {
+            collected.push(cid);
+        }
+    }
+
+    if !options.dry_run && !collected.is_empty() {
+        log::debug!("Deleting {} unreachable blobs.", collected.len());
+        blob_store
+            .delete_many(collected.clone(), options.concurrency_limit)
+            .await?;
+    }
+
+    Ok(GcReport {
+        reachable,
+        pinned,
+        collected,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use integrity_blob::LocalFs;
+    use integrity_cid::iroh::{compute_dir_cid, CidIgnoreConfig, HashingConfig};
+
+    use super::*;
+    use crate::models::statements::DataStatement;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("integrity-gc-{name}-{}", std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn collects_unreachable_blobs() {
+        let model = temp_dir("model");
+        fs::create_dir_all(&model).unwrap();
+        fs::write(model.join("config.json"), b"{}").unwrap();
+        fs::write(model.join("weights.bin"), b"weights").unwrap();
+
+        let mut store = LocalFs::new(temp_dir("store"));
+        store.init().await.unwrap();
+
+        let dir_cid = compute_dir_cid(&model, HashingConfig::default(), CidIgnoreConfig::default())
+            .await
+            .unwrap();
+        let collection = store
+            .put(dir_cid.collection.blob.to_vec(), BLAKE3_HASHSEQ, None)
+            .await
+            .unwrap();
+        let meta = store
+            .put(dir_cid.meta.blob.to_vec(), RAW_BINARY, None)
+            .await
+            .unwrap();
+        let mut files = vec![];
+        for name in ["config.json", "weights.bin"] {
+            let blob = fs::read(model.join(name)).unwrap();
+            files.push(store.put(blob, RAW_BINARY, None).await.unwrap());
+        }
+        let referenced = store
+            .put(b"dataset".to_vec(), RAW_BINARY, None)
+            .await
+            .unwrap();
+        let orphan = store
+            .put(b"orphan".to_vec(), RAW_BINARY, None)
+            .await
+            .unwrap();
+        let pinned = store
+            .put(b"pinned".to_vec(), RAW_BINARY, None)
+            .await
+            .unwrap();
+
+        // references and pins in a non-canonical text form still match the stored blobs
+        let base58 = |cid: &str| {
+            Cid::try_from(cid)
+                .unwrap()
+                .to_string_of_base(cid::multibase::Base::Base58Btc)
+                .unwrap()
+        };
+        let statement = Statement::DataRegistration(
+            DataStatement::create(
+                vec![format!("urn:cid:{collection}"), base58(&referenced)],
+                "did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp".to_owned(),
+                None,
+            )
+            .await
+            .unwrap(),
+        );
+
+        let blob_store: Arc<dyn BlobStore + Send + Sync> = Arc::new(store);
+        let mut options = GcOptions {
+            pinned: HashSet::from([format!("urn:cid:{}", base58(&pinned))]),
+            dry_run: true,
+            concurrency_limit: None,
+        };
+
+        let report = collect_garbage([&statement], blob_store.clone(), &options)
+            .await
+            .unwrap();
+        for cid in [&collection, &meta, &referenced, &files[0], &files[1]] {
+            assert!(report.reachable.contains(cid), "{cid}");
+        }
+        assert_eq!(report.collected, vec![orphan.clone()]);
+        assert_eq!(report.pinned, vec![pinned.clone()]);
+        assert!(blob_store.exists(&orphan).await.unwrap());
+
+        options.dry_run = false;
+        let report = collect_garbage([&statement], blob_store.clone(), &options)
+            .await
+            .unwrap();
+        assert_eq!(report.collected, vec![orphan.clone()]);
+        assert!(!blob_store.exists(&orphan).await.unwrap());
+        assert!(blob_store.exists(&pinned).await.unwrap());
+        assert!(blob_store.exists(&files[1]).await.unwrap());
+
+        // an unreadable collection fails the run rather than orphaning its files
+        blob_store.delete(&collection).await.unwrap();
+        let err = collect_garbage([&statement], blob_store.clone(), &options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(&collection), "{err}");
+        for cid in [&meta, &files[0], &files[1], &referenced] {
+            assert!(blob_store.exists(cid).await.unwrap(), "{cid}");
+        }
+
+        fs::remove_dir_all(&model).unwrap();
+        fs::remove_dir_all(temp_dir("store")).unwrap();
+    }
+}
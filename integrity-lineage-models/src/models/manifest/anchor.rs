@@ -1,3 +1,7 @@
+use anyhow::Result;
+use base64::engine::{general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Utc};
+use integrity_sigstore::{trust::TrustedRoot, TimestampToken};
 use serde::{Deserialize, Serialize};
 
 /// Represents an anchor point for lineage statements on external systems
@@ -12,6 +16,18 @@ pub struct Anchor {
     pub locations: Vec<Location>,
 }
 
+impl Anchor {
+    /// Returns the bytes anchored by timestamp-based locations.
+    ///
+    /// This is the JCS canonical JSON encoding of the anchored statements.
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>>` - Canonical statements bytes, or error if serialization fails
+    pub fn anchored_data(&self) -> Result<Vec<u8>> {
+        Ok(serde_jcs::to_vec(&self.statements)?)
+    }
+}
+
 /// Defines what type of data is being anchored
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(tag = "type")]
@@ -32,6 +48,9 @@ pub enum Location {
     /// Hedera Token Service location
     #[schema(value_type = HtsLocation)]
     Hts(HtsLocation),
+    /// RFC 3161 timestamp authority location
+    #[schema(value_type = Rfc3161Location)]
+    Rfc3161(Rfc3161Location),
 }
 
 /// Hedera Consensus Service location details
@@ -74,5 +93,86 @@ pub struct HtsLocation {
     pub urls: Vec<String>,
 }
 
+/// RFC 3161 timestamp authority location details
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Rfc3161Location {
+    /// URL of the timestamp authority that issued the token
+    pub tsa_url: String,
+    /// Base64-encoded DER timestamp token over the anchor's [`Anchor::anchored_data`]
+    pub token: String,
+    /// Generation time asserted by the token, RFC 3339 formatted
+    pub gen_time: String,
+}
+
+impl Rfc3161Location {
+    /// Creates a location from a timestamp token issued by the authority at `tsa_url`.
+    pub fn new(tsa_url: impl Into<String>, token: &TimestampToken) -> Self {
+        Self {
+            tsa_url: tsa_url.into(),
+            token: BASE64.encode(token.as_der()),
+            gen_time: token.gen_time().to_rfc3339(),
+        }
+    }
+
+    /// Decodes the timestamp token.
+    ///
+    /// # Returns
+    /// * `Result<TimestampToken>` - Parsed token, or error if it isn't valid base64 DER
+    pub fn token(&self) -> Result<TimestampToken> {
+        TimestampToken::from_der(&BASE64.decode(&self.token)?)
+    }
+
+    /// Verifies the timestamp token covers `data` and was issued by a trusted authority.
+    ///
+    /// # Arguments
+    /// * `data` - Anchored bytes, see [`Anchor::anchored_data`]
+    /// * `trusted_root` - Trusted root listing the timestamp authorities
+    ///
+    /// # Returns
+    /// * `Result<DateTime<Utc>>` - Verified generation time, or error if verification fails
+    pub fn verify(&self, data: &[u8], trusted_root: &TrustedRoot) -> Result<DateTime<Utc>> {
+        self.token()?.verify(data, trusted_root)
+    }
+}
+
 /// CAIP (Chain Agnostic Improvement Proposal) identifier for blockchain networks
 pub type CaipIdentifier = String;
+
+#[cfg(test)]
+mod tests {
+    use integrity_sigstore::testing::{TimestampAuthority, TIMESTAMP_TIME};
+
+    use super::*;
+
+    #[test]
+    fn rfc3161_location_round_trips_and_verifies() {
+        let tsa = TimestampAuthority::new();
+        let mut anchor = Anchor {
+            statements: vec![
+                "urn:cid:bafkr4ibthuzk3zug7ghmx63yjqaiu6rx4hhfdv3453j5bodskgw57bx2ya".to_owned(),
+            ],
+            payload: Payload::StatementId,
+            locations: vec![],
+        };
+        let data = anchor.anchored_data().unwrap();
+        let token = tsa.issue(&data);
+        anchor
+            .locations
+            .push(Location::Rfc3161(Rfc3161Location::new(
+                "https://tsa.example.com",
+                &token,
+            )));
+
+        let json = serde_json::to_value(&anchor).unwrap();
+        assert_eq!(json["locations"][0]["type"], "Rfc3161");
+        let anchor: Anchor = serde_json::from_value(json).unwrap();
+
+        let Location::Rfc3161(location) = &anchor.locations[0] else {
+            panic!("expected an RFC 3161 location");
+        };
+        let time = location.verify(&data, &tsa.trusted_root()).unwrap();
+        assert_eq!(time.timestamp(), TIMESTAMP_TIME);
+        assert!(location.verify(b"other", &tsa.trusted_root()).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 /// DSSE envelope models for lineage data
 pub mod dsse;
+/// Reachability-based garbage collection of blob stores
+pub mod gc;
 /// Manifest models for packaging statements and metadata
 pub mod manifest;
 /// Statement types for different lineage events
@@ -87,4 +87,47 @@ impl SigstoreBundleStatement {
 
         Ok(statement)
     }
+
+    /// Decodes the Sigstore bundle stored in this statement.
+    ///
+    /// # Returns
+    ///
+    /// The stored bundle, or error if it isn't valid base64 encoded bundle JSON.
+    pub fn decode_sigstore_bundle(&self) -> Result<SigstoreBundle> {
+        let sigstore_bundle = BASE64.decode(&self.sigstore_bundle)?;
+
+        SigstoreBundle::from_json(std::str::from_utf8(&sigstore_bundle)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stored_sigstore_bundle_can_be_decoded() {
+        let sigstore_bundle = SigstoreBundle::from_json(
+            r#"{
+                "mediaType": "application/vnd.dev.sigstore.bundle.v0.3+json",
+                "verificationMaterial": { "publicKey": { "hint": "abcd" }, "tlogEntries": [] },
+                "dsseEnvelope": {
+                    "payload": "e30=",
+                    "payloadType": "application/vnd.in-toto+json",
+                    "signatures": [{ "sig": "AAEC", "keyid": "" }]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let statement = SigstoreBundleStatement::create(
+            "urn:cid:bafkr4ia3wmrvedxwkjm6jfmtqy2bdcpi47hv5bni7twshohepck3gsgodi".to_owned(),
+            &sigstore_bundle,
+            "did:key:z6Mkvt1grez4Avdvhqc196hTs6Lxb4qmu1NUdGk2An7QKqnT".to_owned(),
+            Some("2024-06-27T14:36:35Z".to_owned()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(statement.decode_sigstore_bundle().unwrap(), sigstore_bundle);
+    }
 }
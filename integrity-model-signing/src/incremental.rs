@@ -0,0 +1,506 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::UNIX_EPOCH,
+};
+
+use anyhow::{anyhow, Result};
+use integrity_blob::BlobStore;
+use integrity_intoto_attestation as intoto_attestation;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    directory_hashes, model_signing_statement, root_digest,
+    serialization::{file_resources, model_files, path_order, resource_file},
+    sorted_by_path, DirectoryInfo, ModelSerialization, ModelSigningManifest,
+    ModelSigningPreviousVersion,
+};
+
+/// The previously signed version of a model.
+pub enum PreviousModel {
+    /// The manifest of the previous statement
+    Manifest(ModelSigningManifest),
+    /// An Iroh collection CID with its associated blob store, hashed with BLAKE3
+    IrohCollectionCidAndBlobStore(String, Arc<dyn BlobStore + Send + Sync>),
+}
+
+/// Per-file digests of a local model directory, keyed by relative path.
+///
+/// A cached entry is reused while the file's size and modification time are unchanged,
+/// so a cache should only ever be used with the directory it was filled from. It can be
+/// persisted between runs with [`HashCache::load`] and [`HashCache::save`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashCache {
+    files: BTreeMap<String, CachedFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedFile {
+    size: u64,
+    modified_nanos: u64,
+    hash_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shard_size: Option<u64>,
+    resources: Vec<(String, String)>,
+}
+
+impl HashCache {
+    /// Reads a cache file, returning an empty cache if it doesn't exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the JSON cache file.
+    ///
+    /// # Returns
+    ///
+    /// The cache, or error if the file exists but can't be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> Result<HashCache> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(HashCache::default());
+        }
+
+        let cache = serde_json::from_slice(&fs::read(path)?)
+            .map_err(|e| anyhow!("Failed to parse hash cache {}: {e}", path.display()))?;
+
+        Ok(cache)
+    }
+
+    /// Writes the cache to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+
+        Ok(())
+    }
+
+    /// Returns the number of cached files.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns whether no files are cached.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    fn get(
+        &self,
+        name: &str,
+        size: u64,
+        modified_nanos: u64,
+        serialization: ModelSerialization,
+    ) -> Option<Vec<(String, [u8; 32])>> {
+        let cached = self.files.get(name)?;
+        if cached.size != size
+            || cached.modified_nanos != modified_nanos
+            || cached.hash_type != serialization.hash_type()
+            || cached.shard_size != serialization.shard_size()
+        {
+            return None;
+        }
+
+        cached
+            .resources
+            .iter()
+            .map(|(name, digest)| Some((name.clone(), decode_digest(digest).ok()?)))
+            .collect()
+    }
+}
+
+/// Files that changed between a previous model version and the current directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelDiff {
+    /// Files that are new in the directory
+    pub added: Vec<String>,
+    /// Files of the previous version that are no longer in the directory
+    pub removed: Vec<String>,
+    /// Files whose contents changed
+    pub modified: Vec<String>,
+}
+
+impl ModelDiff {
+    /// Returns whether the directory matches the previous version.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// A re-signed model statement with the changes since the previous version.
+#[derive(Debug, Clone)]
+pub struct IncrementalStatement {
+    /// The new statement, referencing the previous version in its manifest
+    pub statement: intoto_attestation::Statement,
+    /// Changes since the previous version
+    pub diff: ModelDiff,
+    /// Files that were hashed because the cache had no valid entry for them
+    pub rehashed: Vec<String>,
+}
+
+/// Creates a model signing statement for a local directory that was signed before.
+///
+/// The serialization, `ignore_paths` and `allow_symlinks` of a previous manifest are kept;
+/// a previous iroh collection implies BLAKE3 file digests with nothing ignored. Only files
+/// whose size or modification time changed since they were cached are hashed again, and
+/// `cache` is updated to match the directory. The new manifest records the previous
+/// version's root digest, and its collection CID when there is one.
+///
+/// # Arguments
+///
+/// * `name` - The name of the model being signed.
+/// * `directory` - The local model directory.
+/// * `previous` - The previously signed version of the model.
+/// * `cache` - Per-file digests from earlier runs over the same directory.
+///
+/// # Returns
+///
+/// The new statement with the files added, removed and modified since the previous version,
+/// or error if the previous version can't be read or the directory can't be hashed.
+pub async fn create_incremental_model_signing_intoto_statement(
+    name: String,
+    directory: impl Into<PathBuf>,
+    previous: PreviousModel,
+    cache: &mut HashCache,
+) -> Result<IncrementalStatement> {
+    let directory = directory.into();
+
+    let (serialization, manifest_serialization, previous_resources, collection_cid) = match previous
+    {
+        PreviousModel::Manifest(manifest) => {
+            let serialization = ModelSerialization::from_manifest(&manifest.serialization)?;
+            let resources = manifest
+                .resources
+                .iter()
+                .map(|resource| Ok((resource.name.clone(), decode_digest(&resource.digest)?)))
+                .collect::<Result<Vec<_>>>()?;
+
+            (serialization, manifest.serialization, resources, None)
+        }
+        PreviousModel::IrohCollectionCidAndBlobStore(collection_cid, blob_store) => {
+            let resources = sorted_by_path(
+                directory_hashes(
+                    DirectoryInfo::IrohCollectionCidAndBlobStore(
+                        collection_cid.clone(),
+                        blob_store,
+                    ),
+                    false,
+                    &[],
+                )
+                .await?,
+            );
+            let serialization = ModelSerialization::Blake3Files;
+
+            (
+                serialization,
+                serialization.manifest_serialization(false, vec![]),
+                resources,
+                Some(collection_cid),
+            )
+        }
+    };
+
+    let mut files = model_files(
+        &directory,
+        manifest_serialization.allow_symlinks,
+        &manifest_serialization.ignore_paths,
+    )?;
+    files.sort_by(|(a, _), (b, _)| path_order(a, b));
+
+    let mut current = BTreeMap::new();
+    let mut resources = vec![];
+    let mut rehashed = vec![];
+    let mut files_cache = BTreeMap::new();
+    for (name, path) in files {
+        let metadata = fs::metadata(&path)?;
+        let size = metadata.len();
+        let modified_nanos = u64::try_from(
+            metadata
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos(),
+        )?;
+
+        let file_resources = match cache.get(&name, size, modified_nanos, serialization) {
+            Some(file_resources) => file_resources,
+            None => {
+                rehashed.push(name.clone());
+                file_resources(&name, &path, serialization).await?
+            }
+        };
+
+        files_cache.insert(
+            name.clone(),
+            CachedFile {
+                size,
+                modified_nanos,
+                hash_type: serialization.hash_type().to_owned(),
+                shard_size: serialization.shard_size(),
+                resources: file_resources
+                    .iter()
+                    .map(|(name, digest)| (name.clone(), hex::encode(digest)))
+                    .collect(),
+            },
+        );
+        current.insert(
+            name,
+            file_resources
+                .iter()
+                .map(|(_, digest)| *digest)
+                .collect::<Vec<_>>(),
+        );
+        resources.extend(file_resources);
+    }
+    cache.files = files_cache;
+
+    let mut previous_files = BTreeMap::<_, Vec<_>>::new();
+    for (name, digest) in &previous_resources {
+        previous_files
+            .entry(resource_file(name, serialization).to_owned())
+            .or_default()
+            .push(*digest);
+    }
+
+    let mut diff = ModelDiff::default();
+    for (name, digests) in &current {
+        match previous_files.get(name) {
+            None => diff.added.push(name.clone()),
+            Some(previous_digests) if previous_digests != digests => {
+                diff.modified.push(name.clone())
+            }
+            _ => {}
+        }
+    }
+    diff.removed = previous_files
+        .keys()
+        .filter(|name| !current.contains_key(*name))
+        .cloned()
+        .collect();
+    for names in [&mut diff.added, &mut diff.removed, &mut diff.modified] {
+        names.sort_by(|a, b| path_order(a, b));
+    }
+
+    let previous = ModelSigningPreviousVersion {
+        digest: HashMap::from([(
+            "sha256".to_owned(),
+            root_digest(previous_resources.iter().map(|(_, digest)| digest)),
+        )]),
+        collection_cid,
+    };
+
+    let statement =
+        model_signing_statement(name, &resources, manifest_serialization, Some(previous))?;
+
+    Ok(IncrementalStatement {
+        statement,
+        diff,
+        rehashed,
+    })
+}
+
+fn decode_digest(digest: &str) -> Result<[u8; 32]> {
+    hex::decode(digest)?
+        .try_into()
+        .map_err(|_| anyhow!("Resource digest '{digest}' isn't 32 bytes."))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use integrity_blob::InMemoryStore;
+    use integrity_cid::iroh::{compute_dir_cid, CidIgnoreConfig, HashingConfig};
+    use serde_json::Value;
+
+    use super::*;
+    use crate::create_model_signing_intoto_statement_with_serialization;
+
+    fn model_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "integrity-model-signing-incremental-{name}-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(dir.join("weights")).unwrap();
+        fs::write(dir.join("config.json"), b"{}").unwrap();
+        fs::write(dir.join("weights/part-0.bin"), [1u8; 2500]).unwrap();
+        fs::write(dir.join("weights/part-1.bin"), [2u8; 1200]).unwrap();
+        dir
+    }
+
+    async fn statement_json(
+        dir: &Path,
+        serialization: ModelSerialization,
+    ) -> (Value, ModelSigningManifest) {
+        let statement = create_model_signing_intoto_statement_with_serialization(
+            "model".to_owned(),
+            DirectoryInfo::LocalDirectory(dir.to_path_buf()),
+            serialization,
+            false,
+            vec![],
+        )
+        .await
+        .unwrap();
+        let manifest = serde_json::from_value(statement.predicate.predicate.clone()).unwrap();
+
+        (
+            serde_json::from_str(&statement.into_json_string().unwrap()).unwrap(),
+            manifest,
+        )
+    }
+
+    #[tokio::test]
+    async fn resigns_only_changed_files() {
+        let serialization = ModelSerialization::Sha256Shards { shard_size: 1000 };
+        let dir = model_dir("shards");
+        let (previous_statement, previous) = statement_json(&dir, serialization).await;
+
+        let mut cache = HashCache::default();
+        let first = create_incremental_model_signing_intoto_statement(
+            "model".to_owned(),
+            &dir,
+            PreviousModel::Manifest(previous.clone()),
+            &mut cache,
+        )
+        .await
+        .unwrap();
+        assert!(first.diff.is_empty(), "{:?}", first.diff);
+        assert_eq!(first.rehashed.len(), 3);
+        assert_eq!(cache.len(), 3);
+
+        fs::write(dir.join("config.json"), b"{\"layers\": 2}").unwrap();
+        fs::remove_file(dir.join("weights/part-1.bin")).unwrap();
+        fs::write(dir.join("weights/part-2.bin"), [3u8; 10]).unwrap();
+
+        let cache_file = dir.with_extension("cache.json");
+        cache.save(&cache_file).unwrap();
+        let mut cache = HashCache::load(&cache_file).unwrap();
+        fs::remove_file(&cache_file).unwrap();
+
+        let second = create_incremental_model_signing_intoto_statement(
+            "model".to_owned(),
+            &dir,
+            PreviousModel::Manifest(previous),
+            &mut cache,
+        )
+        .await
+        .unwrap();
+        let (expected, _) = statement_json(&dir, serialization).await;
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(second.rehashed, vec!["config.json", "weights/part-2.bin"]);
+        assert_eq!(
+            second.diff,
+            ModelDiff {
+                added: vec!["weights/part-2.bin".to_owned()],
+                removed: vec!["weights/part-1.bin".to_owned()],
+                modified: vec!["config.json".to_owned()],
+            }
+        );
+        assert_eq!(cache.len(), 3);
+
+        let statement: Value =
+            serde_json::from_str(&second.statement.into_json_string().unwrap()).unwrap();
+        assert_eq!(statement["subject"], expected["subject"]);
+        assert_eq!(
+            statement["predicate"]["resources"],
+            expected["predicate"]["resources"]
+        );
+        assert_eq!(
+            statement["predicate"]["previous"]["digest"]["sha256"],
+            previous_statement["subject"][0]["digest"]["sha256"]
+        );
+    }
+
+    #[tokio::test]
+    async fn resigns_blake3_directories_like_full_signing() {
+        let dir = model_dir("blake3");
+        fs::write(dir.join(".hidden"), b"hidden").unwrap();
+        fs::create_dir_all(dir.join("empty")).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.join("config.json"), dir.join("link.json")).unwrap();
+        let (_, previous) = statement_json(&dir, ModelSerialization::Blake3Files).await;
+
+        let resigned = create_incremental_model_signing_intoto_statement(
+            "model".to_owned(),
+            &dir,
+            PreviousModel::Manifest(previous),
+            &mut HashCache::default(),
+        )
+        .await
+        .unwrap();
+        assert!(resigned.diff.is_empty(), "{:?}", resigned.diff);
+
+        fs::write(dir.join("weights/part-1.bin"), [7u8; 1200]).unwrap();
+        let (expected, _) = statement_json(&dir, ModelSerialization::Blake3Files).await;
+        let resigned = create_incremental_model_signing_intoto_statement(
+            "model".to_owned(),
+            &dir,
+            PreviousModel::Manifest(
+                serde_json::from_value(resigned.statement.predicate.predicate.clone()).unwrap(),
+            ),
+            &mut HashCache::default(),
+        )
+        .await
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(resigned.diff.modified, vec!["weights/part-1.bin"]);
+        let statement: Value =
+            serde_json::from_str(&resigned.statement.into_json_string().unwrap()).unwrap();
+        assert_eq!(statement["subject"], expected["subject"]);
+        assert_eq!(
+            statement["predicate"]["resources"],
+            expected["predicate"]["resources"]
+        );
+    }
+
+    #[tokio::test]
+    async fn resigns_from_iroh_collection() {
+        let dir = model_dir("iroh");
+        let cid_ignore = CidIgnoreConfig {
+            include_hidden_files: true,
+            gitignore: false,
+            include_symlinks: false,
+        };
+        let dir_cid = compute_dir_cid(&dir, HashingConfig::default(), cid_ignore)
+            .await
+            .unwrap();
+        let blob_store = InMemoryStore::from(HashMap::from([
+            (
+                dir_cid.collection.cid.clone(),
+                dir_cid.collection.blob.to_vec(),
+            ),
+            (dir_cid.meta.cid.clone(), dir_cid.meta.blob.to_vec()),
+        ]));
+        let (previous_statement, _) = statement_json(&dir, ModelSerialization::Blake3Files).await;
+
+        fs::write(dir.join("weights/part-0.bin"), [9u8; 2500]).unwrap();
+
+        let resigned = create_incremental_model_signing_intoto_statement(
+            "model".to_owned(),
+            &dir,
+            PreviousModel::IrohCollectionCidAndBlobStore(
+                dir_cid.collection.cid.clone(),
+                Arc::new(blob_store),
+            ),
+            &mut HashCache::default(),
+        )
+        .await
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(resigned.diff.modified, vec!["weights/part-0.bin"]);
+        assert!(resigned.diff.added.is_empty() && resigned.diff.removed.is_empty());
+
+        let manifest: ModelSigningManifest =
+            serde_json::from_value(resigned.statement.predicate.predicate).unwrap();
+        let previous = manifest.previous.unwrap();
+        assert_eq!(previous.collection_cid, Some(dir_cid.collection.cid));
+        assert_eq!(
+            Value::from(previous.digest["sha256"].clone()),
+            previous_statement["subject"][0]["digest"]["sha256"]
+        );
+    }
+}
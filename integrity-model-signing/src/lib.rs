@@ -1,14 +1,39 @@
-use std::{collections::HashMap, sync::Arc};
+/// Re-signing of previously signed models, rehashing only changed files.
+pub mod incremental;
+
+/// Hashing of model files into manifest resources, including the OpenSSF serializations.
+pub mod serialization;
+
+/// Verification of model directories against model signing bundles.
+pub mod verify;
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use anyhow::{anyhow, bail, Result};
 use cid::Cid;
+/// Re-exported incremental re-signing types for convenience.
+pub use incremental::{
+    create_incremental_model_signing_intoto_statement, HashCache, IncrementalStatement, ModelDiff,
+    PreviousModel,
+};
 use integrity_blob::BlobStore;
-use integrity_cid::collection::hashmap_for_iroh_collection;
+use integrity_cid::{
+    collection::hashmap_for_iroh_collection,
+    iroh::{compute_file_cid, HashingConfig},
+};
 use integrity_intoto_attestation as intoto_attestation;
-use integrity_sigstore::SigstoreBundle;
+use integrity_sigstore::{
+    bundle::{PublicKeyIdentifier, X509Certificate},
+    BundleContent, DsseEnvelope, PublicKey, SigstoreBundle, VerificationMaterial,
+    VerificationMaterialContent,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+/// Re-exported serialization types for convenience.
+pub use serialization::{ModelSerialization, DEFAULT_SHARD_SIZE};
 use sha2::{Digest, Sha256};
+/// Re-exported verification types for convenience.
+pub use verify::{verify_model_signing_bundle, ModelVerification};
 
 /// Manifest containing model signing information for integrity verification.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +42,9 @@ pub struct ModelSigningManifest {
     pub serialization: ModelSigningManifestSerialization,
     /// List of resources (files) with their digests
     pub resources: Vec<ModelSigningManifestResource>,
+    /// The model version this manifest was re-signed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<ModelSigningPreviousVersion>,
 }
 
 /// Configuration for how the model manifest was serialized.
@@ -30,6 +58,9 @@ pub struct ModelSigningManifestSerialization {
     pub allow_symlinks: bool,
     /// Paths to ignore when computing the manifest
     pub ignore_paths: Vec<String>,
+    /// Size in bytes of each shard, for the "shards" method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_size: Option<u64>,
 }
 
 /// A single resource entry in the model signing manifest.
@@ -39,16 +70,28 @@ pub struct ModelSigningManifestResource {
     pub algorithm: String,
     /// The hex-encoded digest of the resource
     pub digest: String,
-    /// The relative path/name of the resource
+    /// The relative path of the resource, or `<path>:<start>:<end>` for a shard
     pub name: String,
 }
 
+/// Reference from a re-signed manifest to the model version it was derived from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelSigningPreviousVersion {
+    /// Digest of the previous version, the subject digest of its statement
+    pub digest: HashMap<String, String>,
+    /// CID of the iroh collection the previous version was signed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection_cid: Option<String>,
+}
+
 /// Information about a directory for model signing.
 pub enum DirectoryInfo {
     /// A map of file paths to their 32-byte hashes
     PathHashMap(HashMap<String, [u8; 32]>),
     /// An Iroh collection CID with its associated blob store
     IrohCollectionCidAndBlobStore(String, Arc<dyn BlobStore + Send + Sync>),
+    /// A local model directory whose files are hashed
+    LocalDirectory(PathBuf),
 }
 
 /// Creates an in-toto attestation statement for model signing.
@@ -63,73 +106,91 @@ pub enum DirectoryInfo {
 /// # Returns
 ///
 /// An in-toto `Statement` containing the model signing manifest as the predicate.
+///
+/// Resources are listed in path order and the subject's SHA-256 root digest covers their
+/// digests in that order, so the same model always produces the same statement.
 pub async fn create_model_signing_intoto_statement(
     name: String,
     directory_info: DirectoryInfo,
     allow_symlinks: bool,
     ignore_paths: Vec<String>,
 ) -> Result<intoto_attestation::Statement> {
-    let path_hash_map = match directory_info {
-        DirectoryInfo::PathHashMap(map) => map,
-        DirectoryInfo::IrohCollectionCidAndBlobStore(collection_cid, blob_store) => {
-            let hash_map_of_cids = hashmap_for_iroh_collection(&collection_cid, blob_store).await?;
-            let hash_map_of_hashes = hash_map_of_cids
-                .into_iter()
-                .map(|(path, cid)| {
-                    let cid = Cid::try_from(cid.as_str())
-                        .map_err(|e| anyhow!("Failed to parse cid {cid} from collection: {}", e))?;
-                    let hash = cid.hash().digest();
-                    let hash_bytes: [u8; 32] = hash
-                        .try_into()
-                        .map_err(|e| anyhow!("Unexpected digest length for cid {cid}: {}", e))?;
-
-                    Ok((path, hash_bytes))
-                })
-                .collect::<Result<HashMap<String, [u8; 32]>>>()?;
-
-            hash_map_of_hashes
-        }
-    };
-
-    let serialization = ModelSigningManifestSerialization {
-        method: "files".to_owned(),
-        hash_type: "blake3".to_owned(),
+    create_model_signing_intoto_statement_with_serialization(
+        name,
+        directory_info,
+        ModelSerialization::default(),
         allow_symlinks,
         ignore_paths,
-    };
+    )
+    .await
+}
 
-    let resources = path_hash_map
+/// Creates an in-toto attestation statement for model signing with the given serialization.
+///
+/// # Arguments
+///
+/// * `name` - The name of the model being signed.
+/// * `directory_info` - Information about the directory containing model files.
+/// * `serialization` - How model files are hashed, e.g. the OpenSSF `shards` serialization.
+/// * `allow_symlinks` - Whether symlinks are allowed in the model directory.
+/// * `ignore_paths` - Paths to ignore when computing the manifest.
+///
+/// # Returns
+///
+/// An in-toto `Statement` containing the model signing manifest as the predicate.
+pub async fn create_model_signing_intoto_statement_with_serialization(
+    name: String,
+    directory_info: DirectoryInfo,
+    serialization: ModelSerialization,
+    allow_symlinks: bool,
+    ignore_paths: Vec<String>,
+) -> Result<intoto_attestation::Statement> {
+    let path_hashes = serialization::resource_digests(
+        directory_info,
+        serialization,
+        allow_symlinks,
+        &ignore_paths,
+    )
+    .await?;
+
+    model_signing_statement(
+        name,
+        &path_hashes,
+        serialization.manifest_serialization(allow_symlinks, ignore_paths),
+        None,
+    )
+}
+
+/// Builds a model signing statement from resources already in canonical order.
+pub(crate) fn model_signing_statement(
+    name: String,
+    path_hashes: &[(String, [u8; 32])],
+    serialization: ModelSigningManifestSerialization,
+    previous: Option<ModelSigningPreviousVersion>,
+) -> Result<intoto_attestation::Statement> {
+    let resources = path_hashes
         .iter()
         .map(|(name, hash)| ModelSigningManifestResource {
-            algorithm: "blake3".to_owned(),
+            algorithm: serialization.hash_type.clone(),
             digest: hex::encode(hash),
             name: name.clone(),
         })
         .collect::<Vec<_>>();
 
+    let model_signing_root_hash = root_digest(path_hashes.iter().map(|(_, hash)| hash));
+
     let model_signing_manifest = ModelSigningManifest {
         serialization,
         resources,
-    };
-
-    let model_signing_root_hash = {
-        let mut hasher = Sha256::new();
-        for (_, hash) in path_hash_map {
-            hasher.update(hash);
-        }
-        let hash = hasher.finalize();
-        hex::encode(hash)
+        previous,
     };
 
     let intoto_attestation_statement = intoto_attestation::Statement {
-        subject: vec![intoto_attestation::Subject {
-            name,
-            digest: {
-                let mut m = std::collections::HashMap::new();
-                m.insert("sha256".to_owned(), model_signing_root_hash);
-                m
-            },
-        }],
+        subject: vec![intoto_attestation::Subject::new(name, {
+            let mut m = std::collections::HashMap::new();
+            m.insert("sha256".to_owned(), model_signing_root_hash);
+            m
+        })],
         predicate: intoto_attestation::Predicate {
             predicate_type: intoto_attestation::PredicateType::ModelSigningSignature,
             predicate: serde_json::to_value(&model_signing_manifest)?,
@@ -139,6 +200,106 @@ pub async fn create_model_signing_intoto_statement(
     Ok(intoto_attestation_statement)
 }
 
+/// Computes the hex SHA-256 root digest over resource digests in manifest order.
+pub(crate) fn root_digest<'a>(digests: impl IntoIterator<Item = &'a [u8; 32]>) -> String {
+    let mut hasher = Sha256::new();
+    for digest in digests {
+        hasher.update(digest);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Computes the BLAKE3 hash of every file described by `directory_info`.
+///
+/// A path hash map is returned as given, minus ignored paths.
+///
+/// Files under any of `ignore_paths` are left out. Local directories are walked with the same
+/// walker as the SHA-256 serializations and incremental re-signing, so symlinks are only
+/// followed when `allow_symlinks` is set, otherwise they are skipped.
+///
+/// # Arguments
+///
+/// * `directory_info` - Information about the directory containing model files.
+/// * `allow_symlinks` - Whether symlinks in a local directory are hashed.
+/// * `ignore_paths` - Relative file or directory paths to leave out.
+///
+/// # Returns
+///
+/// A map of relative file paths to their 32-byte BLAKE3 hashes.
+pub(crate) async fn directory_hashes(
+    directory_info: DirectoryInfo,
+    allow_symlinks: bool,
+    ignore_paths: &[String],
+) -> Result<HashMap<String, [u8; 32]>> {
+    let path_hash_map = match directory_info {
+        DirectoryInfo::PathHashMap(map) => map,
+        DirectoryInfo::IrohCollectionCidAndBlobStore(collection_cid, blob_store) => {
+            let hash_map_of_cids = hashmap_for_iroh_collection(&collection_cid, blob_store).await?;
+            hash_map_of_cids
+                .into_iter()
+                .map(|(path, cid)| Ok((path, blake3_hash_from_cid(&cid)?)))
+                .collect::<Result<HashMap<String, [u8; 32]>>>()?
+        }
+        DirectoryInfo::LocalDirectory(root) => {
+            let mut map = HashMap::new();
+            for (name, path) in serialization::model_files(&root, allow_symlinks, ignore_paths)? {
+                let cid = compute_file_cid(&path, HashingConfig::default()).await?.cid;
+                map.insert(name, blake3_hash_from_cid(&cid)?);
+            }
+            map
+        }
+    };
+
+    let path_hash_map = path_hash_map
+        .into_iter()
+        .filter(|(path, _)| !is_ignored(path, ignore_paths))
+        .collect();
+
+    Ok(path_hash_map)
+}
+
+/// Orders file hashes canonically by path, as the OpenSSF model signing spec does.
+///
+/// Paths are compared component by component, so `a/b` sorts before `a-b/c`.
+pub(crate) fn sorted_by_path(path_hashes: HashMap<String, [u8; 32]>) -> Vec<(String, [u8; 32])> {
+    let mut path_hashes = path_hashes.into_iter().collect::<Vec<_>>();
+    path_hashes.sort_by(|(a, _), (b, _)| serialization::path_order(a, b));
+    path_hashes
+}
+
+/// Returns whether `path` is, or is inside, one of the `ignore_paths`.
+pub(crate) fn is_ignored(path: &str, ignore_paths: &[String]) -> bool {
+    ignore_paths.iter().any(|ignore_path| {
+        let ignore_path = ignore_path.trim_end_matches('/');
+        path == ignore_path
+            || path
+                .strip_prefix(ignore_path)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+pub(crate) fn blake3_hash_from_cid(cid: &str) -> Result<[u8; 32]> {
+    let cid = Cid::try_from(cid)
+        .map_err(|e| anyhow!("Failed to parse cid {cid} from collection: {}", e))?;
+    let hash = cid.hash().digest();
+    let hash_bytes: [u8; 32] = hash
+        .try_into()
+        .map_err(|e| anyhow!("Unexpected digest length for cid {cid}: {}", e))?;
+
+    Ok(hash_bytes)
+}
+
+/// How the signer's public key is identified in a model signing bundle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BundleKeyMaterial {
+    /// Hex SHA-256 digest of the PEM encoded public key, as the OpenSSF model signing
+    /// tooling writes it
+    #[default]
+    Hint,
+    /// A DER encoded X.509 certificate for the signing key
+    Certificate(Vec<u8>),
+}
+
 /// Creates a Sigstore bundle for model signing from a DSSE envelope.
 ///
 /// # Arguments
@@ -153,73 +314,242 @@ pub fn create_model_signing_sigstore_bundle(
     dsse: Value,
     signer_did_key: &str,
 ) -> Result<SigstoreBundle> {
-    let signer_pub_key_hex = {
-        let multibase_str = signer_did_key
-            .strip_prefix("did:key:")
-            .ok_or_else(|| anyhow!("Missing 'did:key:' prefix"))?;
-
-        // 'z' prefix means base58btc
-        let b58_str = multibase_str
-            .strip_prefix('z')
-            .ok_or_else(|| anyhow!("Expected base58btc (z-prefix) multibase encoding"))?;
-
-        let decoded = bs58::decode(b58_str)
-            .into_vec()
-            .map_err(|e| anyhow!("Failed to decode base58btc: {}", e))?;
-
-        // first 2 bytes are multicodec for key type
-        let (multicodec, pub_key_bytes) = (&decoded[0..2], &decoded[2..]);
-
-        let pub_key_hash_hex = match multicodec {
-            // secp256r1 (P-256)
-            [0x80, 0x24] => {
-                use p256::elliptic_curve::sec1::FromEncodedPoint;
-                use pkcs8::EncodePublicKey;
-
-                match pub_key_bytes.len() {
-                    65 => {} // uncompressed point
-                    33 => {} // compressed point
-                    _ => {
-                        bail!("Unexpected public key length: {}", pub_key_bytes.len());
-                    }
-                }
-
-                let encoded_point =
-                    p256::elliptic_curve::sec1::EncodedPoint::<p256::NistP256>::from_bytes(
-                        pub_key_bytes,
-                    )
-                    .map_err(|e| anyhow!("Failed to parse public key bytes: {}", e))?;
-                let pub_key = p256::PublicKey::from_encoded_point(&encoded_point).unwrap();
-                let pem = pub_key
-                    .to_public_key_pem(Default::default())
-                    .map_err(|e| anyhow!("Failed to encode public key as PEM: {}", e))?;
-
-                let pub_key_hash = {
-                    let mut hasher = Sha256::new();
-                    hasher.update(pem.as_bytes());
-                    hasher.finalize()
-                };
-
-                hex::encode(pub_key_hash)
-            }
-            _ => {
-                bail!(
-                    "Unsupported multicodec for public key: {:x} {:x}",
-                    multicodec[0],
-                    multicodec[1]
-                );
+    create_model_signing_sigstore_bundle_with_key_material(
+        dsse,
+        signer_did_key,
+        BundleKeyMaterial::default(),
+    )
+}
+
+/// Creates a Sigstore bundle for model signing, identifying the signer's key as requested.
+///
+/// Ed25519, P-256 and secp256k1 `did:key`s are supported.
+///
+/// # Arguments
+///
+/// * `dsse` - The DSSE envelope as a JSON value.
+/// * `signer_did_key` - The DID key of the signer (must be a `did:key:` URI).
+/// * `key_material` - How the signer's key is recorded in the verification material.
+///
+/// # Returns
+///
+/// A `SigstoreBundle` containing verification material and the DSSE envelope, or error if
+/// the DID can't be resolved or the certificate isn't for the signer's key.
+pub fn create_model_signing_sigstore_bundle_with_key_material(
+    dsse: Value,
+    signer_did_key: &str,
+    key_material: BundleKeyMaterial,
+) -> Result<SigstoreBundle> {
+    let signer_key = PublicKey::from_did_key(signer_did_key)?;
+
+    let content = match key_material {
+        BundleKeyMaterial::Hint => {
+            let pem = signer_key.to_pem()?;
+            VerificationMaterialContent::PublicKey(PublicKeyIdentifier {
+                hint: hex::encode(Sha256::digest(pem.as_bytes())),
+            })
+        }
+        BundleKeyMaterial::Certificate(raw_bytes) => {
+            if PublicKey::from_certificate_der(&raw_bytes)? != signer_key {
+                bail!("Certificate is not for the signer's key '{signer_did_key}'.");
             }
-        };
+            VerificationMaterialContent::Certificate(X509Certificate { raw_bytes })
+        }
+    };
 
-        pub_key_hash_hex
+    let verification_material = VerificationMaterial {
+        content,
+        tlog_entries: vec![],
+        timestamp_verification_data: None,
     };
+    let dsse = serde_json::from_value::<DsseEnvelope>(dsse)
+        .map_err(|e| anyhow!("Failed to parse DSSE envelope: {e}"))?;
 
-    let verification_material = serde_json::json!({
-        "publicKey": {
-            "hint": signer_pub_key_hex
-        },
-        "tlogEntries": []
-    });
+    Ok(SigstoreBundle::new(
+        verification_material,
+        BundleContent::DsseEnvelope(dsse),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use integrity_signer::{Ed25519Signer, P256Signer, Secp256k1Signer, Signer, SignerType};
+    use integrity_sigstore::{testing, BundleVerifier, PublicKey};
+
+    use super::*;
+
+    async fn signed_dsse(signer: SignerType) -> (Value, String) {
+        let did = Signer::get_did_doc(&signer).await.unwrap().unwrap().id;
+
+        let statement = create_model_signing_intoto_statement(
+            "model".to_owned(),
+            DirectoryInfo::PathHashMap(HashMap::from([("weights.bin".to_owned(), [7u8; 32])])),
+            false,
+            vec![],
+        )
+        .await
+        .unwrap();
+        let dsse = intoto_attestation::sign_intoto_attestation(statement, Arc::new(signer))
+            .await
+            .unwrap();
+
+        (serde_json::from_str(&dsse).unwrap(), did)
+    }
+
+    fn signers() -> Vec<SignerType> {
+        vec![
+            SignerType::ED25519(Ed25519Signer::create().unwrap()),
+            SignerType::P256(P256Signer::create().unwrap()),
+            SignerType::SECP256K1(Secp256k1Signer::create().unwrap()),
+        ]
+    }
+
+    #[tokio::test]
+    async fn model_signing_bundle_verifies_offline_with_signer_key() {
+        for signer in signers() {
+            let (dsse, did) = signed_dsse(signer).await;
+
+            let bundle = create_model_signing_sigstore_bundle(dsse, &did).unwrap();
+            let bundle = SigstoreBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+
+            let key = PublicKey::from_did_key(&did).unwrap();
+            let VerificationMaterialContent::PublicKey(identifier) =
+                &bundle.verification_material().content
+            else {
+                panic!("{did}: expected a public key hint");
+            };
+            assert_eq!(
+                identifier.hint,
+                hex::encode(Sha256::digest(key.to_pem().unwrap()))
+            );
+            assert!(BundleVerifier::with_public_key(key).verify(&bundle).is_ok());
+
+            let (_, other_did) = signed_dsse(SignerType::P256(P256Signer::create().unwrap())).await;
+            let other_key = PublicKey::from_did_key(&other_did).unwrap();
+            assert!(BundleVerifier::with_public_key(other_key)
+                .verify(&bundle)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn hint_matches_fixture() {
+        const SIGNING_KEY: &str = include_str!("../../fixtures/model-signing/signing-key.pub.pem");
+        const BUNDLE: &str =
+            include_str!("../../fixtures/model-signing/model.sha256-files.sigstore.json");
+
+        // the hint is computed over the PEM as we encode it
+        let key = PublicKey::from_pem(SIGNING_KEY).unwrap();
+        assert_eq!(key.to_pem().unwrap(), SIGNING_KEY);
+
+        let bundle = SigstoreBundle::from_json(BUNDLE).unwrap();
+        let VerificationMaterialContent::PublicKey(identifier) =
+            &bundle.verification_material().content
+        else {
+            panic!("expected a public key hint");
+        };
+        assert_eq!(
+            identifier.hint,
+            hex::encode(Sha256::digest(key.to_pem().unwrap()))
+        );
+    }
+
+    #[tokio::test]
+    async fn model_signing_bundle_embeds_certificate() {
+        for signer in signers() {
+            let (dsse, did) = signed_dsse(signer).await;
+            let key = PublicKey::from_did_key(&did).unwrap();
+
+            let certificate = testing::certificate_for(&key);
+            let bundle = create_model_signing_sigstore_bundle_with_key_material(
+                dsse.clone(),
+                &did,
+                BundleKeyMaterial::Certificate(certificate),
+            )
+            .unwrap();
+            let bundle = SigstoreBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+            let verification = BundleVerifier::with_public_key(key.clone())
+                .verify(&bundle)
+                .unwrap();
+            assert_eq!(verification.signing_key, key);
+
+            let other =
+                testing::certificate_for(&PublicKey::P256(*testing::key(5).verifying_key()));
+            assert!(create_model_signing_sigstore_bundle_with_key_material(
+                dsse,
+                &did,
+                BundleKeyMaterial::Certificate(other),
+            )
+            .is_err());
+        }
+    }
+
+    fn path_hashes() -> HashMap<String, [u8; 32]> {
+        HashMap::from([
+            ("weights/part-1.bin".to_owned(), [2u8; 32]),
+            ("weights-extra/part-0.bin".to_owned(), [3u8; 32]),
+            ("config.json".to_owned(), [1u8; 32]),
+            ("README.md".to_owned(), [4u8; 32]),
+            ("weights/part-0.bin".to_owned(), [5u8; 32]),
+        ])
+    }
+
+    async fn statement_json(directory_info: DirectoryInfo) -> Value {
+        let statement = create_model_signing_intoto_statement(
+            "model".to_owned(),
+            directory_info,
+            false,
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        serde_json::from_str(&statement.into_json_string().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn statement_from_hashes_matches_golden_file() {
+        let golden: Value = serde_json::from_str(include_str!(
+            "../../fixtures/model-signing/path-hashes.statement.json"
+        ))
+        .unwrap();
+
+        // hash map iteration order differs between maps, the statement must not
+        for _ in 0..8 {
+            let statement = statement_json(DirectoryInfo::PathHashMap(path_hashes())).await;
+            assert_eq!(statement, golden);
+        }
+    }
+
+    #[tokio::test]
+    async fn statement_from_local_directory_matches_golden_file() {
+        let golden: Value = serde_json::from_str(include_str!(
+            "../../fixtures/model-signing/iroh-collection.statement.json"
+        ))
+        .unwrap();
+        let dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../fixtures/iroh-collection");
+
+        let statement = statement_json(DirectoryInfo::LocalDirectory(dir)).await;
+        assert_eq!(statement, golden);
+    }
+
+    #[test]
+    fn sorted_by_path_compares_path_components() {
+        let names = sorted_by_path(path_hashes())
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
 
-    Ok(SigstoreBundle::new(verification_material, dsse))
+        assert_eq!(
+            names,
+            vec![
+                "README.md",
+                "config.json",
+                "weights/part-0.bin",
+                "weights/part-1.bin",
+                "weights-extra/part-0.bin",
+            ]
+        );
+    }
 }
@@ -0,0 +1,416 @@
+use std::{
+    cmp::Ordering,
+    fs,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Result};
+use sha2::{Digest, Sha256};
+
+use integrity_cid::iroh::{compute_file_cid, HashingConfig};
+
+use crate::{
+    blake3_hash_from_cid, directory_hashes, is_ignored, sorted_by_path, DirectoryInfo,
+    ModelSigningManifestSerialization,
+};
+
+/// Default shard size of the OpenSSF model signing `shards` serialization, 1 GB.
+pub const DEFAULT_SHARD_SIZE: u64 = 1_000_000_000;
+
+const READ_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// How model files are hashed into manifest resources.
+///
+/// `Sha256Files` and `Sha256Shards` follow the layout of the OpenSSF model signing `files`
+/// and `shards` serializations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModelSerialization {
+    /// One BLAKE3 digest per file
+    #[default]
+    Blake3Files,
+    /// One SHA-256 digest per file
+    Sha256Files,
+    /// One SHA-256 digest per `shard_size` byte range of each file
+    Sha256Shards {
+        /// Maximum number of bytes covered by each shard digest
+        shard_size: u64,
+    },
+}
+
+impl ModelSerialization {
+    /// Returns the manifest serialization method, `files` or `shards`.
+    pub fn method(&self) -> &'static str {
+        match self {
+            ModelSerialization::Blake3Files | ModelSerialization::Sha256Files => "files",
+            ModelSerialization::Sha256Shards { .. } => "shards",
+        }
+    }
+
+    /// Returns the hash algorithm of the resource digests.
+    pub fn hash_type(&self) -> &'static str {
+        match self {
+            ModelSerialization::Blake3Files => "blake3",
+            ModelSerialization::Sha256Files | ModelSerialization::Sha256Shards { .. } => "sha256",
+        }
+    }
+
+    /// Returns the shard size of the `shards` serialization.
+    pub fn shard_size(&self) -> Option<u64> {
+        match self {
+            ModelSerialization::Sha256Shards { shard_size } => Some(*shard_size),
+            _ => None,
+        }
+    }
+
+    /// Returns whether a manifest resource's digest algorithm names this serialization's
+    /// hashes.
+    ///
+    /// Resources record the hash type, except that the OpenSSF `shards` serialization
+    /// names its sharded file hasher, `file-sha256-<shard_size>`.
+    pub fn is_resource_algorithm(&self, algorithm: &str) -> bool {
+        match self {
+            ModelSerialization::Sha256Shards { shard_size } => {
+                algorithm == self.hash_type() || algorithm == format!("file-sha256-{shard_size}")
+            }
+            _ => algorithm == self.hash_type(),
+        }
+    }
+
+    /// Parses the serialization recorded in a model signing manifest.
+    ///
+    /// # Arguments
+    ///
+    /// * `serialization` - The manifest's serialization parameters.
+    ///
+    /// # Returns
+    ///
+    /// The serialization, or error if the method and hash type aren't supported.
+    pub fn from_manifest(serialization: &ModelSigningManifestSerialization) -> Result<Self> {
+        let method = serialization.method.as_str();
+        let hash_type = serialization.hash_type.as_str();
+
+        match (method, hash_type, serialization.shard_size) {
+            ("files", "blake3", _) => Ok(ModelSerialization::Blake3Files),
+            ("files", "sha256", _) => Ok(ModelSerialization::Sha256Files),
+            ("shards", "sha256", Some(shard_size)) if shard_size > 0 => {
+                Ok(ModelSerialization::Sha256Shards { shard_size })
+            }
+            ("shards", "sha256", _) => {
+                bail!("Shards serialization requires a positive shard size.")
+            }
+            _ => {
+                bail!("Unsupported model signing serialization '{method}' with hash '{hash_type}'.")
+            }
+        }
+    }
+
+    pub(crate) fn manifest_serialization(
+        &self,
+        allow_symlinks: bool,
+        ignore_paths: Vec<String>,
+    ) -> ModelSigningManifestSerialization {
+        ModelSigningManifestSerialization {
+            method: self.method().to_owned(),
+            hash_type: self.hash_type().to_owned(),
+            allow_symlinks,
+            ignore_paths,
+            shard_size: self.shard_size(),
+        }
+    }
+}
+
+/// Computes the manifest resources of a model as `(name, digest)` pairs in canonical order.
+///
+/// Files are ordered by path; shards, named `<path>:<start>:<end>`, by path then offset.
+/// SHA-256 serializations need a local directory, except that a path hash map is taken
+/// to already hold SHA-256 file digests.
+pub(crate) async fn resource_digests(
+    directory_info: DirectoryInfo,
+    serialization: ModelSerialization,
+    allow_symlinks: bool,
+    ignore_paths: &[String],
+) -> Result<Vec<(String, [u8; 32])>> {
+    match (serialization, directory_info) {
+        (ModelSerialization::Blake3Files, directory_info) => Ok(sorted_by_path(
+            directory_hashes(directory_info, allow_symlinks, ignore_paths).await?,
+        )),
+        (ModelSerialization::Sha256Files, DirectoryInfo::PathHashMap(map)) => Ok(sorted_by_path(
+            directory_hashes(
+                DirectoryInfo::PathHashMap(map),
+                allow_symlinks,
+                ignore_paths,
+            )
+            .await?,
+        )),
+        (serialization, DirectoryInfo::LocalDirectory(root)) => {
+            let mut files = model_files(&root, allow_symlinks, ignore_paths)?;
+            files.sort_by(|(a, _), (b, _)| path_order(a, b));
+
+            let mut resources = vec![];
+            for (name, path) in files {
+                resources.extend(file_resources(&name, &path, serialization).await?);
+            }
+
+            Ok(resources)
+        }
+        (serialization, _) => bail!(
+            "The '{}' serialization with '{}' hashes requires a local model directory.",
+            serialization.method(),
+            serialization.hash_type()
+        ),
+    }
+}
+
+/// Hashes a single model file into its manifest resources.
+///
+/// Shards are returned in offset order.
+pub(crate) async fn file_resources(
+    name: &str,
+    path: &Path,
+    serialization: ModelSerialization,
+) -> Result<Vec<(String, [u8; 32])>> {
+    match serialization {
+        ModelSerialization::Blake3Files => {
+            let cid = compute_file_cid(path, HashingConfig::default()).await?.cid;
+            Ok(vec![(name.to_owned(), blake3_hash_from_cid(&cid)?)])
+        }
+        ModelSerialization::Sha256Files => {
+            Ok(vec![(name.to_owned(), sha256_range(path, 0, None)?)])
+        }
+        ModelSerialization::Sha256Shards { shard_size } => {
+            let size = fs::metadata(path)?.len();
+            let mut shards = vec![];
+            let mut start = 0;
+            loop {
+                let end = size.min(start + shard_size);
+                let digest = sha256_range(path, start, Some(end - start))?;
+                shards.push((format!("{name}:{start}:{end}"), digest));
+                start = end;
+                if start >= size {
+                    break;
+                }
+            }
+
+            Ok(shards)
+        }
+    }
+}
+
+/// Returns the file a manifest resource belongs to, stripping the range from shard names.
+pub(crate) fn resource_file(name: &str, serialization: ModelSerialization) -> &str {
+    match serialization {
+        ModelSerialization::Sha256Shards { .. } => name.rsplitn(3, ':').nth(2).unwrap_or(name),
+        _ => name,
+    }
+}
+
+/// Compares relative paths component by component.
+pub(crate) fn path_order(a: &str, b: &str) -> Ordering {
+    a.split('/').cmp(b.split('/'))
+}
+
+/// Lists the files of a local model directory as `(relative name, path)` pairs.
+///
+/// Symlinks are followed when `allow_symlinks` is set and skipped otherwise.
+pub(crate) fn model_files(
+    root: &Path,
+    allow_symlinks: bool,
+    ignore_paths: &[String],
+) -> Result<Vec<(String, PathBuf)>> {
+    if !root.is_dir() {
+        bail!("The provided path ({}) is not a directory", root.display());
+    }
+
+    let mut files = vec![];
+    let mut dirs = vec![(String::new(), root.to_path_buf())];
+    while let Some((prefix, dir)) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+            if is_ignored(&name, ignore_paths) {
+                continue;
+            }
+
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            let is_dir = if file_type.is_symlink() {
+                if !allow_symlinks {
+                    continue;
+                }
+                path.is_dir()
+            } else {
+                file_type.is_dir()
+            };
+
+            if is_dir {
+                dirs.push((format!("{name}/"), path));
+            } else {
+                files.push((name, path));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Hashes `len` bytes of a file starting at `start`, or the rest of the file if `len` is `None`.
+fn sha256_range(path: &Path, start: u64, len: Option<u64>) -> Result<[u8; 32]> {
+    let mut file = fs::File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut reader: Box<dyn Read> = match len {
+        Some(len) => Box::new(file.take(len)),
+        None => Box::new(file),
+    };
+
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; READ_CHUNK_SIZE];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use integrity_sigstore::{PublicKey, SigstoreBundle};
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        create_model_signing_intoto_statement_with_serialization, verify_model_signing_bundle,
+    };
+
+    const FILES_BUNDLE: &str =
+        include_str!("../../fixtures/model-signing/model.sha256-files.sigstore.json");
+    const SHARDS_BUNDLE: &str =
+        include_str!("../../fixtures/model-signing/model.sha256-shards.sigstore.json");
+    const SIGNING_KEY: &str = include_str!("../../fixtures/model-signing/signing-key.pub.pem");
+
+    fn fixture_model() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../fixtures/model-signing/model")
+    }
+
+    fn fixtures() -> [(ModelSerialization, &'static str); 2] {
+        [
+            (ModelSerialization::Sha256Files, FILES_BUNDLE),
+            (
+                ModelSerialization::Sha256Shards { shard_size: 1000 },
+                SHARDS_BUNDLE,
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn statements_match_fixtures() {
+        for (serialization, bundle) in fixtures() {
+            let bundle = SigstoreBundle::from_json(bundle).unwrap();
+            let expected: Value =
+                serde_json::from_slice(&bundle.dsse_envelope().unwrap().payload).unwrap();
+
+            let statement = create_model_signing_intoto_statement_with_serialization(
+                "model".to_owned(),
+                DirectoryInfo::LocalDirectory(fixture_model()),
+                serialization,
+                false,
+                vec!["notes".to_owned()],
+            )
+            .await
+            .unwrap();
+            let statement: Value =
+                serde_json::from_str(&statement.into_json_string().unwrap()).unwrap();
+
+            assert_eq!(statement, expected, "{serialization:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn verifies_fixture_bundles() {
+        let key = PublicKey::from_pem(SIGNING_KEY).unwrap();
+
+        for (serialization, bundle) in fixtures() {
+            let bundle = SigstoreBundle::from_json(bundle).unwrap();
+            let verification = verify_model_signing_bundle(
+                &bundle,
+                DirectoryInfo::LocalDirectory(fixture_model()),
+                std::slice::from_ref(&key),
+            )
+            .await
+            .unwrap();
+
+            assert!(
+                verification.is_valid(),
+                "{serialization:?}: {verification:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_modified_shards() {
+        let dir = std::env::temp_dir().join(format!(
+            "integrity-model-signing-shards-{}",
+            std::process::id()
+        ));
+        for (name, path) in model_files(&fixture_model(), false, &[]).unwrap() {
+            let target = dir.join(&name);
+            fs::create_dir_all(target.parent().unwrap()).unwrap();
+            fs::copy(path, target).unwrap();
+        }
+        let mut weights = fs::read(dir.join("model.safetensors")).unwrap();
+        weights[1500] ^= 0xff;
+        fs::write(dir.join("model.safetensors"), weights).unwrap();
+
+        let verification = verify_model_signing_bundle(
+            &SigstoreBundle::from_json(SHARDS_BUNDLE).unwrap(),
+            DirectoryInfo::LocalDirectory(dir.clone()),
+            &[PublicKey::from_pem(SIGNING_KEY).unwrap()],
+        )
+        .await
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(verification.modified, vec!["model.safetensors:1000:2000"]);
+        assert!(verification.missing.is_empty() && verification.extra.is_empty());
+    }
+
+    #[test]
+    fn from_manifest_rejects_unsupported_serializations() {
+        let serialization = |method: &str, hash_type: &str, shard_size| {
+            ModelSerialization::from_manifest(&ModelSigningManifestSerialization {
+                method: method.to_owned(),
+                hash_type: hash_type.to_owned(),
+                allow_symlinks: false,
+                ignore_paths: vec![],
+                shard_size,
+            })
+        };
+
+        assert_eq!(
+            serialization("shards", "sha256", Some(10)).unwrap(),
+            ModelSerialization::Sha256Shards { shard_size: 10 }
+        );
+        assert!(serialization("shards", "sha256", None).is_err());
+        assert!(serialization("shards", "sha256", Some(0)).is_err());
+        assert!(serialization("files", "blake2", None).is_err());
+        assert!(serialization("digests", "sha256", None).is_err());
+    }
+
+    #[test]
+    fn matches_exact_resource_algorithms() {
+        let shards = ModelSerialization::Sha256Shards { shard_size: 1000 };
+        assert!(shards.is_resource_algorithm("sha256"));
+        assert!(shards.is_resource_algorithm("file-sha256-1000"));
+        assert!(!shards.is_resource_algorithm("file-sha256-2000"));
+        assert!(!shards.is_resource_algorithm("not-sha256"));
+
+        assert!(ModelSerialization::Sha256Files.is_resource_algorithm("sha256"));
+        assert!(!ModelSerialization::Sha256Files.is_resource_algorithm("sha256-truncated"));
+        assert!(!ModelSerialization::Sha256Files.is_resource_algorithm("file-sha256-1000"));
+        assert!(ModelSerialization::Blake3Files.is_resource_algorithm("blake3"));
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, bail, Result};
+use integrity_intoto_attestation as intoto_attestation;
+use integrity_sigstore::{BundleVerifier, PublicKey, SigstoreBundle};
+
+use crate::{
+    serialization::resource_digests, DirectoryInfo, ModelSerialization, ModelSigningManifest,
+};
+
+/// Outcome of verifying a model directory against a model signing bundle.
+///
+/// The bundle signature is always verified; file differences are reported rather than
+/// returned as errors so callers can show every mismatch at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelVerification {
+    /// Trusted key the bundle was signed with
+    pub signing_key: PublicKey,
+    /// Files listed in the manifest that are missing from the directory
+    pub missing: Vec<String>,
+    /// Files in the directory that aren't listed in the manifest
+    pub extra: Vec<String>,
+    /// Files whose digest differs from the manifest
+    pub modified: Vec<String>,
+}
+
+impl ModelVerification {
+    /// Returns whether the directory matches the signed manifest exactly.
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Verifies a model directory against a model signing Sigstore bundle.
+///
+/// The bundle's DSSE signature must verify against one of `trusted_keys`. Files are then
+/// re-hashed with the serialization, `ignore_paths` and `allow_symlinks` recorded in the
+/// signed manifest and compared with its resources. Manifests in the OpenSSF model signing
+/// layout using the `files` or `shards` serialization with SHA-256 are supported.
+///
+/// # Arguments
+///
+/// * `bundle` - The model signing bundle.
+/// * `directory_info` - Information about the directory containing model files.
+/// * `trusted_keys` - Public keys the bundle may be signed with.
+///
+/// # Returns
+///
+/// The missing, extra and modified files, or error if the signature doesn't verify against
+/// a trusted key or the bundle isn't a model signing bundle.
+pub async fn verify_model_signing_bundle(
+    bundle: &SigstoreBundle,
+    directory_info: DirectoryInfo,
+    trusted_keys: &[PublicKey],
+) -> Result<ModelVerification> {
+    let signing_key = trusted_signing_key(bundle, trusted_keys)?;
+    let manifest = signed_manifest(bundle)?;
+
+    let serialization = ModelSerialization::from_manifest(&manifest.serialization)?;
+
+    let mut expected = BTreeMap::new();
+    for resource in &manifest.resources {
+        if !serialization.is_resource_algorithm(&resource.algorithm) {
+            bail!(
+                "Digest algorithm '{}' for '{}' doesn't match the manifest's '{}' hashes.",
+                resource.algorithm,
+                resource.name,
+                serialization.hash_type()
+            );
+        }
+        if expected
+            .insert(resource.name.as_str(), resource.digest.to_lowercase())
+            .is_some()
+        {
+            bail!("Manifest lists '{}' more than once.", resource.name);
+        }
+    }
+
+    let actual = resource_digests(
+        directory_info,
+        serialization,
+        manifest.serialization.allow_symlinks,
+        &manifest.serialization.ignore_paths,
+    )
+    .await?;
+    let actual = actual
+        .iter()
+        .map(|(name, hash)| (name.as_str(), hex::encode(hash)))
+        .collect::<BTreeMap<_, _>>();
+
+    let names = expected
+        .keys()
+        .chain(actual.keys())
+        .copied()
+        .collect::<BTreeSet<_>>();
+
+    let mut verification = ModelVerification {
+        signing_key,
+        missing: vec![],
+        extra: vec![],
+        modified: vec![],
+    };
+    for name in names {
+        match (expected.get(name), actual.get(name)) {
+            (Some(_), None) => verification.missing.push(name.to_owned()),
+            (None, Some(_)) => verification.extra.push(name.to_owned()),
+            (Some(expected), Some(actual)) if expected != actual => {
+                verification.modified.push(name.to_owned())
+            }
+            _ => {}
+        }
+    }
+
+    Ok(verification)
+}
+
+/// Returns the first trusted key the bundle's signature verifies against.
+fn trusted_signing_key(bundle: &SigstoreBundle, trusted_keys: &[PublicKey]) -> Result<PublicKey> {
+    if bundle.dsse_envelope().is_none() {
+        bail!("Model signing bundle doesn't contain a DSSE envelope.");
+    }
+
+    let mut error = anyhow!("No trusted keys were provided.");
+    for key in trusted_keys {
+        match BundleVerifier::with_public_key(key.clone()).verify(bundle) {
+            Ok(verification) => return Ok(verification.signing_key),
+            Err(e) => error = e,
+        }
+    }
+
+    Err(error.context("Model signing bundle isn't signed by a trusted key."))
+}
+
+/// Parses the model signing manifest from the bundle's in-toto statement.
+fn signed_manifest(bundle: &SigstoreBundle) -> Result<ModelSigningManifest> {
+    let envelope = bundle
+        .dsse_envelope()
+        .ok_or_else(|| anyhow!("Model signing bundle doesn't contain a DSSE envelope."))?;
+
+    let statement =
+        serde_json::from_slice::<intoto_attestation::models::Statement>(&envelope.payload)?;
+    let statement = intoto_attestation::Statement::try_from(statement)?;
+
+    if statement.predicate.predicate_type
+        != intoto_attestation::PredicateType::ModelSigningSignature
+    {
+        bail!(
+            "Expected a model signing statement, got predicate type '{}'.",
+            statement.predicate.predicate_type
+        );
+    }
+
+    let manifest = serde_json::from_value::<ModelSigningManifest>(statement.predicate.predicate)?;
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
+
+    use integrity_signer::{P256Signer, Signer, SignerType};
+
+    use super::*;
+    use crate::{create_model_signing_intoto_statement, create_model_signing_sigstore_bundle};
+
+    async fn sign(
+        directory_info: DirectoryInfo,
+        ignore_paths: Vec<String>,
+        signer: SignerType,
+    ) -> SigstoreBundle {
+        let did = Signer::get_did_doc(&signer).await.unwrap().unwrap().id;
+        let statement = create_model_signing_intoto_statement(
+            "model".to_owned(),
+            directory_info,
+            false,
+            ignore_paths,
+        )
+        .await
+        .unwrap();
+        let dsse = intoto_attestation::sign_intoto_attestation(statement, Arc::new(signer))
+            .await
+            .unwrap();
+
+        create_model_signing_sigstore_bundle(serde_json::from_str(&dsse).unwrap(), &did).unwrap()
+    }
+
+    async fn key_of(signer: &SignerType) -> PublicKey {
+        let did = Signer::get_did_doc(signer).await.unwrap().unwrap().id;
+        PublicKey::from_did_key(&did).unwrap()
+    }
+
+    fn model_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "integrity-model-signing-verify-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(dir.join("weights")).unwrap();
+        fs::write(dir.join("config.json"), b"{}").unwrap();
+        fs::write(dir.join("weights/part-0.bin"), b"first shard").unwrap();
+        fs::write(dir.join("weights/part-1.bin"), b"second shard").unwrap();
+        fs::create_dir_all(dir.join(".cache")).unwrap();
+        fs::write(dir.join(".cache/state"), b"scratch").unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn verifies_local_directory_and_reports_differences() {
+        let signer = SignerType::P256(P256Signer::create().unwrap());
+        let key = key_of(&signer).await;
+        let dir = model_dir();
+
+        let bundle = sign(
+            DirectoryInfo::LocalDirectory(dir.clone()),
+            vec![".cache".to_owned()],
+            signer,
+        )
+        .await;
+        let bundle = SigstoreBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+
+        let verification = verify_model_signing_bundle(
+            &bundle,
+            DirectoryInfo::LocalDirectory(dir.clone()),
+            std::slice::from_ref(&key),
+        )
+        .await
+        .unwrap();
+        assert!(verification.is_valid(), "{verification:?}");
+        assert_eq!(verification.signing_key, key);
+
+        // changes under ignored paths don't matter
+        fs::write(dir.join(".cache/state"), b"changed").unwrap();
+        fs::write(dir.join("weights/part-1.bin"), b"tampered").unwrap();
+        fs::remove_file(dir.join("config.json")).unwrap();
+        fs::write(dir.join("weights/part-2.bin"), b"third shard").unwrap();
+
+        let verification = verify_model_signing_bundle(
+            &bundle,
+            DirectoryInfo::LocalDirectory(dir.clone()),
+            &[key],
+        )
+        .await
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!verification.is_valid());
+        assert_eq!(verification.missing, vec!["config.json"]);
+        assert_eq!(verification.extra, vec!["weights/part-2.bin"]);
+        assert_eq!(verification.modified, vec!["weights/part-1.bin"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_bundle_not_signed_by_trusted_key() {
+        let signer = SignerType::P256(P256Signer::create().unwrap());
+        let other = SignerType::P256(P256Signer::create().unwrap());
+        let files = HashMap::from([("weights.bin".to_owned(), [7u8; 32])]);
+
+        let bundle = sign(DirectoryInfo::PathHashMap(files.clone()), vec![], signer).await;
+
+        let result = verify_model_signing_bundle(
+            &bundle,
+            DirectoryInfo::PathHashMap(files.clone()),
+            &[key_of(&other).await],
+        )
+        .await;
+        assert!(result.is_err());
+
+        assert!(
+            verify_model_signing_bundle(&bundle, DirectoryInfo::PathHashMap(files), &[])
+                .await
+                .is_err()
+        );
+    }
+}
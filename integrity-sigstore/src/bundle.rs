@@ -0,0 +1,238 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::encoding::{base64_bytes, base64_bytes_vec, i64_string};
+
+/// Media type of Sigstore bundles created by this crate.
+pub const BUNDLE_V03_MEDIA_TYPE: &str = "application/vnd.dev.sigstore.bundle.v0.3+json";
+
+/// Bundle media types that can be parsed.
+pub const SUPPORTED_MEDIA_TYPES: &[&str] = &[
+    "application/vnd.dev.sigstore.bundle+json;version=0.1",
+    "application/vnd.dev.sigstore.bundle+json;version=0.2",
+    "application/vnd.dev.sigstore.bundle+json;version=0.3",
+    BUNDLE_V03_MEDIA_TYPE,
+];
+
+/// Material used to verify a bundle's signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMaterial {
+    /// Signing key or certificate
+    #[serde(flatten)]
+    pub content: VerificationMaterialContent,
+    /// Transparency log entries for the signature
+    #[serde(default)]
+    pub tlog_entries: Vec<TransparencyLogEntry>,
+    /// Signed timestamps for the signature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_verification_data: Option<TimestampVerificationData>,
+}
+
+/// The key or certificate a bundle was signed with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationMaterialContent {
+    /// A hint identifying a public key that's distributed out of band
+    PublicKey(PublicKeyIdentifier),
+    /// An X.509 certificate chain, leaf first (bundle v0.1 and v0.2)
+    X509CertificateChain(X509CertificateChain),
+    /// A single X.509 leaf certificate (bundle v0.3)
+    Certificate(X509Certificate),
+}
+
+/// Identifies a public key distributed out of band.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyIdentifier {
+    /// Opaque key hint, e.g. a hash of the key
+    pub hint: String,
+}
+
+/// An X.509 certificate chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X509CertificateChain {
+    /// Certificates, leaf first
+    pub certificates: Vec<X509Certificate>,
+}
+
+/// A DER encoded X.509 certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X509Certificate {
+    /// DER bytes of the certificate
+    #[serde(with = "base64_bytes")]
+    pub raw_bytes: Vec<u8>,
+}
+
+/// An entry in a transparency log (Rekor) for a bundle's signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransparencyLogEntry {
+    /// Index of the entry in the log
+    #[serde(with = "i64_string")]
+    pub log_index: i64,
+    /// Identifier of the log
+    pub log_id: LogId,
+    /// Type and version of the log entry
+    pub kind_version: KindVersion,
+    /// Unix timestamp of when the entry was integrated into the log
+    #[serde(with = "i64_string")]
+    pub integrated_time: i64,
+    /// Signed promise of inclusion from the log
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inclusion_promise: Option<InclusionPromise>,
+    /// Merkle tree inclusion proof
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inclusion_proof: Option<InclusionProof>,
+    /// Canonicalized body of the log entry
+    #[serde(with = "base64_bytes")]
+    pub canonicalized_body: Vec<u8>,
+}
+
+/// Identifier of a transparency log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogId {
+    /// SHA-256 hash of the log's DER encoded public key
+    #[serde(with = "base64_bytes")]
+    pub key_id: Vec<u8>,
+}
+
+/// Type and version of a transparency log entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KindVersion {
+    /// Entry kind, e.g. `dsse` or `hashedrekord`
+    pub kind: String,
+    /// Entry schema version, e.g. `0.0.1`
+    pub version: String,
+}
+
+/// Signed entry timestamp returned by the log when an entry is added.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionPromise {
+    /// Signature of the log over the entry
+    #[serde(with = "base64_bytes")]
+    pub signed_entry_timestamp: Vec<u8>,
+}
+
+/// Merkle tree inclusion proof for a transparency log entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionProof {
+    /// Index of the entry in the tree
+    #[serde(with = "i64_string")]
+    pub log_index: i64,
+    /// Root hash of the tree the proof is for
+    #[serde(with = "base64_bytes")]
+    pub root_hash: Vec<u8>,
+    /// Size of the tree the proof is for
+    #[serde(with = "i64_string")]
+    pub tree_size: i64,
+    /// Sibling hashes from the leaf to the root
+    #[serde(with = "base64_bytes_vec")]
+    pub hashes: Vec<Vec<u8>>,
+    /// Signed checkpoint of the tree
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<Checkpoint>,
+}
+
+/// A signed transparency log checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    /// Checkpoint in signed note format
+    pub envelope: String,
+}
+
+/// Signed timestamps for a bundle's signature.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimestampVerificationData {
+    /// RFC 3161 timestamp tokens
+    #[serde(default)]
+    pub rfc3161_timestamps: Vec<Rfc3161SignedTimestamp>,
+}
+
+/// An RFC 3161 timestamp token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rfc3161SignedTimestamp {
+    /// DER encoded `TimeStampToken`
+    #[serde(with = "base64_bytes")]
+    pub signed_timestamp: Vec<u8>,
+}
+
+/// The signed content of a bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleContent {
+    /// A DSSE envelope
+    DsseEnvelope(DsseEnvelope),
+    /// A signature over an artifact digest
+    MessageSignature(MessageSignature),
+}
+
+/// A DSSE envelope as embedded in a Sigstore bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DsseEnvelope {
+    /// Signed payload bytes
+    #[serde(with = "base64_bytes")]
+    pub payload: Vec<u8>,
+    /// Type of the payload
+    pub payload_type: String,
+    /// Signatures over the payload's pre-authentication encoding
+    pub signatures: Vec<DsseSignature>,
+}
+
+/// A signature within a DSSE envelope.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DsseSignature {
+    /// Signature bytes
+    #[serde(with = "base64_bytes")]
+    pub sig: Vec<u8>,
+    /// Identifier of the signing key
+    #[serde(default)]
+    pub keyid: String,
+}
+
+/// A signature over an artifact digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSignature {
+    /// Digest of the signed artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_digest: Option<HashOutput>,
+    /// Signature bytes
+    #[serde(with = "base64_bytes")]
+    pub signature: Vec<u8>,
+}
+
+/// A digest and the algorithm that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashOutput {
+    /// Hash algorithm, e.g. `SHA2_256`
+    pub algorithm: String,
+    /// Digest bytes
+    #[serde(with = "base64_bytes")]
+    pub digest: Vec<u8>,
+}
+
+pub(crate) fn deserialize_media_type<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    let media_type = String::deserialize(deserializer)?;
+
+    if !SUPPORTED_MEDIA_TYPES.contains(&media_type.as_str()) {
+        return Err(serde::de::Error::custom(format!(
+            "unsupported Sigstore bundle media type '{media_type}'"
+        )));
+    }
+
+    Ok(media_type)
+}
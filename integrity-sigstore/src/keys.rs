@@ -0,0 +1,229 @@
+use anyhow::{anyhow, bail, Result};
+use p256::{
+    ecdsa::signature::{hazmat::PrehashVerifier, Verifier},
+    pkcs8::{der::Decode, DecodePublicKey, EncodePublicKey},
+};
+use sha2::{Digest, Sha256};
+use x509_cert::{der::Encode, spki::SubjectPublicKeyInfoRef, Certificate};
+
+const ID_EC_PUBLIC_KEY: &str = "1.2.840.10045.2.1";
+const ID_ED25519: &str = "1.3.101.112";
+const SECP256R1: &str = "1.2.840.10045.3.1.7";
+const SECP384R1: &str = "1.3.132.0.34";
+const SECP256K1: &str = "1.3.132.0.10";
+
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+const P256_MULTICODEC: [u8; 2] = [0x80, 0x24];
+const SECP256K1_MULTICODEC: [u8; 2] = [0xe7, 0x01];
+
+/// A public key trusted to have signed Sigstore bundles or log entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicKey {
+    /// ECDSA P-256 key, signatures over SHA-256
+    P256(p256::ecdsa::VerifyingKey),
+    /// ECDSA P-384 key, signatures over SHA-384
+    P384(p384::ecdsa::VerifyingKey),
+    /// ECDSA secp256k1 key, signatures over SHA-256
+    Secp256k1(k256::ecdsa::VerifyingKey),
+    /// Ed25519 key
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl PublicKey {
+    /// Parses a PEM encoded `SubjectPublicKeyInfo` (`-----BEGIN PUBLIC KEY-----`).
+    pub fn from_pem(pem: &str) -> Result<Self> {
+        let (label, der) = p256::pkcs8::der::pem::decode_vec(pem.trim().as_bytes())
+            .map_err(|e| anyhow!("Failed to decode public key PEM: {e}"))?;
+
+        if label != "PUBLIC KEY" {
+            bail!("Expected a PUBLIC KEY PEM block, found '{label}'.");
+        }
+
+        Self::from_spki_der(&der)
+    }
+
+    /// Parses a DER encoded `SubjectPublicKeyInfo`.
+    pub fn from_spki_der(der: &[u8]) -> Result<Self> {
+        let spki = SubjectPublicKeyInfoRef::from_der(der)
+            .map_err(|e| anyhow!("Failed to parse public key: {e}"))?;
+        let algorithm = spki.algorithm.oid.to_string();
+        let curve = spki
+            .algorithm
+            .parameters_oid()
+            .ok()
+            .map(|oid| oid.to_string());
+
+        let key = match (algorithm.as_str(), curve.as_deref()) {
+            (ID_EC_PUBLIC_KEY, Some(SECP256R1)) => {
+                PublicKey::P256(p256::ecdsa::VerifyingKey::from_public_key_der(der)?)
+            }
+            (ID_EC_PUBLIC_KEY, Some(SECP384R1)) => {
+                PublicKey::P384(p384::ecdsa::VerifyingKey::from_public_key_der(der)?)
+            }
+            (ID_EC_PUBLIC_KEY, Some(SECP256K1)) => {
+                PublicKey::Secp256k1(k256::ecdsa::VerifyingKey::from_public_key_der(der)?)
+            }
+            (ID_ED25519, _) => {
+                PublicKey::Ed25519(ed25519_dalek::VerifyingKey::from_public_key_der(der)?)
+            }
+            (algorithm, curve) => {
+                bail!("Unsupported public key algorithm {algorithm} ({curve:?}).")
+            }
+        };
+
+        Ok(key)
+    }
+
+    /// Resolves the public key of a `did:key` identifier.
+    pub fn from_did_key(did: &str) -> Result<Self> {
+        let did = did.split('#').next().unwrap_or(did);
+        let b58_str = did
+            .strip_prefix("did:key:z")
+            .ok_or_else(|| anyhow!("'{did}' is not a base58btc encoded did:key"))?;
+        let decoded = bs58::decode(b58_str)
+            .into_vec()
+            .map_err(|e| anyhow!("Failed to decode base58btc: {e}"))?;
+
+        if decoded.len() < 2 {
+            bail!("'{did}' is too short to contain a multicodec prefix");
+        }
+
+        let (multicodec, pub_key_bytes) = decoded.split_at(2);
+
+        let key = match [multicodec[0], multicodec[1]] {
+            ED25519_MULTICODEC => PublicKey::Ed25519(ed25519_dalek::VerifyingKey::from_bytes(
+                pub_key_bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("Ed25519 public key must be 32 bytes"))?,
+            )?),
+            P256_MULTICODEC => {
+                PublicKey::P256(p256::ecdsa::VerifyingKey::from_sec1_bytes(pub_key_bytes)?)
+            }
+            SECP256K1_MULTICODEC => {
+                PublicKey::Secp256k1(k256::ecdsa::VerifyingKey::from_sec1_bytes(pub_key_bytes)?)
+            }
+            [a, b] => bail!("Unsupported multicodec for public key: {a:x} {b:x}"),
+        };
+
+        Ok(key)
+    }
+
+    /// Extracts the subject public key of a DER encoded X.509 certificate.
+    pub fn from_certificate_der(der: &[u8]) -> Result<Self> {
+        let certificate =
+            Certificate::from_der(der).map_err(|e| anyhow!("Failed to parse certificate: {e}"))?;
+
+        Self::from_certificate(&certificate)
+    }
+
+    /// Extracts the subject public key of an X.509 certificate.
+    pub(crate) fn from_certificate(certificate: &Certificate) -> Result<Self> {
+        let spki = certificate
+            .tbs_certificate
+            .subject_public_key_info
+            .to_der()
+            .map_err(|e| anyhow!("Failed to encode certificate public key: {e}"))?;
+
+        Self::from_spki_der(&spki)
+    }
+
+    /// Returns the DER encoded `SubjectPublicKeyInfo` of the key.
+    pub fn to_spki_der(&self) -> Result<Vec<u8>> {
+        let der = match self {
+            PublicKey::P256(key) => key.to_public_key_der(),
+            PublicKey::P384(key) => key.to_public_key_der(),
+            PublicKey::Secp256k1(key) => key.to_public_key_der(),
+            PublicKey::Ed25519(key) => key.to_public_key_der(),
+        }
+        .map_err(|e| anyhow!("Failed to encode public key: {e}"))?;
+
+        Ok(der.into_vec())
+    }
+
+    /// Returns the PEM encoded `SubjectPublicKeyInfo` of the key.
+    pub fn to_pem(&self) -> Result<String> {
+        let pem = match self {
+            PublicKey::P256(key) => key.to_public_key_pem(Default::default()),
+            PublicKey::P384(key) => key.to_public_key_pem(Default::default()),
+            PublicKey::Secp256k1(key) => key.to_public_key_pem(Default::default()),
+            PublicKey::Ed25519(key) => key.to_public_key_pem(Default::default()),
+        }
+        .map_err(|e| anyhow!("Failed to encode public key as PEM: {e}"))?;
+
+        Ok(pem)
+    }
+
+    /// Returns the SHA-256 hash of the DER encoded key, as used for transparency log ids.
+    pub fn key_id(&self) -> Result<Vec<u8>> {
+        Ok(Sha256::digest(self.to_spki_der()?).to_vec())
+    }
+
+    /// Verifies a signature over a message.
+    ///
+    /// ECDSA signatures are accepted as ASN.1 DER or as raw fixed-size `r || s`.
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> Result<()> {
+        match self {
+            PublicKey::P256(key) => {
+                let sig = match sig.len() {
+                    64 => p256::ecdsa::Signature::from_slice(sig),
+                    _ => p256::ecdsa::Signature::from_der(sig),
+                }?;
+                key.verify(msg, &sig)?;
+            }
+            PublicKey::P384(key) => {
+                let sig = match sig.len() {
+                    96 => p384::ecdsa::Signature::from_slice(sig),
+                    _ => p384::ecdsa::Signature::from_der(sig),
+                }?;
+                key.verify(msg, &sig)?;
+            }
+            PublicKey::Secp256k1(key) => {
+                let sig = match sig.len() {
+                    64 => k256::ecdsa::Signature::from_slice(sig),
+                    _ => k256::ecdsa::Signature::from_der(sig),
+                }?;
+                let sig = sig.normalize_s().unwrap_or(sig);
+                key.verify(msg, &sig)?;
+            }
+            PublicKey::Ed25519(key) => {
+                let sig = ed25519_dalek::Signature::from_slice(sig)?;
+                key.verify(msg, &sig)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies an ECDSA signature over a precomputed message digest.
+    ///
+    /// Ed25519 keys can't verify prehashed messages and always fail.
+    pub fn verify_prehash(&self, digest: &[u8], sig: &[u8]) -> Result<()> {
+        match self {
+            PublicKey::P256(key) => {
+                let sig = match sig.len() {
+                    64 => p256::ecdsa::Signature::from_slice(sig),
+                    _ => p256::ecdsa::Signature::from_der(sig),
+                }?;
+                key.verify_prehash(digest, &sig)?;
+            }
+            PublicKey::P384(key) => {
+                let sig = match sig.len() {
+                    96 => p384::ecdsa::Signature::from_slice(sig),
+                    _ => p384::ecdsa::Signature::from_der(sig),
+                }?;
+                key.verify_prehash(digest, &sig)?;
+            }
+            PublicKey::Secp256k1(key) => {
+                let sig = match sig.len() {
+                    64 => k256::ecdsa::Signature::from_slice(sig),
+                    _ => k256::ecdsa::Signature::from_der(sig),
+                }?;
+                let sig = sig.normalize_s().unwrap_or(sig);
+                key.verify_prehash(digest, &sig)?;
+            }
+            PublicKey::Ed25519(_) => bail!("Ed25519 keys can't verify prehashed messages."),
+        }
+
+        Ok(())
+    }
+}
@@ -1,31 +1,339 @@
+/// Typed model of the Sigstore bundle format.
+pub mod bundle;
+
+mod encoding;
+
+/// Public keys trusted to sign bundles and log entries.
+pub mod keys;
+
+mod merkle;
+
+/// Client for the Rekor transparency log.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rekor;
+
+/// Sigstore trusted root, listing trusted logs and certificate authorities.
+pub mod trust;
+
+/// Offline verification of Sigstore bundles.
+pub mod verify;
+
+/// RFC 3161 timestamp tokens and timestamp authority client.
+pub mod timestamp;
+
+/// In-process stand-ins for a transparency log and a timestamp authority, for tests.
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
+use anyhow::Result;
+/// Re-exported bundle types for convenience.
+pub use bundle::{
+    BundleContent, DsseEnvelope, DsseSignature, MessageSignature, TransparencyLogEntry,
+    VerificationMaterial, VerificationMaterialContent, BUNDLE_V03_MEDIA_TYPE,
+};
+use bundle::{Rfc3161SignedTimestamp, X509Certificate};
+/// Re-exported public key type for convenience.
+pub use keys::PublicKey;
+/// Re-exported Rekor client types for convenience.
+#[cfg(not(target_arch = "wasm32"))]
+pub use rekor::{EntryKind, RekorClient};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+/// Re-exported timestamp client for convenience.
+#[cfg(not(target_arch = "wasm32"))]
+pub use timestamp::TimestampClient;
+/// Re-exported timestamp token type for convenience.
+pub use timestamp::TimestampToken;
+/// Re-exported trusted root type for convenience.
+pub use trust::TrustedRoot;
+/// Re-exported verifier types for convenience.
+pub use verify::{pae, BundleVerification, BundleVerifier};
 
-/// A Sigstore bundle containing signature verification material and a DSSE envelope.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A Sigstore bundle containing signature verification material and signed content.
+///
+/// Follows the protobuf-JSON encoding of the Sigstore bundle spec, v0.1 through v0.3.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SigstoreBundle {
+    #[serde(deserialize_with = "bundle::deserialize_media_type")]
     media_type: String,
-    verification_material: Value,
-    dsse_envelope: Value,
+    verification_material: VerificationMaterial,
+    #[serde(flatten)]
+    content: BundleContent,
 }
 
 impl SigstoreBundle {
-    /// Creates a new Sigstore bundle with the given verification material and DSSE envelope.
+    /// Creates a new v0.3 Sigstore bundle with the given verification material and content.
     ///
     /// # Arguments
     ///
-    /// * `verification_material` - JSON value containing public key or certificate information.
-    /// * `dsse_envelope` - The DSSE envelope containing the signed payload.
+    /// * `verification_material` - Public key or certificate information and log entries.
+    /// * `content` - The DSSE envelope or message signature being bundled.
     ///
     /// # Returns
     ///
     /// A new `SigstoreBundle` with the standard media type.
-    pub fn new(verification_material: Value, dsse_envelope: Value) -> SigstoreBundle {
+    pub fn new(
+        verification_material: VerificationMaterial,
+        content: BundleContent,
+    ) -> SigstoreBundle {
         SigstoreBundle {
-            media_type: "application/vnd.dev.sigstore.bundle.v0.3+json".to_owned(),
+            media_type: BUNDLE_V03_MEDIA_TYPE.to_owned(),
             verification_material,
-            dsse_envelope,
+            content,
         }
     }
+
+    /// Parses a Sigstore bundle from JSON, validating its media type.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - JSON string of the bundle.
+    ///
+    /// # Returns
+    ///
+    /// The parsed bundle, or error if the JSON is malformed or the media type is unsupported.
+    pub fn from_json(json: &str) -> Result<SigstoreBundle> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Serializes the bundle to a JSON string.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Returns the bundle media type.
+    pub fn media_type(&self) -> &str {
+        &self.media_type
+    }
+
+    /// Returns the verification material.
+    pub fn verification_material(&self) -> &VerificationMaterial {
+        &self.verification_material
+    }
+
+    /// Returns the verification material for modification, e.g. to add log entries.
+    pub fn verification_material_mut(&mut self) -> &mut VerificationMaterial {
+        &mut self.verification_material
+    }
+
+    /// Returns the signed content.
+    pub fn content(&self) -> &BundleContent {
+        &self.content
+    }
+
+    /// Returns the DSSE envelope, if the bundle carries one.
+    pub fn dsse_envelope(&self) -> Option<&DsseEnvelope> {
+        match &self.content {
+            BundleContent::DsseEnvelope(envelope) => Some(envelope),
+            BundleContent::MessageSignature(_) => None,
+        }
+    }
+
+    /// Returns the message signature, if the bundle carries one.
+    pub fn message_signature(&self) -> Option<&MessageSignature> {
+        match &self.content {
+            BundleContent::MessageSignature(signature) => Some(signature),
+            BundleContent::DsseEnvelope(_) => None,
+        }
+    }
+
+    /// Returns the public key hint, if the bundle was signed with an out-of-band key.
+    pub fn public_key_hint(&self) -> Option<&str> {
+        match &self.verification_material.content {
+            VerificationMaterialContent::PublicKey(key) => Some(&key.hint),
+            _ => None,
+        }
+    }
+
+    /// Returns the DER encoded signing certificates, leaf first.
+    ///
+    /// Empty if the bundle was signed with an out-of-band key.
+    pub fn certificates(&self) -> Vec<&[u8]> {
+        let certificates: &[X509Certificate] = match &self.verification_material.content {
+            VerificationMaterialContent::PublicKey(_) => &[],
+            VerificationMaterialContent::X509CertificateChain(chain) => &chain.certificates,
+            VerificationMaterialContent::Certificate(certificate) => {
+                std::slice::from_ref(certificate)
+            }
+        };
+
+        certificates
+            .iter()
+            .map(|c| c.raw_bytes.as_slice())
+            .collect()
+    }
+
+    /// Returns the transparency log entries.
+    pub fn tlog_entries(&self) -> &[TransparencyLogEntry] {
+        &self.verification_material.tlog_entries
+    }
+
+    /// Returns the DER encoded RFC 3161 timestamp tokens.
+    pub fn rfc3161_timestamps(&self) -> Vec<&[u8]> {
+        self.verification_material
+            .timestamp_verification_data
+            .iter()
+            .flat_map(|data| &data.rfc3161_timestamps)
+            .map(|Rfc3161SignedTimestamp { signed_timestamp }| signed_timestamp.as_slice())
+            .collect()
+    }
+
+    /// Adds an RFC 3161 timestamp token to the verification material.
+    ///
+    /// The token should cover the bytes returned by [`SigstoreBundle::timestamped_signature`].
+    pub fn add_rfc3161_timestamp(&mut self, token: &TimestampToken) {
+        self.verification_material
+            .timestamp_verification_data
+            .get_or_insert_with(Default::default)
+            .rfc3161_timestamps
+            .push(Rfc3161SignedTimestamp {
+                signed_timestamp: token.as_der().to_vec(),
+            });
+    }
+
+    /// Returns the signature bytes RFC 3161 timestamps are requested over.
+    ///
+    /// This is the first DSSE signature, or the message signature.
+    pub fn timestamped_signature(&self) -> Option<&[u8]> {
+        match &self.content {
+            BundleContent::DsseEnvelope(envelope) => {
+                envelope.signatures.first().map(|s| s.sig.as_slice())
+            }
+            BundleContent::MessageSignature(signature) => Some(&signature.signature),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::bundle::PublicKeyIdentifier;
+
+    fn cosign_style_bundle() -> serde_json::Value {
+        json!({
+            "mediaType": "application/vnd.dev.sigstore.bundle.v0.3+json",
+            "verificationMaterial": {
+                "certificate": { "rawBytes": "MIIBAA==" },
+                "tlogEntries": [{
+                    "logIndex": "25915956",
+                    "logId": { "keyId": "wNI9atQGlz+VWfO6LRygH4QUfY/8W4RFwiT5i5WRgB0=" },
+                    "kindVersion": { "kind": "dsse", "version": "0.0.1" },
+                    "integratedTime": "1709222392",
+                    "inclusionPromise": { "signedEntryTimestamp": "MEUCIQ==" },
+                    "inclusionProof": {
+                        "logIndex": "21752525",
+                        "rootHash": "AAEC",
+                        "treeSize": "21752526",
+                        "hashes": ["AwQF", "BgcI"],
+                        "checkpoint": { "envelope": "rekor.sigstore.dev - 2605736670972794746\n" }
+                    },
+                    "canonicalizedBody": "e30="
+                }],
+                "timestampVerificationData": {
+                    "rfc3161Timestamps": [{ "signedTimestamp": "MIIC" }]
+                }
+            },
+            "dsseEnvelope": {
+                "payload": "e30=",
+                "payloadType": "application/vnd.in-toto+json",
+                "signatures": [{ "sig": "MEQCIA==", "keyid": "" }]
+            }
+        })
+    }
+
+    #[test]
+    fn parses_v03_bundle_with_certificate() {
+        let bundle = SigstoreBundle::from_json(&cosign_style_bundle().to_string()).unwrap();
+
+        assert_eq!(bundle.media_type(), BUNDLE_V03_MEDIA_TYPE);
+        assert_eq!(bundle.certificates(), vec![&[0x30, 0x82, 0x01, 0x00][..]]);
+        assert_eq!(bundle.public_key_hint(), None);
+        assert_eq!(bundle.rfc3161_timestamps(), vec![&[0x30, 0x82, 0x02][..]]);
+
+        let entry = &bundle.tlog_entries()[0];
+        assert_eq!(entry.log_index, 25915956);
+        assert_eq!(entry.integrated_time, 1709222392);
+        assert_eq!(entry.kind_version.kind, "dsse");
+        let proof = entry.inclusion_proof.as_ref().unwrap();
+        assert_eq!(proof.tree_size, 21752526);
+        assert_eq!(proof.hashes, vec![vec![3, 4, 5], vec![6, 7, 8]]);
+
+        let envelope = bundle.dsse_envelope().unwrap();
+        assert_eq!(envelope.payload, b"{}");
+        assert!(bundle.message_signature().is_none());
+    }
+
+    #[test]
+    fn bundle_round_trips_through_json() {
+        let json = cosign_style_bundle();
+        let bundle = SigstoreBundle::from_json(&json.to_string()).unwrap();
+
+        let reserialized: serde_json::Value =
+            serde_json::from_str(&bundle.to_json().unwrap()).unwrap();
+        assert_eq!(reserialized, json);
+    }
+
+    #[test]
+    fn parses_message_signature_bundle() {
+        let json = json!({
+            "mediaType": "application/vnd.dev.sigstore.bundle+json;version=0.2",
+            "verificationMaterial": {
+                "x509CertificateChain": { "certificates": [{ "rawBytes": "AQ==" }, { "rawBytes": "Ag==" }] },
+                "tlogEntries": []
+            },
+            "messageSignature": {
+                "messageDigest": { "algorithm": "SHA2_256", "digest": "AAEC" },
+                "signature": "AwQF"
+            }
+        });
+
+        let bundle = SigstoreBundle::from_json(&json.to_string()).unwrap();
+        assert_eq!(bundle.certificates(), vec![&[1][..], &[2][..]]);
+        assert_eq!(bundle.message_signature().unwrap().signature, vec![3, 4, 5]);
+        assert!(bundle.dsse_envelope().is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_media_type() {
+        let mut json = cosign_style_bundle();
+        json["mediaType"] = json!("application/json");
+
+        let err = SigstoreBundle::from_json(&json.to_string()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unsupported Sigstore bundle media type"));
+    }
+
+    #[test]
+    fn new_bundle_serializes_public_key_hint() {
+        let bundle = SigstoreBundle::new(
+            VerificationMaterial {
+                content: VerificationMaterialContent::PublicKey(PublicKeyIdentifier {
+                    hint: "abcd".to_owned(),
+                }),
+                tlog_entries: vec![],
+                timestamp_verification_data: None,
+            },
+            BundleContent::DsseEnvelope(DsseEnvelope {
+                payload: b"{}".to_vec(),
+                payload_type: "application/vnd.in-toto+json".to_owned(),
+                signatures: vec![],
+            }),
+        );
+
+        assert_eq!(
+            serde_json::to_value(&bundle).unwrap(),
+            json!({
+                "mediaType": "application/vnd.dev.sigstore.bundle.v0.3+json",
+                "verificationMaterial": { "publicKey": { "hint": "abcd" }, "tlogEntries": [] },
+                "dsseEnvelope": {
+                    "payload": "e30=",
+                    "payloadType": "application/vnd.in-toto+json",
+                    "signatures": []
+                }
+            })
+        );
+    }
 }
@@ -0,0 +1,136 @@
+//! RFC 6962 Merkle tree hashing and inclusion proof verification.
+
+use anyhow::{bail, Result};
+use sha2::{Digest, Sha256};
+
+/// Hashes a log entry into a Merkle tree leaf.
+pub(crate) fn leaf_hash(entry: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(entry);
+    hasher.finalize().into()
+}
+
+/// Hashes two child nodes into their parent.
+pub(crate) fn node_hash(left: &[u8], right: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Recomputes the tree root from a leaf and its inclusion proof (RFC 9162 section 2.1.3.2).
+pub(crate) fn root_from_inclusion_proof(
+    index: u64,
+    tree_size: u64,
+    leaf_hash: [u8; 32],
+    proof: &[Vec<u8>],
+) -> Result<[u8; 32]> {
+    if index >= tree_size {
+        bail!("Leaf index {index} is outside the tree of size {tree_size}.");
+    }
+
+    let inner = (64 - (index ^ (tree_size - 1)).leading_zeros()) as usize;
+    let border = (index >> inner).count_ones() as usize;
+
+    if proof.len() != inner + border {
+        bail!(
+            "Inclusion proof has {} hashes, expected {}.",
+            proof.len(),
+            inner + border
+        );
+    }
+
+    let mut hash = leaf_hash;
+    for (i, sibling) in proof.iter().enumerate() {
+        if sibling.len() != 32 {
+            bail!("Inclusion proof hash {i} is not 32 bytes.");
+        }
+
+        hash = if i >= inner || (index >> i) & 1 == 1 {
+            node_hash(sibling, &hash)
+        } else {
+            node_hash(&hash, sibling)
+        };
+    }
+
+    Ok(hash)
+}
+
+/// Builds Merkle trees and inclusion proofs for tests that stand in for a transparency log.
+#[cfg(any(test, feature = "test-utils"))]
+pub(crate) mod testing {
+    use super::*;
+
+    /// Computes the root hash of a tree with the given leaf hashes.
+    pub(crate) fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+        match leaves.len() {
+            0 => Sha256::digest([]).into(),
+            1 => leaves[0],
+            n => {
+                let k = split(n);
+                node_hash(&root(&leaves[..k]), &root(&leaves[k..]))
+            }
+        }
+    }
+
+    /// Computes the inclusion proof of the leaf at `index`.
+    pub(crate) fn inclusion_proof(leaves: &[[u8; 32]], index: usize) -> Vec<Vec<u8>> {
+        if leaves.len() <= 1 {
+            return vec![];
+        }
+
+        let k = split(leaves.len());
+        if index < k {
+            let mut proof = inclusion_proof(&leaves[..k], index);
+            proof.push(root(&leaves[k..]).to_vec());
+            proof
+        } else {
+            let mut proof = inclusion_proof(&leaves[k..], index - k);
+            proof.push(root(&leaves[..k]).to_vec());
+            proof
+        }
+    }
+
+    /// Largest power of two smaller than `n`, the RFC 6962 subtree split point.
+    fn split(n: usize) -> usize {
+        let mut k = 1;
+        while k * 2 < n {
+            k *= 2;
+        }
+        k
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{testing::*, *};
+
+    #[test]
+    fn inclusion_proofs_verify_for_every_leaf() {
+        for size in 1..=9u64 {
+            let leaves = (0..size)
+                .map(|i| leaf_hash(&i.to_be_bytes()))
+                .collect::<Vec<_>>();
+            let expected = root(&leaves);
+
+            for index in 0..size {
+                let proof = inclusion_proof(&leaves, index as usize);
+                let computed =
+                    root_from_inclusion_proof(index, size, leaves[index as usize], &proof).unwrap();
+                assert_eq!(computed, expected, "size {size} index {index}");
+            }
+        }
+    }
+
+    #[test]
+    fn inclusion_proof_rejects_wrong_leaf() {
+        let leaves = (0..5u8).map(|i| leaf_hash(&[i])).collect::<Vec<_>>();
+        let proof = inclusion_proof(&leaves, 2);
+
+        let computed = root_from_inclusion_proof(2, 5, leaf_hash(b"other"), &proof).unwrap();
+        assert_ne!(computed, root(&leaves));
+        assert!(root_from_inclusion_proof(5, 5, leaves[2], &proof).is_err());
+    }
+}
@@ -0,0 +1,572 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Result};
+use base64::engine::{general_purpose::STANDARD as BASE64, Engine};
+use reqwest::{header::LOCATION, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use x509_cert::der::pem::{self, LineEnding};
+
+use crate::{
+    bundle::{
+        Checkpoint, DsseEnvelope, InclusionPromise, InclusionProof, KindVersion, LogId,
+        TransparencyLogEntry,
+    },
+    PublicKey, SigstoreBundle,
+};
+
+/// Base URL of the public-good Sigstore Rekor instance.
+pub const DEFAULT_REKOR_URL: &str = "https://rekor.sigstore.dev";
+
+const ENTRIES_PATH: &str = "/api/v1/log/entries";
+
+/// Rekor entry type used to record a DSSE envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// `dsse` v0.0.1 entries, as produced by current cosign releases
+    Dsse,
+    /// `intoto` v0.0.2 entries, as produced by older cosign releases
+    Intoto,
+}
+
+/// A Rekor log entry, as returned by the Rekor v1 API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    /// Base64 encoded canonicalized entry body
+    pub body: String,
+    /// Unix timestamp the entry was integrated into the log
+    pub integrated_time: i64,
+    /// Hex encoded log id
+    #[serde(rename = "logID")]
+    pub log_id: String,
+    /// Global index of the entry in the log
+    pub log_index: i64,
+    /// Inclusion proof and signed entry timestamp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<LogEntryVerification>,
+}
+
+/// Inclusion evidence for a Rekor log entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntryVerification {
+    /// Inclusion proof against a signed checkpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inclusion_proof: Option<LogEntryInclusionProof>,
+    /// Base64 encoded signed entry timestamp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_entry_timestamp: Option<String>,
+}
+
+/// Merkle inclusion proof for a Rekor log entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntryInclusionProof {
+    /// Signed note checkpoint of the tree the proof is against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<String>,
+    /// Hex encoded sibling hashes, leaf to root
+    pub hashes: Vec<String>,
+    /// Index of the entry within the tree the proof is against
+    pub log_index: i64,
+    /// Hex encoded root hash of the tree
+    pub root_hash: String,
+    /// Size of the tree
+    pub tree_size: i64,
+}
+
+impl TryFrom<LogEntry> for TransparencyLogEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(entry: LogEntry) -> Result<Self> {
+        let canonicalized_body = BASE64
+            .decode(&entry.body)
+            .map_err(|e| anyhow!("Failed to decode Rekor entry body: {e}"))?;
+        let body: Value = serde_json::from_slice(&canonicalized_body)
+            .map_err(|e| anyhow!("Failed to parse Rekor entry body: {e}"))?;
+        let kind_version = KindVersion {
+            kind: body["kind"].as_str().unwrap_or_default().to_owned(),
+            version: body["apiVersion"].as_str().unwrap_or_default().to_owned(),
+        };
+
+        let verification = entry.verification.unwrap_or(LogEntryVerification {
+            inclusion_proof: None,
+            signed_entry_timestamp: None,
+        });
+
+        let inclusion_promise = verification
+            .signed_entry_timestamp
+            .map(|set| {
+                Ok::<_, anyhow::Error>(InclusionPromise {
+                    signed_entry_timestamp: BASE64.decode(set)?,
+                })
+            })
+            .transpose()
+            .map_err(|e| anyhow!("Failed to decode signed entry timestamp: {e}"))?;
+
+        let inclusion_proof = verification
+            .inclusion_proof
+            .map(|proof| {
+                Ok::<_, anyhow::Error>(InclusionProof {
+                    log_index: proof.log_index,
+                    root_hash: hex::decode(proof.root_hash)?,
+                    tree_size: proof.tree_size,
+                    hashes: proof
+                        .hashes
+                        .iter()
+                        .map(hex::decode)
+                        .collect::<Result<_, _>>()?,
+                    checkpoint: proof.checkpoint.map(|envelope| Checkpoint { envelope }),
+                })
+            })
+            .transpose()
+            .map_err(|e| anyhow!("Failed to decode inclusion proof: {e}"))?;
+
+        Ok(TransparencyLogEntry {
+            log_index: entry.log_index,
+            log_id: LogId {
+                key_id: hex::decode(&entry.log_id)
+                    .map_err(|e| anyhow!("Failed to decode log id: {e}"))?,
+            },
+            kind_version,
+            integrated_time: entry.integrated_time,
+            inclusion_promise,
+            inclusion_proof,
+            canonicalized_body,
+        })
+    }
+}
+
+/// Client for the Rekor v1 transparency log API.
+#[derive(Debug, Clone)]
+pub struct RekorClient {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl Default for RekorClient {
+    fn default() -> Self {
+        RekorClient::new(DEFAULT_REKOR_URL)
+    }
+}
+
+impl RekorClient {
+    /// Creates a client for the Rekor instance at `url`, e.g. [`DEFAULT_REKOR_URL`].
+    pub fn new(url: impl Into<String>) -> RekorClient {
+        RekorClient {
+            url: url.into().trim_end_matches('/').to_owned(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Returns the base URL of the Rekor instance.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Uploads a DSSE envelope to the log.
+    ///
+    /// If the log already holds an identical entry, the existing entry is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `envelope` - The signed DSSE envelope.
+    /// * `verifier_pem` - PEM encoded public key or certificate the envelope verifies under.
+    /// * `kind` - Rekor entry type to record the envelope as.
+    ///
+    /// # Returns
+    ///
+    /// The log entry with its inclusion proof and signed entry timestamp, or error if the
+    /// log rejected the entry.
+    pub async fn create_entry(
+        &self,
+        envelope: &DsseEnvelope,
+        verifier_pem: &str,
+        kind: EntryKind,
+    ) -> Result<TransparencyLogEntry> {
+        let proposed_entry = proposed_entry(envelope, verifier_pem, kind)?;
+
+        let response = self
+            .client
+            .post(format!("{}{ENTRIES_PATH}", self.url))
+            .json(&proposed_entry)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::CONFLICT {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .ok_or_else(|| anyhow!("Rekor reported a conflicting entry without a location"))?
+                .to_owned();
+            log::debug!("Entry already in the log at {location}");
+
+            let url = match location.starts_with('/') {
+                true => format!("{}{location}", self.url),
+                false => location,
+            };
+            let response = self.client.get(url).send().await?;
+            return parse_entry_response(response).await;
+        }
+
+        parse_entry_response(response).await
+    }
+
+    /// Retrieves a log entry by its UUID.
+    pub async fn get_entry_by_uuid(&self, uuid: &str) -> Result<TransparencyLogEntry> {
+        let response = self
+            .client
+            .get(format!("{}{ENTRIES_PATH}/{uuid}", self.url))
+            .send()
+            .await?;
+
+        parse_entry_response(response).await
+    }
+
+    /// Retrieves a log entry by its global log index.
+    pub async fn get_entry_by_index(&self, log_index: i64) -> Result<TransparencyLogEntry> {
+        let response = self
+            .client
+            .get(format!("{}{ENTRIES_PATH}", self.url))
+            .query(&[("logIndex", log_index)])
+            .send()
+            .await?;
+
+        parse_entry_response(response).await
+    }
+
+    /// Uploads a bundle's DSSE envelope to the log and records the entry in its
+    /// verification material.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The bundle to log; must carry a DSSE envelope.
+    /// * `kind` - Rekor entry type to record the envelope as.
+    /// * `public_key` - Key the envelope verifies under. Required unless the bundle
+    ///   carries a signing certificate.
+    ///
+    /// # Returns
+    ///
+    /// The recorded log entry, or error if the upload failed.
+    pub async fn log_bundle(
+        &self,
+        bundle: &mut SigstoreBundle,
+        kind: EntryKind,
+        public_key: Option<&PublicKey>,
+    ) -> Result<TransparencyLogEntry> {
+        let envelope = bundle
+            .dsse_envelope()
+            .ok_or_else(|| anyhow!("Only bundles with a DSSE envelope can be logged."))?;
+
+        let verifier_pem = match (bundle.certificates().first(), public_key) {
+            (Some(certificate), _) => {
+                pem::encode_string("CERTIFICATE", LineEnding::LF, certificate)
+                    .map_err(|e| anyhow!("Failed to encode certificate as PEM: {e}"))?
+            }
+            (None, Some(public_key)) => public_key.to_pem()?,
+            (None, None) => {
+                bail!("A public key is required to log a bundle without a certificate.")
+            }
+        };
+
+        let entry = self.create_entry(envelope, &verifier_pem, kind).await?;
+
+        bundle
+            .verification_material_mut()
+            .tlog_entries
+            .push(entry.clone());
+
+        Ok(entry)
+    }
+}
+
+/// Builds the Rekor proposed entry for a DSSE envelope.
+fn proposed_entry(envelope: &DsseEnvelope, verifier_pem: &str, kind: EntryKind) -> Result<Value> {
+    let verifier = BASE64.encode(verifier_pem);
+
+    let entry = match kind {
+        EntryKind::Dsse => json!({
+            "apiVersion": "0.0.1",
+            "kind": "dsse",
+            "spec": {
+                "proposedContent": {
+                    "envelope": serde_json::to_string(envelope)?,
+                    "verifiers": [verifier],
+                }
+            }
+        }),
+        // intoto v0.0.2 expects the payload and signatures base64 encoded twice
+        EntryKind::Intoto => json!({
+            "apiVersion": "0.0.2",
+            "kind": "intoto",
+            "spec": {
+                "content": {
+                    "envelope": {
+                        "payloadType": envelope.payload_type,
+                        "payload": BASE64.encode(BASE64.encode(&envelope.payload)),
+                        "signatures": envelope.signatures.iter().map(|signature| json!({
+                            "sig": BASE64.encode(BASE64.encode(&signature.sig)),
+                            "publicKey": verifier,
+                        })).collect::<Vec<_>>(),
+                    }
+                }
+            }
+        }),
+    };
+
+    Ok(entry)
+}
+
+/// Parses a `{uuid: entry}` response from the Rekor entries API.
+async fn parse_entry_response(response: Response) -> Result<TransparencyLogEntry> {
+    let status = response.status();
+    let text = response.text().await?;
+
+    log::trace!("Rekor response status: {status}, body: {text}");
+
+    if !status.is_success() {
+        bail!("Rekor request failed with status {status}: {text}");
+    }
+
+    let entries: BTreeMap<String, LogEntry> = serde_json::from_str(&text)
+        .map_err(|e| anyhow!("Failed to parse Rekor response: {e}. Response body: {text}"))?;
+
+    let (uuid, entry) = entries
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Rekor returned no log entry"))?;
+    log::debug!("Rekor entry {uuid} at index {}", entry.log_index);
+
+    entry.try_into()
+}
+
+#[cfg(test)]
+mod tests {
+    use p256::ecdsa::{signature::Signer, DerSignature};
+    use sha2::{Digest, Sha256};
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, Request, Respond, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{
+        bundle::{BundleContent, DsseSignature, PublicKeyIdentifier},
+        merkle,
+        testing::{key, TestLog},
+        verify::pae,
+        BundleVerifier, VerificationMaterial, VerificationMaterialContent,
+    };
+
+    const PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+    /// Converts an entry back to the Rekor API response it would be served as.
+    fn rekor_response(entry: &TransparencyLogEntry) -> Value {
+        let proof = entry.inclusion_proof.as_ref().unwrap();
+        let uuid = hex::encode(merkle::leaf_hash(&entry.canonicalized_body));
+
+        json!({
+            uuid: {
+                "body": BASE64.encode(&entry.canonicalized_body),
+                "integratedTime": entry.integrated_time,
+                "logID": hex::encode(&entry.log_id.key_id),
+                "logIndex": entry.log_index,
+                "verification": {
+                    "inclusionProof": {
+                        "checkpoint": proof.checkpoint.as_ref().unwrap().envelope,
+                        "hashes": proof.hashes.iter().map(hex::encode).collect::<Vec<_>>(),
+                        "logIndex": proof.log_index,
+                        "rootHash": hex::encode(&proof.root_hash),
+                        "treeSize": proof.tree_size,
+                    },
+                    "signedEntryTimestamp": BASE64.encode(
+                        &entry.inclusion_promise.as_ref().unwrap().signed_entry_timestamp
+                    ),
+                }
+            }
+        })
+    }
+
+    /// Canonicalizes proposed entries the way Rekor does and logs them in a [`TestLog`].
+    struct MockRekor(TestLog);
+
+    impl MockRekor {
+        fn canonicalize(proposed: &Value) -> (String, Vec<u8>) {
+            let kind = proposed["kind"].as_str().unwrap().to_owned();
+            let spec = match kind.as_str() {
+                "dsse" => {
+                    let content = &proposed["spec"]["proposedContent"];
+                    let envelope: DsseEnvelope =
+                        serde_json::from_str(content["envelope"].as_str().unwrap()).unwrap();
+                    json!({
+                        "payloadHash": {
+                            "algorithm": "sha256",
+                            "value": hex::encode(Sha256::digest(&envelope.payload)),
+                        },
+                        "signatures": [{
+                            "signature": BASE64.encode(&envelope.signatures[0].sig),
+                            "verifier": content["verifiers"][0],
+                        }]
+                    })
+                }
+                "intoto" => {
+                    let envelope = &proposed["spec"]["content"]["envelope"];
+                    let payload = BASE64
+                        .decode(
+                            BASE64
+                                .decode(envelope["payload"].as_str().unwrap())
+                                .unwrap(),
+                        )
+                        .unwrap();
+                    json!({
+                        "content": {
+                            "payloadHash": {
+                                "algorithm": "sha256",
+                                "value": hex::encode(Sha256::digest(payload)),
+                            }
+                        }
+                    })
+                }
+                kind => panic!("unexpected entry kind {kind}"),
+            };
+
+            let body = json!({
+                "apiVersion": proposed["apiVersion"],
+                "kind": kind,
+                "spec": spec,
+            });
+            (kind, serde_jcs::to_vec(&body).unwrap())
+        }
+    }
+
+    impl Respond for MockRekor {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let proposed: Value = serde_json::from_slice(&request.body).unwrap();
+            let (kind, body) = MockRekor::canonicalize(&proposed);
+            let entry = self.0.entry(&kind, body);
+
+            ResponseTemplate::new(201).set_body_json(rekor_response(&entry))
+        }
+    }
+
+    fn public_key_bundle() -> (SigstoreBundle, PublicKey) {
+        let signer = key(6);
+        let payload = br#"{"_type":"https://in-toto.io/Statement/v1"}"#.to_vec();
+        let sig: DerSignature = signer.sign(&pae(PAYLOAD_TYPE, &payload));
+
+        let bundle = SigstoreBundle::new(
+            VerificationMaterial {
+                content: VerificationMaterialContent::PublicKey(PublicKeyIdentifier {
+                    hint: "test".to_owned(),
+                }),
+                tlog_entries: vec![],
+                timestamp_verification_data: None,
+            },
+            BundleContent::DsseEnvelope(DsseEnvelope {
+                payload,
+                payload_type: PAYLOAD_TYPE.to_owned(),
+                signatures: vec![DsseSignature {
+                    sig: sig.as_bytes().to_vec(),
+                    keyid: String::new(),
+                }],
+            }),
+        );
+
+        (bundle, PublicKey::P256(*signer.verifying_key()))
+    }
+
+    #[tokio::test]
+    async fn log_bundle_populates_verifiable_tlog_entries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(ENTRIES_PATH))
+            .respond_with(MockRekor(TestLog { key: key(3) }))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let rekor = RekorClient::new(server.uri());
+        let verifier = BundleVerifier::with_trusted_root(TestLog { key: key(3) }.trusted_root());
+
+        for kind in [EntryKind::Dsse, EntryKind::Intoto] {
+            let (mut bundle, public_key) = public_key_bundle();
+            let entry = rekor
+                .log_bundle(&mut bundle, kind, Some(&public_key))
+                .await
+                .unwrap();
+
+            assert_eq!(bundle.tlog_entries(), std::slice::from_ref(&entry));
+            assert!(entry.inclusion_proof.is_some());
+            assert!(entry.inclusion_promise.is_some());
+
+            let bundle = SigstoreBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+            let verification = verifier.clone().public_key(public_key).verify(&bundle);
+            assert!(verification.is_ok(), "{kind:?}: {verification:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn create_entry_returns_existing_entry_on_conflict() {
+        let log = TestLog { key: key(3) };
+        let (bundle, public_key) = public_key_bundle();
+        let envelope = bundle.dsse_envelope().unwrap();
+        let proposed =
+            proposed_entry(envelope, &public_key.to_pem().unwrap(), EntryKind::Dsse).unwrap();
+        let (_, body) = MockRekor::canonicalize(&proposed);
+        let existing = log.entry("dsse", body);
+        let uuid = hex::encode(merkle::leaf_hash(&existing.canonicalized_body));
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(ENTRIES_PATH))
+            .respond_with(
+                ResponseTemplate::new(409)
+                    .insert_header("Location", format!("{ENTRIES_PATH}/{uuid}").as_str()),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{ENTRIES_PATH}/{uuid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rekor_response(&existing)))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(ENTRIES_PATH))
+            .and(query_param("logIndex", "1003"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rekor_response(&existing)))
+            .mount(&server)
+            .await;
+
+        let rekor = RekorClient::new(format!("{}/", server.uri()));
+        let entry = rekor
+            .create_entry(envelope, &public_key.to_pem().unwrap(), EntryKind::Dsse)
+            .await
+            .unwrap();
+        assert_eq!(entry, existing);
+        assert_eq!(rekor.get_entry_by_index(1003).await.unwrap(), existing);
+    }
+
+    #[tokio::test]
+    async fn surfaces_rekor_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("entry not found"))
+            .mount(&server)
+            .await;
+
+        let err = RekorClient::new(server.uri())
+            .get_entry_by_uuid("missing")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("404"), "{err}");
+
+        let (mut bundle, _) = public_key_bundle();
+        let err = RekorClient::new(server.uri())
+            .log_bundle(&mut bundle, EntryKind::Dsse, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("public key is required"), "{err}");
+    }
+}
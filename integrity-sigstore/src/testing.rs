@@ -0,0 +1,441 @@
+use std::{str::FromStr, time::Duration};
+
+use base64::engine::{general_purpose::STANDARD as BASE64, Engine};
+use cms::{
+    cert::{CertificateChoices, IssuerAndSerialNumber},
+    content_info::{CmsVersion, ContentInfo},
+    signed_data::{
+        CertificateSet, EncapsulatedContentInfo, SignedData, SignerIdentifier, SignerInfo,
+        SignerInfos,
+    },
+};
+use der::{
+    asn1::{Any, Int, ObjectIdentifier, OctetString, SetOfVec},
+    Decode, Encode, Tag,
+};
+use p256::ecdsa::{signature::Signer, DerSignature, SigningKey};
+use sha2::{Digest, Sha256};
+use x509_cert::{
+    attr::Attribute,
+    builder::{Builder, CertificateBuilder, Profile},
+    ext::pkix::ExtendedKeyUsage,
+    name::Name,
+    serial_number::SerialNumber,
+    spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned},
+    time::{Time, Validity},
+    Certificate,
+};
+
+use crate::{
+    bundle::{
+        Checkpoint, InclusionPromise, InclusionProof, KindVersion, LogId, TransparencyLogEntry,
+        X509Certificate, X509CertificateChain,
+    },
+    merkle,
+    timestamp::{
+        EssCertIdV2, MessageImprint, PkiStatusInfo, SigningCertificateV2, TimeStampReq,
+        TimeStampResp, TimestampToken, TstInfo, ID_AA_SIGNING_CERTIFICATE_V2, ID_CONTENT_TYPE,
+        ID_CT_TST_INFO, ID_KP_TIME_STAMPING, ID_MESSAGE_DIGEST, ID_SHA256, ID_SIGNED_DATA,
+        TIMESTAMP_REPLY_CONTENT_TYPE,
+    },
+    trust::{
+        CertificateAuthority, TimeRange, TransparencyLogInstance, TrustedPublicKey,
+        TRUSTED_ROOT_V01_MEDIA_TYPE,
+    },
+    verify::{signed_entry_timestamp_payload, ECDSA_WITH_SHA256},
+    PublicKey, TrustedRoot,
+};
+
+/// Integrated time of every entry logged by [`TestLog`].
+pub const INTEGRATED_TIME: i64 = 1_700_000_100;
+
+/// Deterministic P-256 key derived from a seed byte.
+pub fn key(seed: u8) -> SigningKey {
+    SigningKey::from_slice(&[seed; 32]).unwrap()
+}
+
+/// A transparency log that proves and promises every entry it is given.
+pub struct TestLog {
+    pub key: SigningKey,
+}
+
+impl TestLog {
+    pub fn key_id(&self) -> Vec<u8> {
+        PublicKey::P256(*self.key.verifying_key()).key_id().unwrap()
+    }
+
+    /// Trusted root listing only this log.
+    pub fn trusted_root(&self) -> TrustedRoot {
+        TrustedRoot {
+            media_type: TRUSTED_ROOT_V01_MEDIA_TYPE.to_owned(),
+            tlogs: vec![TransparencyLogInstance {
+                base_url: "https://rekor.example.com".to_owned(),
+                hash_algorithm: "SHA2_256".to_owned(),
+                public_key: TrustedPublicKey {
+                    raw_bytes: PublicKey::P256(*self.key.verifying_key())
+                        .to_spki_der()
+                        .unwrap(),
+                    key_details: "PKIX_ECDSA_P256_SHA_256".to_owned(),
+                    valid_for: TimeRange {
+                        start: chrono::DateTime::from_timestamp(1_600_000_000, 0).unwrap(),
+                        end: None,
+                    },
+                },
+                log_id: LogId {
+                    key_id: self.key_id(),
+                },
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Logs `body` at index 3 of a 5 entry tree, returning a fully proven entry.
+    pub fn entry(&self, kind: &str, body: Vec<u8>) -> TransparencyLogEntry {
+        let mut leaves = (0..5u8)
+            .map(|i| merkle::leaf_hash(&[i]))
+            .collect::<Vec<_>>();
+        leaves[3] = merkle::leaf_hash(&body);
+        let root = merkle::testing::root(&leaves);
+
+        let note = format!("test-rekor\n5\n{}\n", BASE64.encode(root));
+        let note_sig: DerSignature = self.key.sign(note.as_bytes());
+        let mut note_sig_bytes = self.key_id()[..4].to_vec();
+        note_sig_bytes.extend_from_slice(note_sig.as_bytes());
+
+        let mut entry = TransparencyLogEntry {
+            log_index: 1003,
+            log_id: LogId {
+                key_id: self.key_id(),
+            },
+            kind_version: KindVersion {
+                kind: kind.to_owned(),
+                version: "0.0.1".to_owned(),
+            },
+            integrated_time: INTEGRATED_TIME,
+            inclusion_promise: None,
+            inclusion_proof: Some(InclusionProof {
+                log_index: 3,
+                root_hash: root.to_vec(),
+                tree_size: 5,
+                hashes: merkle::testing::inclusion_proof(&leaves, 3),
+                checkpoint: Some(Checkpoint {
+                    envelope: format!(
+                        "{note}\n\u{2014} test-rekor {}\n",
+                        BASE64.encode(note_sig_bytes)
+                    ),
+                }),
+            }),
+            canonicalized_body: body,
+        };
+
+        let set: DerSignature = self
+            .key
+            .sign(&signed_entry_timestamp_payload(&entry).unwrap());
+        entry.inclusion_promise = Some(InclusionPromise {
+            signed_entry_timestamp: set.as_bytes().to_vec(),
+        });
+
+        entry
+    }
+}
+
+/// Generation time of every token issued by [`TimestampAuthority`].
+pub const TIMESTAMP_TIME: i64 = 1_700_000_050;
+
+fn validity(not_before: i64, not_after: i64) -> Validity {
+    let time = |secs: i64| {
+        Time::try_from(std::time::UNIX_EPOCH + Duration::from_secs(secs as u64)).unwrap()
+    };
+    Validity {
+        not_before: time(not_before),
+        not_after: time(not_after),
+    }
+}
+
+fn algorithm(oid: &str) -> AlgorithmIdentifierOwned {
+    AlgorithmIdentifierOwned {
+        oid: ObjectIdentifier::new_unwrap(oid),
+        parameters: None,
+    }
+}
+
+/// Issues a code signing certificate for `public_key` from a fixed test CA.
+///
+/// The certificate is DER encoded and valid from 2020 to 2033.
+pub fn certificate_for(public_key: &PublicKey) -> Vec<u8> {
+    let ca_key = key(20);
+    let spki = SubjectPublicKeyInfoOwned::from_der(&public_key.to_spki_der().unwrap()).unwrap();
+    let mut builder = CertificateBuilder::new(
+        Profile::Leaf {
+            issuer: Name::from_str("CN=test-ca,O=integrity").unwrap(),
+            enable_key_agreement: false,
+            enable_key_encipherment: false,
+        },
+        SerialNumber::new(&[3]).unwrap(),
+        validity(1_600_000_000, 2_000_000_000),
+        Name::from_str("CN=signer,O=integrity").unwrap(),
+        spki,
+        &ca_key,
+    )
+    .unwrap();
+    builder
+        .add_extension(&ExtendedKeyUsage(vec![ObjectIdentifier::new_unwrap(
+            "1.3.6.1.5.5.7.3.3",
+        )]))
+        .unwrap();
+
+    builder.build::<DerSignature>().unwrap().to_der().unwrap()
+}
+
+/// An in-process RFC 3161 timestamp authority with its own test CA.
+pub struct TimestampAuthority {
+    key: SigningKey,
+    certificate: Certificate,
+    ca: Certificate,
+}
+
+impl Default for TimestampAuthority {
+    fn default() -> Self {
+        TimestampAuthority::new()
+    }
+}
+
+impl TimestampAuthority {
+    /// Creates a timestamp authority with a fixed key.
+    pub fn new() -> TimestampAuthority {
+        TimestampAuthority::with_seed(10)
+    }
+
+    /// Creates a timestamp authority whose CA and signing keys derive from `seed`.
+    pub fn with_seed(seed: u8) -> TimestampAuthority {
+        let ca_key = key(seed);
+        let ca_name = Name::from_str("CN=test-tsa-ca,O=integrity").unwrap();
+        let ca = CertificateBuilder::new(
+            Profile::Root,
+            SerialNumber::new(&[1]).unwrap(),
+            validity(1_600_000_000, 2_000_000_000),
+            ca_name.clone(),
+            SubjectPublicKeyInfoOwned::from_key(*ca_key.verifying_key()).unwrap(),
+            &ca_key,
+        )
+        .unwrap()
+        .build::<DerSignature>()
+        .unwrap();
+
+        let key = key(seed.wrapping_add(1));
+        let mut builder = CertificateBuilder::new(
+            Profile::Leaf {
+                issuer: ca_name,
+                enable_key_agreement: false,
+                enable_key_encipherment: false,
+            },
+            SerialNumber::new(&[2]).unwrap(),
+            validity(1_600_000_000, 2_000_000_000),
+            Name::from_str("CN=test-tsa,O=integrity").unwrap(),
+            SubjectPublicKeyInfoOwned::from_key(*key.verifying_key()).unwrap(),
+            &ca_key,
+        )
+        .unwrap();
+        builder
+            .add_extension(&ExtendedKeyUsage(vec![ObjectIdentifier::new_unwrap(
+                ID_KP_TIME_STAMPING,
+            )]))
+            .unwrap();
+        let certificate = builder.build::<DerSignature>().unwrap();
+
+        TimestampAuthority {
+            key,
+            certificate,
+            ca,
+        }
+    }
+
+    /// Generation time of the tokens this authority issues, as a unix timestamp.
+    pub fn time(&self) -> i64 {
+        TIMESTAMP_TIME
+    }
+
+    /// Certificate this authority signs tokens with.
+    pub fn certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+
+    /// Trusted root listing only this authority.
+    pub fn trusted_root(&self) -> TrustedRoot {
+        TrustedRoot {
+            media_type: TRUSTED_ROOT_V01_MEDIA_TYPE.to_owned(),
+            timestamp_authorities: vec![CertificateAuthority {
+                subject: Default::default(),
+                uri: "https://tsa.example.com".to_owned(),
+                cert_chain: X509CertificateChain {
+                    certificates: [&self.certificate, &self.ca]
+                        .into_iter()
+                        .map(|certificate| X509Certificate {
+                            raw_bytes: certificate.to_der().unwrap(),
+                        })
+                        .collect(),
+                },
+                valid_for: TimeRange {
+                    start: chrono::DateTime::from_timestamp(1_600_000_000, 0).unwrap(),
+                    end: None,
+                },
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Issues a token over the SHA-256 digest of `data`.
+    pub fn issue(&self, data: &[u8]) -> TimestampToken {
+        let imprint = MessageImprint {
+            hash_algorithm: algorithm(ID_SHA256),
+            hashed_message: OctetString::new(Sha256::digest(data).to_vec()).unwrap(),
+        };
+        self.issue_for(imprint, None, &self.certificate)
+    }
+
+    /// Issues a token over the SHA-256 digest of `data` whose signing certificate attribute
+    /// names `certificate` instead of the TSA's own.
+    pub fn issue_naming(&self, data: &[u8], certificate: &Certificate) -> TimestampToken {
+        let imprint = MessageImprint {
+            hash_algorithm: algorithm(ID_SHA256),
+            hashed_message: OctetString::new(Sha256::digest(data).to_vec()).unwrap(),
+        };
+        self.issue_for(imprint, None, certificate)
+    }
+
+    /// Answers a DER encoded RFC 3161 request with a DER encoded response.
+    pub fn respond(&self, request: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let request = TimeStampReq::from_der(request)?;
+        let token = self.issue_for(request.message_imprint, request.nonce, &self.certificate);
+
+        let response = TimeStampResp {
+            status: PkiStatusInfo {
+                status: 0,
+                status_string: None,
+                fail_info: None,
+            },
+            time_stamp_token: Some(ContentInfo::from_der(token.as_der())?),
+        };
+
+        Ok(response.to_der()?)
+    }
+
+    /// Starts a local HTTP server answering timestamp requests.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn serve(self) -> wiremock::MockServer {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(self)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn issue_for(
+        &self,
+        message_imprint: MessageImprint,
+        nonce: Option<Int>,
+        signing_certificate: &Certificate,
+    ) -> TimestampToken {
+        let gen_time = chrono::DateTime::from_timestamp(TIMESTAMP_TIME, 0)
+            .unwrap()
+            .format("%Y%m%d%H%M%SZ")
+            .to_string();
+        let info = TstInfo {
+            version: 1,
+            policy: ObjectIdentifier::new_unwrap("1.3.6.1.4.1.57264.2"),
+            message_imprint,
+            serial_number: Int::new(&[7]).unwrap(),
+            gen_time: Any::new(Tag::GeneralizedTime, gen_time.as_bytes()).unwrap(),
+            accuracy: None,
+            ordering: false,
+            nonce,
+            tsa: None,
+            extensions: None,
+        };
+        let econtent = info.to_der().unwrap();
+
+        let attribute = |oid: &str, value: Any| Attribute {
+            oid: ObjectIdentifier::new_unwrap(oid),
+            values: SetOfVec::try_from(vec![value]).unwrap(),
+        };
+        let signed_attrs = SetOfVec::try_from(vec![
+            attribute(
+                ID_CONTENT_TYPE,
+                Any::encode_from(&ObjectIdentifier::new_unwrap(ID_CT_TST_INFO)).unwrap(),
+            ),
+            attribute(
+                ID_MESSAGE_DIGEST,
+                Any::encode_from(&OctetString::new(Sha256::digest(&econtent).to_vec()).unwrap())
+                    .unwrap(),
+            ),
+            attribute(
+                ID_AA_SIGNING_CERTIFICATE_V2,
+                Any::encode_from(&SigningCertificateV2 {
+                    certs: vec![EssCertIdV2 {
+                        hash_algorithm: None,
+                        cert_hash: OctetString::new(
+                            Sha256::digest(signing_certificate.to_der().unwrap()).to_vec(),
+                        )
+                        .unwrap(),
+                        issuer_serial: None,
+                    }],
+                    policies: None,
+                })
+                .unwrap(),
+            ),
+        ])
+        .unwrap();
+        let signature: DerSignature = self.key.sign(&signed_attrs.to_der().unwrap());
+
+        let signed_data = SignedData {
+            version: CmsVersion::V3,
+            digest_algorithms: SetOfVec::try_from(vec![algorithm(ID_SHA256)]).unwrap(),
+            encap_content_info: EncapsulatedContentInfo {
+                econtent_type: ObjectIdentifier::new_unwrap(ID_CT_TST_INFO),
+                econtent: Some(Any::encode_from(&OctetString::new(econtent).unwrap()).unwrap()),
+            },
+            certificates: Some(CertificateSet(
+                SetOfVec::try_from(vec![CertificateChoices::Certificate(
+                    self.certificate.clone(),
+                )])
+                .unwrap(),
+            )),
+            crls: None,
+            signer_infos: SignerInfos(
+                SetOfVec::try_from(vec![SignerInfo {
+                    version: CmsVersion::V1,
+                    sid: SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+                        issuer: self.certificate.tbs_certificate.issuer.clone(),
+                        serial_number: self.certificate.tbs_certificate.serial_number.clone(),
+                    }),
+                    digest_alg: algorithm(ID_SHA256),
+                    signed_attrs: Some(signed_attrs),
+                    signature_algorithm: algorithm(ECDSA_WITH_SHA256),
+                    signature: OctetString::new(signature.as_bytes()).unwrap(),
+                    unsigned_attrs: None,
+                }])
+                .unwrap(),
+            ),
+        };
+
+        let content_info = ContentInfo {
+            content_type: ObjectIdentifier::new_unwrap(ID_SIGNED_DATA),
+            content: Any::encode_from(&signed_data).unwrap(),
+        };
+
+        TimestampToken::from_der(&content_info.to_der().unwrap()).unwrap()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl wiremock::Respond for TimestampAuthority {
+    fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+        match TimestampAuthority::respond(self, &request.body) {
+            Ok(response) => wiremock::ResponseTemplate::new(200)
+                .insert_header("Content-Type", TIMESTAMP_REPLY_CONTENT_TYPE)
+                .set_body_bytes(response),
+            Err(e) => wiremock::ResponseTemplate::new(400).set_body_string(e.to_string()),
+        }
+    }
+}
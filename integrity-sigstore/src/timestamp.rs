@@ -0,0 +1,713 @@
+//! RFC 3161 timestamp tokens: requesting them from a timestamp authority (TSA) and
+//! verifying them offline against a trusted root.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use cms::{
+    cert::CertificateChoices,
+    content_info::ContentInfo,
+    signed_data::{SignedData, SignerIdentifier},
+};
+use der::{
+    asn1::{Any, Int, ObjectIdentifier, OctetString},
+    Decode, Encode, Sequence, Tag, Tagged,
+};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use x509_cert::{
+    ext::{
+        pkix::{
+            name::{GeneralName, GeneralNames},
+            ExtendedKeyUsage, SubjectKeyIdentifier,
+        },
+        Extensions,
+    },
+    serial_number::SerialNumber,
+    spki::AlgorithmIdentifierOwned,
+    Certificate,
+};
+
+use crate::{
+    trust::TrustedRoot,
+    verify::{check_validity, verify_chain},
+    PublicKey, SigstoreBundle,
+};
+
+pub(crate) const ID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+pub(crate) const ID_CT_TST_INFO: &str = "1.2.840.113549.1.9.16.1.4";
+pub(crate) const ID_CONTENT_TYPE: &str = "1.2.840.113549.1.9.3";
+pub(crate) const ID_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+const ID_AA_SIGNING_CERTIFICATE: &str = "1.2.840.113549.1.9.16.2.12";
+pub(crate) const ID_AA_SIGNING_CERTIFICATE_V2: &str = "1.2.840.113549.1.9.16.2.47";
+pub(crate) const ID_KP_TIME_STAMPING: &str = "1.3.6.1.5.5.7.3.8";
+pub(crate) const ID_SHA256: &str = "2.16.840.1.101.3.4.2.1";
+const ID_SHA384: &str = "2.16.840.1.101.3.4.2.2";
+const ID_SHA512: &str = "2.16.840.1.101.3.4.2.3";
+const ID_ED25519: &str = "1.3.101.112";
+
+/// Content type of RFC 3161 timestamp requests.
+pub const TIMESTAMP_QUERY_CONTENT_TYPE: &str = "application/timestamp-query";
+/// Content type of RFC 3161 timestamp responses.
+pub const TIMESTAMP_REPLY_CONTENT_TYPE: &str = "application/timestamp-reply";
+
+/// `MessageImprint` (RFC 3161 section 2.4.1).
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct MessageImprint {
+    pub(crate) hash_algorithm: AlgorithmIdentifierOwned,
+    pub(crate) hashed_message: OctetString,
+}
+
+/// `TimeStampReq` (RFC 3161 section 2.4.1).
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct TimeStampReq {
+    pub(crate) version: u8,
+    pub(crate) message_imprint: MessageImprint,
+    #[asn1(optional = "true")]
+    pub(crate) req_policy: Option<ObjectIdentifier>,
+    #[asn1(optional = "true")]
+    pub(crate) nonce: Option<Int>,
+    #[asn1(default = "Default::default")]
+    pub(crate) cert_req: bool,
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT", optional = "true")]
+    pub(crate) extensions: Option<Extensions>,
+}
+
+/// `PKIStatusInfo` (RFC 3161 section 2.4.2).
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct PkiStatusInfo {
+    pub(crate) status: u8,
+    #[asn1(optional = "true")]
+    pub(crate) status_string: Option<Vec<String>>,
+    #[asn1(optional = "true")]
+    pub(crate) fail_info: Option<der::asn1::BitString>,
+}
+
+/// `TimeStampResp` (RFC 3161 section 2.4.2).
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct TimeStampResp {
+    pub(crate) status: PkiStatusInfo,
+    #[asn1(optional = "true")]
+    pub(crate) time_stamp_token: Option<ContentInfo>,
+}
+
+/// `Accuracy` (RFC 3161 section 2.4.2).
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct Accuracy {
+    #[asn1(optional = "true")]
+    pub(crate) seconds: Option<u32>,
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT", optional = "true")]
+    pub(crate) millis: Option<u16>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    pub(crate) micros: Option<u16>,
+}
+
+/// `TSTInfo` (RFC 3161 section 2.4.2).
+///
+/// `genTime` is kept as its raw encoding, as TSAs commonly include fractional seconds
+/// which `der`'s `GeneralizedTime` rejects.
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct TstInfo {
+    pub(crate) version: u8,
+    pub(crate) policy: ObjectIdentifier,
+    pub(crate) message_imprint: MessageImprint,
+    pub(crate) serial_number: Int,
+    pub(crate) gen_time: Any,
+    #[asn1(optional = "true")]
+    pub(crate) accuracy: Option<Accuracy>,
+    #[asn1(default = "Default::default")]
+    pub(crate) ordering: bool,
+    #[asn1(optional = "true")]
+    pub(crate) nonce: Option<Int>,
+    #[asn1(context_specific = "0", tag_mode = "EXPLICIT", optional = "true")]
+    pub(crate) tsa: Option<Any>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    pub(crate) extensions: Option<Extensions>,
+}
+
+/// `IssuerSerial` (RFC 2634 section 5.4.1).
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct IssuerSerial {
+    pub(crate) issuer: GeneralNames,
+    pub(crate) serial_number: SerialNumber,
+}
+
+/// `ESSCertID` (RFC 2634 section 5.4.1), identifying a certificate by its SHA-1 hash.
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+struct EssCertId {
+    cert_hash: OctetString,
+    #[asn1(optional = "true")]
+    issuer_serial: Option<IssuerSerial>,
+}
+
+/// `SigningCertificate` (RFC 2634 section 5.4).
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+struct SigningCertificate {
+    certs: Vec<EssCertId>,
+    #[asn1(optional = "true")]
+    policies: Option<Vec<Any>>,
+}
+
+/// `ESSCertIDv2` (RFC 5035 section 4).
+///
+/// An absent `hashAlgorithm` is the DEFAULT of SHA-256.
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct EssCertIdV2 {
+    #[asn1(optional = "true")]
+    pub(crate) hash_algorithm: Option<AlgorithmIdentifierOwned>,
+    pub(crate) cert_hash: OctetString,
+    #[asn1(optional = "true")]
+    pub(crate) issuer_serial: Option<IssuerSerial>,
+}
+
+/// `SigningCertificateV2` (RFC 5035 section 3).
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub(crate) struct SigningCertificateV2 {
+    pub(crate) certs: Vec<EssCertIdV2>,
+    #[asn1(optional = "true")]
+    pub(crate) policies: Option<Vec<Any>>,
+}
+
+/// An RFC 3161 timestamp token, a CMS `SignedData` over a `TSTInfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimestampToken {
+    der: Vec<u8>,
+    signed_data: SignedData,
+    econtent: Vec<u8>,
+    info: TstInfo,
+    gen_time: DateTime<Utc>,
+}
+
+impl TimestampToken {
+    /// Parses a DER encoded timestamp token.
+    ///
+    /// # Arguments
+    ///
+    /// * `der` - DER encoded CMS `ContentInfo` of the token.
+    ///
+    /// # Returns
+    ///
+    /// The parsed token, or error if it isn't a well-formed timestamp token.
+    pub fn from_der(der: &[u8]) -> Result<TimestampToken> {
+        let content_info = ContentInfo::from_der(der)
+            .map_err(|e| anyhow!("Failed to parse timestamp token: {e}"))?;
+
+        TimestampToken::from_content_info(content_info)
+    }
+
+    fn from_content_info(content_info: ContentInfo) -> Result<TimestampToken> {
+        if content_info.content_type.to_string() != ID_SIGNED_DATA {
+            bail!(
+                "Timestamp token is not CMS SignedData ({}).",
+                content_info.content_type
+            );
+        }
+
+        let der = content_info
+            .to_der()
+            .map_err(|e| anyhow!("Failed to encode timestamp token: {e}"))?;
+        let signed_data: SignedData = content_info
+            .content
+            .decode_as()
+            .map_err(|e| anyhow!("Failed to parse timestamp token SignedData: {e}"))?;
+
+        let encap = &signed_data.encap_content_info;
+        if encap.econtent_type.to_string() != ID_CT_TST_INFO {
+            bail!(
+                "Timestamp token does not contain a TSTInfo ({}).",
+                encap.econtent_type
+            );
+        }
+
+        let econtent = encap
+            .econtent
+            .as_ref()
+            .ok_or_else(|| anyhow!("Timestamp token has no TSTInfo content."))?
+            .decode_as::<OctetString>()
+            .map_err(|e| anyhow!("Failed to parse TSTInfo content: {e}"))?
+            .into_bytes();
+        let info =
+            TstInfo::from_der(&econtent).map_err(|e| anyhow!("Failed to parse TSTInfo: {e}"))?;
+        let gen_time = parse_generalized_time(&info.gen_time)?;
+
+        Ok(TimestampToken {
+            der,
+            signed_data,
+            econtent,
+            info,
+            gen_time,
+        })
+    }
+
+    /// Returns the DER encoding of the token.
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// Returns the time the TSA asserts the token was generated at.
+    pub fn gen_time(&self) -> DateTime<Utc> {
+        self.gen_time
+    }
+
+    /// Returns the digest of the timestamped data, as recorded in the token.
+    pub fn hashed_message(&self) -> &[u8] {
+        self.info.message_imprint.hashed_message.as_bytes()
+    }
+
+    /// Verifies the token was issued over `data` by one of the root's timestamp authorities.
+    ///
+    /// Checks the message imprint, the CMS signature and its signed attributes, that the
+    /// signing certificate attribute names the certificate that made the signature, and that
+    /// this certificate is valid for timestamping at the token's time and chains to a trusted
+    /// timestamp authority.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The timestamped data.
+    /// * `trusted_root` - Trust root listing the trusted timestamp authorities.
+    ///
+    /// # Returns
+    ///
+    /// The verified timestamp, or error describing why verification failed.
+    pub fn verify(&self, data: &[u8], trusted_root: &TrustedRoot) -> Result<DateTime<Utc>> {
+        let imprint = &self.info.message_imprint;
+        let expected = digest(&imprint.hash_algorithm.oid, data)?;
+        if imprint.hashed_message.as_bytes() != expected.as_slice() {
+            bail!("Timestamp verification failed: token does not cover the given data.");
+        }
+
+        let time = self.gen_time.timestamp();
+        let signer_info = match self.signed_data.signer_infos.0.as_slice() {
+            [signer_info] => signer_info,
+            _ => bail!("Timestamp verification failed: token must have exactly one signer."),
+        };
+
+        let mut errors = vec![];
+        for authority in &trusted_root.timestamp_authorities {
+            if !authority.valid_for.contains(time) {
+                continue;
+            }
+
+            let chain = authority
+                .cert_chain
+                .certificates
+                .iter()
+                .map(|c| Certificate::from_der(&c.raw_bytes))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| anyhow!("Failed to parse timestamp authority chain: {e}"))?;
+
+            let result =
+                self.signing_certificate(&signer_info.sid, &chain)
+                    .and_then(|certificate| {
+                        self.verify_signer(certificate, &chain, time)?;
+                        Ok(())
+                    });
+
+            match result {
+                Ok(()) => return Ok(self.gen_time),
+                Err(e) => errors.push(format!("{}: {e}", authority.uri)),
+            }
+        }
+
+        bail!(
+            "Timestamp verification failed: token is not signed by a trusted timestamp authority ({}).",
+            errors.join("; ")
+        )
+    }
+
+    /// Finds the certificate identified by the signer identifier, in the token or the chain.
+    fn signing_certificate<'a>(
+        &'a self,
+        sid: &SignerIdentifier,
+        chain: &'a [Certificate],
+    ) -> Result<&'a Certificate> {
+        let embedded = self
+            .signed_data
+            .certificates
+            .iter()
+            .flat_map(|set| set.0.iter())
+            .filter_map(|choice| match choice {
+                CertificateChoices::Certificate(certificate) => Some(certificate),
+                CertificateChoices::Other(_) => None,
+            });
+
+        embedded
+            .chain(chain)
+            .find(|certificate| match sid {
+                SignerIdentifier::IssuerAndSerialNumber(id) => {
+                    certificate.tbs_certificate.issuer == id.issuer
+                        && certificate.tbs_certificate.serial_number == id.serial_number
+                }
+                SignerIdentifier::SubjectKeyIdentifier(id) => certificate
+                    .tbs_certificate
+                    .get::<SubjectKeyIdentifier>()
+                    .ok()
+                    .flatten()
+                    .is_some_and(|(_, ski)| ski == *id),
+            })
+            .ok_or_else(|| anyhow!("signing certificate not found"))
+    }
+
+    fn verify_signer(
+        &self,
+        certificate: &Certificate,
+        chain: &[Certificate],
+        time: i64,
+    ) -> Result<()> {
+        check_validity(certificate, time)?;
+
+        let usage = certificate
+            .tbs_certificate
+            .get::<ExtendedKeyUsage>()
+            .map_err(|e| anyhow!("failed to parse extended key usage: {e}"))?;
+        if !usage.is_some_and(|(_, eku)| {
+            eku.0
+                .iter()
+                .any(|oid| oid.to_string() == ID_KP_TIME_STAMPING)
+        }) {
+            bail!("signing certificate is not valid for timestamping");
+        }
+
+        verify_chain(certificate, chain, &[time])?;
+
+        let signer_info = &self.signed_data.signer_infos.0.as_slice()[0];
+        let signed_attrs = signer_info
+            .signed_attrs
+            .as_ref()
+            .ok_or_else(|| anyhow!("token has no signed attributes"))?;
+
+        let attribute = |oid: &str| {
+            signed_attrs
+                .iter()
+                .find(|attr| attr.oid.to_string() == oid)
+                .and_then(|attr| attr.values.iter().next())
+                .ok_or_else(|| anyhow!("token is missing signed attribute {oid}"))
+        };
+
+        let content_type: ObjectIdentifier = attribute(ID_CONTENT_TYPE)?.decode_as()?;
+        if content_type.to_string() != ID_CT_TST_INFO {
+            bail!("signed content type is not TSTInfo");
+        }
+
+        let message_digest: OctetString = attribute(ID_MESSAGE_DIGEST)?.decode_as()?;
+        if message_digest.as_bytes() != digest(&signer_info.digest_alg.oid, &self.econtent)? {
+            bail!("signed message digest does not match the TSTInfo");
+        }
+
+        // the first ESSCertID must name the certificate the signature is checked against,
+        // otherwise the token could be re-signed by any other certificate of the TSA
+        let (cert_hash, expected_hash, issuer_serial) =
+            if let Ok(value) = attribute(ID_AA_SIGNING_CERTIFICATE_V2) {
+                let attribute: SigningCertificateV2 = value.decode_as()?;
+                let cert_id =
+                    attribute.certs.into_iter().next().ok_or_else(|| {
+                        anyhow!("signing certificate attribute names no certificate")
+                    })?;
+                let hash_algorithm = match &cert_id.hash_algorithm {
+                    Some(algorithm) => algorithm.oid,
+                    None => ObjectIdentifier::new_unwrap(ID_SHA256),
+                };
+                let expected_hash = digest(&hash_algorithm, &certificate.to_der()?)?;
+                (cert_id.cert_hash, expected_hash, cert_id.issuer_serial)
+            } else if let Ok(value) = attribute(ID_AA_SIGNING_CERTIFICATE) {
+                let attribute: SigningCertificate = value.decode_as()?;
+                let cert_id =
+                    attribute.certs.into_iter().next().ok_or_else(|| {
+                        anyhow!("signing certificate attribute names no certificate")
+                    })?;
+                // ESSCertID hashes are always SHA-1, which is not accepted anywhere else
+                let expected_hash = Sha1::digest(certificate.to_der()?).to_vec();
+                (cert_id.cert_hash, expected_hash, cert_id.issuer_serial)
+            } else {
+                bail!("token has no signing certificate attribute");
+            };
+
+        let tbs = &certificate.tbs_certificate;
+        let issuer_matches = issuer_serial.is_none_or(|issuer_serial| {
+            issuer_serial.serial_number == tbs.serial_number
+                && issuer_serial.issuer.iter().any(
+                    |name| matches!(name, GeneralName::DirectoryName(name) if *name == tbs.issuer),
+                )
+        });
+        if cert_hash.as_bytes() != expected_hash || !issuer_matches {
+            bail!("signing certificate attribute does not identify the signing certificate");
+        }
+
+        let signed_attrs = signed_attrs
+            .to_der()
+            .map_err(|e| anyhow!("failed to encode signed attributes: {e}"))?;
+        let key = PublicKey::from_certificate(certificate)?;
+        let signature = signer_info.signature.as_bytes();
+
+        let result = match (
+            &key,
+            signer_info.signature_algorithm.oid.to_string().as_str(),
+        ) {
+            (PublicKey::Ed25519(_), ID_ED25519) => key.verify(&signed_attrs, signature),
+            (PublicKey::Ed25519(_), algorithm) => {
+                bail!("unsupported signature algorithm {algorithm} for an Ed25519 key")
+            }
+            _ => key.verify_prehash(
+                &digest(&signer_info.digest_alg.oid, &signed_attrs)?,
+                signature,
+            ),
+        };
+
+        result.map_err(|_| anyhow!("invalid token signature"))
+    }
+}
+
+/// Hashes `data` with the digest algorithm identified by `oid`.
+pub(crate) fn digest(oid: &ObjectIdentifier, data: &[u8]) -> Result<Vec<u8>> {
+    let digest = match oid.to_string().as_str() {
+        ID_SHA256 => Sha256::digest(data).to_vec(),
+        ID_SHA384 => Sha384::digest(data).to_vec(),
+        ID_SHA512 => Sha512::digest(data).to_vec(),
+        oid => bail!("Unsupported digest algorithm {oid}."),
+    };
+
+    Ok(digest)
+}
+
+/// Parses a `GeneralizedTime`, allowing fractional seconds.
+fn parse_generalized_time(time: &Any) -> Result<DateTime<Utc>> {
+    if time.tag() != Tag::GeneralizedTime {
+        bail!("Timestamp genTime is not a GeneralizedTime.");
+    }
+
+    let value = std::str::from_utf8(time.value())?;
+    let value = value
+        .strip_suffix('Z')
+        .ok_or_else(|| anyhow!("Timestamp genTime '{value}' is not in UTC."))?;
+    let (seconds, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+    let time = NaiveDateTime::parse_from_str(seconds, "%Y%m%d%H%M%S")
+        .map_err(|e| anyhow!("Failed to parse timestamp genTime '{value}': {e}"))?;
+    let nanos = match fraction {
+        "" => 0,
+        fraction => format!("{fraction:0<9}")[..9]
+            .parse::<i64>()
+            .map_err(|e| anyhow!("Failed to parse timestamp genTime '{value}': {e}"))?,
+    };
+
+    Ok(time.and_utc() + chrono::Duration::nanoseconds(nanos))
+}
+
+/// Encodes a nonce as a canonical ASN.1 INTEGER.
+fn nonce_int(nonce: u64) -> Result<Int> {
+    Ok(Any::encode_from(&nonce)?.decode_as()?)
+}
+
+/// Builds a DER encoded RFC 3161 request for a SHA-256 imprint of `data`.
+pub(crate) fn timestamp_request(data: &[u8], nonce: u64) -> Result<Vec<u8>> {
+    let request = TimeStampReq {
+        version: 1,
+        message_imprint: MessageImprint {
+            hash_algorithm: AlgorithmIdentifierOwned {
+                oid: ObjectIdentifier::new_unwrap(ID_SHA256),
+                parameters: None,
+            },
+            hashed_message: OctetString::new(Sha256::digest(data).to_vec())?,
+        },
+        req_policy: None,
+        nonce: Some(nonce_int(nonce)?),
+        cert_req: true,
+        extensions: None,
+    };
+
+    Ok(request.to_der()?)
+}
+
+/// Extracts the token from a DER encoded RFC 3161 response, checking it answers the request.
+pub(crate) fn parse_timestamp_response(
+    response: &[u8],
+    data: &[u8],
+    nonce: u64,
+) -> Result<TimestampToken> {
+    let response = TimeStampResp::from_der(response)
+        .map_err(|e| anyhow!("Failed to parse timestamp response: {e}"))?;
+
+    // 0 = granted, 1 = granted with modifications
+    if response.status.status > 1 {
+        bail!(
+            "Timestamp request rejected with status {}: {}",
+            response.status.status,
+            response.status.status_string.unwrap_or_default().join("; ")
+        );
+    }
+
+    let token = TimestampToken::from_content_info(
+        response
+            .time_stamp_token
+            .ok_or_else(|| anyhow!("Timestamp response has no token."))?,
+    )?;
+
+    if token.hashed_message() != Sha256::digest(data).as_slice() {
+        bail!("Timestamp token does not cover the requested data.");
+    }
+
+    if token.info.nonce != Some(nonce_int(nonce)?) {
+        bail!("Timestamp token nonce does not match the request.");
+    }
+
+    Ok(token)
+}
+
+/// Client for an RFC 3161 timestamp authority.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct TimestampClient {
+    url: String,
+    client: reqwest::Client,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TimestampClient {
+    /// Creates a client for the timestamp authority at `url`.
+    pub fn new(url: impl Into<String>) -> TimestampClient {
+        TimestampClient {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Returns the URL of the timestamp authority.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Requests a timestamp token over the SHA-256 digest of `data`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to timestamp, e.g. signature bytes.
+    ///
+    /// # Returns
+    ///
+    /// The timestamp token, or error if the request failed or the response doesn't answer it.
+    pub async fn timestamp(&self, data: &[u8]) -> Result<TimestampToken> {
+        let nonce = rand::random::<u64>() >> 1;
+        let request = timestamp_request(data, nonce)?;
+
+        let response = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, TIMESTAMP_QUERY_CONTENT_TYPE)
+            .body(request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let body = response.bytes().await?;
+
+        log::trace!("TSA response status: {status}, {} bytes", body.len());
+
+        if !status.is_success() {
+            bail!(
+                "Timestamp request failed with status {status}: {}",
+                String::from_utf8_lossy(&body)
+            );
+        }
+
+        parse_timestamp_response(&body, data, nonce)
+    }
+
+    /// Timestamps a bundle's signature and records the token in its verification material.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The bundle to timestamp.
+    ///
+    /// # Returns
+    ///
+    /// The timestamp token added to the bundle, or error if the bundle has no signature or
+    /// the request failed.
+    pub async fn timestamp_bundle(&self, bundle: &mut SigstoreBundle) -> Result<TimestampToken> {
+        let signature = bundle
+            .timestamped_signature()
+            .ok_or_else(|| anyhow!("Bundle has no signature to timestamp."))?;
+
+        let token = self.timestamp(signature).await?;
+        bundle.add_rfc3161_timestamp(&token);
+
+        Ok(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TimestampAuthority;
+
+    #[test]
+    fn parses_fractional_generalized_time() {
+        let time = Any::new(Tag::GeneralizedTime, b"20240102030405.25Z".as_slice()).unwrap();
+        let time = parse_generalized_time(&time).unwrap();
+
+        assert_eq!(time.to_rfc3339(), "2024-01-02T03:04:05.250+00:00");
+    }
+
+    #[test]
+    fn verifies_token_against_trusted_timestamp_authority() {
+        let tsa = TimestampAuthority::new();
+        let token = tsa.issue(b"signature bytes");
+        let token = TimestampToken::from_der(token.as_der()).unwrap();
+
+        let time = token
+            .verify(b"signature bytes", &tsa.trusted_root())
+            .unwrap();
+        assert_eq!(time.timestamp(), tsa.time());
+
+        let err = token
+            .verify(b"other bytes", &tsa.trusted_root())
+            .unwrap_err();
+        assert!(err.to_string().contains("does not cover"), "{err}");
+
+        let other = TimestampAuthority::with_seed(9);
+        let err = token
+            .verify(b"signature bytes", &other.trusted_root())
+            .unwrap_err();
+        assert!(err.to_string().contains("not signed by a trusted"), "{err}");
+    }
+
+    #[test]
+    fn rejects_token_naming_another_certificate() {
+        let tsa = TimestampAuthority::new();
+        let other = TimestampAuthority::with_seed(9);
+        let token = tsa.issue_naming(b"signature bytes", other.certificate());
+
+        let err = token
+            .verify(b"signature bytes", &tsa.trusted_root())
+            .unwrap_err();
+        assert!(err.to_string().contains("does not identify"), "{err}");
+    }
+
+    #[test]
+    fn response_must_answer_the_request() {
+        let tsa = TimestampAuthority::new();
+        let request = timestamp_request(b"data", 42).unwrap();
+        let response = tsa.respond(&request).unwrap();
+
+        assert!(parse_timestamp_response(&response, b"data", 42).is_ok());
+        assert!(parse_timestamp_response(&response, b"data", 43)
+            .unwrap_err()
+            .to_string()
+            .contains("nonce"));
+        assert!(parse_timestamp_response(&response, b"other", 42)
+            .unwrap_err()
+            .to_string()
+            .contains("does not cover"));
+    }
+
+    #[tokio::test]
+    async fn client_requests_tokens_from_timestamp_authority() {
+        let tsa = TimestampAuthority::new();
+        let trusted_root = tsa.trusted_root();
+        let server = tsa.serve().await;
+
+        let token = TimestampClient::new(server.uri())
+            .timestamp(b"signature bytes")
+            .await
+            .unwrap();
+        assert!(token.verify(b"signature bytes", &trusted_root).is_ok());
+    }
+}
@@ -0,0 +1,201 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bundle::{LogId, X509CertificateChain},
+    encoding::base64_bytes,
+};
+
+/// Media type of the trusted root format, v0.1.
+pub const TRUSTED_ROOT_V01_MEDIA_TYPE: &str =
+    "application/vnd.dev.sigstore.trustedroot+json;version=0.1";
+
+/// Sigstore trust root, as distributed through the Sigstore TUF repository as `trusted_root.json`.
+///
+/// Lists the transparency logs, certificate authorities and timestamp authorities
+/// whose keys are trusted when verifying bundles.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedRoot {
+    /// Media type of the trusted root
+    #[serde(default)]
+    pub media_type: String,
+    /// Rekor transparency logs
+    #[serde(default)]
+    pub tlogs: Vec<TransparencyLogInstance>,
+    /// Fulcio certificate authorities
+    #[serde(default)]
+    pub certificate_authorities: Vec<CertificateAuthority>,
+    /// Certificate transparency logs
+    #[serde(default)]
+    pub ctlogs: Vec<TransparencyLogInstance>,
+    /// RFC 3161 timestamp authorities
+    #[serde(default)]
+    pub timestamp_authorities: Vec<CertificateAuthority>,
+}
+
+impl TrustedRoot {
+    /// Parses a trusted root from JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - JSON string of the trusted root.
+    ///
+    /// # Returns
+    ///
+    /// The parsed trusted root, or error if the JSON is malformed.
+    pub fn from_json(json: &str) -> Result<TrustedRoot> {
+        serde_json::from_str(json).map_err(|e| anyhow!("Failed to parse trusted root: {e}"))
+    }
+
+    /// Reads a trusted root from a local `trusted_root.json` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the trusted root file.
+    ///
+    /// # Returns
+    ///
+    /// The parsed trusted root, or error if the file can't be read or is malformed.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<TrustedRoot> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read trusted root {}", path.display()))?;
+
+        TrustedRoot::from_json(&json)
+    }
+
+    /// Serializes the trusted root to a JSON string.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Returns the transparency log with the given log id.
+    pub fn tlog(&self, key_id: &[u8]) -> Option<&TransparencyLogInstance> {
+        self.tlogs.iter().find(|log| log.log_id.key_id == key_id)
+    }
+}
+
+/// A transparency log and the key it signs checkpoints and entry timestamps with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransparencyLogInstance {
+    /// Base URL of the log
+    pub base_url: String,
+    /// Hash algorithm of the log's Merkle tree, e.g. `SHA2_256`
+    pub hash_algorithm: String,
+    /// Public key of the log
+    pub public_key: TrustedPublicKey,
+    /// Log id, the SHA-256 hash of the DER encoded public key
+    pub log_id: LogId,
+}
+
+/// A trusted public key and the period it was valid for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPublicKey {
+    /// DER encoded `SubjectPublicKeyInfo`
+    #[serde(with = "base64_bytes")]
+    pub raw_bytes: Vec<u8>,
+    /// Key algorithm, e.g. `PKIX_ECDSA_P256_SHA_256`
+    pub key_details: String,
+    /// Period the key was used for signing
+    pub valid_for: TimeRange,
+}
+
+/// A certificate authority and its certificate chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateAuthority {
+    /// Distinguished name of the authority
+    #[serde(default)]
+    pub subject: DistinguishedName,
+    /// URI of the authority
+    #[serde(default)]
+    pub uri: String,
+    /// Certificate chain, intermediates first and the root last
+    pub cert_chain: X509CertificateChain,
+    /// Period the authority issued certificates in
+    pub valid_for: TimeRange,
+}
+
+/// Distinguished name of a certificate authority.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistinguishedName {
+    /// Organization name
+    #[serde(default)]
+    pub organization: String,
+    /// Common name
+    #[serde(default)]
+    pub common_name: String,
+}
+
+/// A validity period. An unset end means the period is still open.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeRange {
+    /// Start of the period
+    pub start: DateTime<Utc>,
+    /// End of the period, if it has ended
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    /// Returns true if the unix timestamp falls within the period.
+    pub fn contains(&self, unix_seconds: i64) -> bool {
+        unix_seconds >= self.start.timestamp()
+            && self.end.is_none_or(|end| unix_seconds <= end.timestamp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trimmed from the public-good Sigstore instance's trusted_root.json
+    const TRUSTED_ROOT: &str = r#"{
+      "mediaType": "application/vnd.dev.sigstore.trustedroot+json;version=0.1",
+      "tlogs": [{
+        "baseUrl": "https://rekor.sigstore.dev",
+        "hashAlgorithm": "SHA2_256",
+        "publicKey": {
+          "rawBytes": "MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE2G2Y+2tabdTV5BcGiBIx0a9fAFwrkBbmLSGtks4L3qX6yYY0zufBnhC8Ur/iy55GhWP/9A/bY2LhC30M9+RYtw==",
+          "keyDetails": "PKIX_ECDSA_P256_SHA_256",
+          "validFor": { "start": "2021-01-12T11:53:27.000Z" }
+        },
+        "logId": { "keyId": "wNI9atQGlz+VWfO6LRygH4QUfY/8W4RFwiT5i5WRgB0=" }
+      }],
+      "certificateAuthorities": [{
+        "subject": { "organization": "sigstore.dev", "commonName": "sigstore" },
+        "uri": "https://fulcio.sigstore.dev",
+        "certChain": { "certificates": [{ "rawBytes": "AQ==" }] },
+        "validFor": { "start": "2021-03-07T03:20:29.000Z", "end": "2022-12-31T23:59:59.999Z" }
+      }],
+      "ctlogs": [],
+      "timestampAuthorities": []
+    }"#;
+
+    #[test]
+    fn parses_sigstore_trusted_root() {
+        let root = TrustedRoot::from_json(TRUSTED_ROOT).unwrap();
+
+        assert_eq!(root.media_type, TRUSTED_ROOT_V01_MEDIA_TYPE);
+        let log = &root.tlogs[0];
+        assert_eq!(log.base_url, "https://rekor.sigstore.dev");
+        assert!(log.public_key.valid_for.end.is_none());
+
+        let key = crate::PublicKey::from_spki_der(&log.public_key.raw_bytes).unwrap();
+        assert_eq!(key.key_id().unwrap(), log.log_id.key_id);
+        assert!(root.tlog(&log.log_id.key_id).is_some());
+
+        let ca = &root.certificate_authorities[0];
+        assert_eq!(ca.subject.common_name, "sigstore");
+        assert!(ca.valid_for.contains(1_640_995_200));
+        assert!(!ca.valid_for.contains(1_704_067_200));
+    }
+}
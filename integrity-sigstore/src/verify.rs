@@ -0,0 +1,1166 @@
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::{general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use x509_cert::{
+    der::{Decode, Encode},
+    ext::pkix::{BasicConstraints, ExtendedKeyUsage},
+    Certificate,
+};
+
+use crate::{
+    bundle::{BundleContent, DsseEnvelope, MessageSignature, TransparencyLogEntry},
+    merkle,
+    trust::TrustedRoot,
+    PublicKey, SigstoreBundle, TimestampToken,
+};
+
+const ID_KP_CODE_SIGNING: &str = "1.3.6.1.5.5.7.3.3";
+pub(crate) const ECDSA_WITH_SHA256: &str = "1.2.840.10045.4.3.2";
+const ECDSA_WITH_SHA384: &str = "1.2.840.10045.4.3.3";
+const ID_ED25519: &str = "1.3.101.112";
+
+/// Verifies Sigstore bundles offline against a trusted public key or a trusted root.
+///
+/// With a trusted public key, the bundle's signature must verify under that key.
+/// With a trusted root, the signing certificate must chain to one of its certificate
+/// authorities and every transparency log entry must carry a signed entry timestamp or an
+/// inclusion proof with a checkpoint signed by one of its logs. Only integrated times
+/// covered by a signed entry timestamp are trusted to check certificate validity. Both can be
+/// configured at once, e.g. to check log entries of a bundle signed with an out-of-band key.
+/// No network access is performed.
+#[derive(Debug, Clone)]
+pub struct BundleVerifier {
+    public_key: Option<PublicKey>,
+    trusted_root: Option<TrustedRoot>,
+    require_tlog_entries: bool,
+}
+
+/// Outcome of a successful bundle verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleVerification {
+    /// Key the bundle's signature verified under
+    pub signing_key: PublicKey,
+    /// True if the signing key came from a certificate chaining to the trusted root
+    pub certificate_verified: bool,
+    /// Integrated times of the verified transparency log entries covered by a signed entry
+    /// timestamp, as unix timestamps
+    pub integrated_times: Vec<i64>,
+    /// Times asserted by the verified RFC 3161 timestamps, as unix timestamps
+    pub timestamps: Vec<i64>,
+}
+
+impl BundleVerifier {
+    /// Creates a verifier trusting a single public key.
+    ///
+    /// Transparency log entries are not required, as bundles signed with an out-of-band
+    /// key (e.g. by `create_model_signing_sigstore_bundle`) usually carry none.
+    pub fn with_public_key(public_key: PublicKey) -> BundleVerifier {
+        BundleVerifier {
+            public_key: Some(public_key),
+            trusted_root: None,
+            require_tlog_entries: false,
+        }
+    }
+
+    /// Creates a verifier trusting the authorities and logs of a trusted root.
+    ///
+    /// At least one verified transparency log entry is required by default.
+    pub fn with_trusted_root(trusted_root: TrustedRoot) -> BundleVerifier {
+        BundleVerifier {
+            public_key: None,
+            trusted_root: Some(trusted_root),
+            require_tlog_entries: true,
+        }
+    }
+
+    /// Creates a verifier from a local `trusted_root.json` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the trusted root file, e.g. from a Sigstore TUF checkout.
+    ///
+    /// # Returns
+    ///
+    /// The verifier, or error if the trusted root can't be read.
+    pub fn from_trusted_root_file(path: impl AsRef<Path>) -> Result<BundleVerifier> {
+        Ok(BundleVerifier::with_trusted_root(TrustedRoot::from_file(
+            path,
+        )?))
+    }
+
+    /// Sets the trusted public key.
+    pub fn public_key(mut self, public_key: PublicKey) -> BundleVerifier {
+        self.public_key = Some(public_key);
+        self
+    }
+
+    /// Sets the trusted root.
+    pub fn trusted_root(mut self, trusted_root: TrustedRoot) -> BundleVerifier {
+        self.trusted_root = Some(trusted_root);
+        self
+    }
+
+    /// Sets whether bundles must carry at least one verified transparency log entry.
+    pub fn require_tlog_entries(mut self, require: bool) -> BundleVerifier {
+        self.require_tlog_entries = require;
+        self
+    }
+
+    /// Verifies a bundle's signature, certificate chain and transparency log entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The bundle to verify.
+    ///
+    /// # Returns
+    ///
+    /// The verification outcome, or error describing why verification failed.
+    pub fn verify(&self, bundle: &SigstoreBundle) -> Result<BundleVerification> {
+        self.verify_inner(bundle, None)
+    }
+
+    /// Verifies a bundle and checks that it signs the given artifact.
+    ///
+    /// For message signatures the artifact's SHA-256 digest must match the signed digest.
+    /// For DSSE envelopes carrying an in-toto statement, the artifact's SHA-256 digest must
+    /// match one of the statement's subjects.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The bundle to verify.
+    /// * `artifact` - The signed artifact bytes.
+    ///
+    /// # Returns
+    ///
+    /// The verification outcome, or error describing why verification failed.
+    pub fn verify_artifact(
+        &self,
+        bundle: &SigstoreBundle,
+        artifact: &[u8],
+    ) -> Result<BundleVerification> {
+        self.verify_inner(bundle, Some(artifact))
+    }
+
+    fn verify_inner(
+        &self,
+        bundle: &SigstoreBundle,
+        artifact: Option<&[u8]>,
+    ) -> Result<BundleVerification> {
+        let entry_times = self.verify_tlog_entries(bundle)?;
+
+        if self.require_tlog_entries && entry_times.is_empty() {
+            bail!("Bundle verification failed: no verified transparency log entry.");
+        }
+
+        let integrated_times = entry_times.into_iter().flatten().collect::<Vec<_>>();
+
+        let timestamps = self.verify_timestamps(bundle)?;
+
+        let verified_times = [integrated_times.as_slice(), timestamps.as_slice()].concat();
+        let (signing_key, certificate_verified) = self.signing_key(bundle, &verified_times)?;
+
+        match bundle.content() {
+            BundleContent::DsseEnvelope(envelope) => {
+                verify_dsse_envelope(envelope, &signing_key)?;
+
+                if let Some(artifact) = artifact {
+                    verify_statement_subject(envelope, artifact)?;
+                }
+            }
+            BundleContent::MessageSignature(signature) => {
+                verify_message_signature(signature, &signing_key, artifact)?;
+            }
+        }
+
+        Ok(BundleVerification {
+            signing_key,
+            certificate_verified,
+            integrated_times,
+            timestamps,
+        })
+    }
+
+    /// Verifies every RFC 3161 timestamp over the bundle's signature, returning their times.
+    fn verify_timestamps(&self, bundle: &SigstoreBundle) -> Result<Vec<i64>> {
+        let tokens = bundle.rfc3161_timestamps();
+        if tokens.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let Some(trusted_root) = &self.trusted_root else {
+            return Ok(vec![]);
+        };
+
+        let signature = bundle.timestamped_signature().ok_or_else(|| {
+            anyhow!("Bundle verification failed: bundle has no signature to timestamp.")
+        })?;
+
+        tokens
+            .into_iter()
+            .map(|token| {
+                let time = TimestampToken::from_der(token)?.verify(signature, trusted_root)?;
+                Ok(time.timestamp())
+            })
+            .collect()
+    }
+
+    /// Verifies every transparency log entry, returning for each its integrated time if a
+    /// signed entry timestamp vouches for it.
+    fn verify_tlog_entries(&self, bundle: &SigstoreBundle) -> Result<Vec<Option<i64>>> {
+        let entries = bundle.tlog_entries();
+        if entries.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let Some(trusted_root) = &self.trusted_root else {
+            if self.require_tlog_entries {
+                bail!("Bundle verification failed: no trusted root to verify log entries.");
+            }
+            return Ok(vec![]);
+        };
+
+        entries
+            .iter()
+            .map(|entry| {
+                verify_tlog_entry(entry, bundle.content(), trusted_root).with_context(|| {
+                    format!(
+                        "Transparency log entry {} verification failed",
+                        entry.log_index
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Resolves the key the bundle must be signed with.
+    ///
+    /// Certificates must have been valid at every verified log entry and timestamp time.
+    fn signing_key(
+        &self,
+        bundle: &SigstoreBundle,
+        verified_times: &[i64],
+    ) -> Result<(PublicKey, bool)> {
+        let certificates = bundle.certificates();
+
+        let Some(leaf) = certificates.first() else {
+            let key = self.public_key.clone().ok_or_else(|| {
+                anyhow!("Bundle verification failed: bundle has no certificate and no trusted public key is configured.")
+            })?;
+            return Ok((key, false));
+        };
+
+        let leaf = Certificate::from_der(leaf)
+            .map_err(|e| anyhow!("Failed to parse signing certificate: {e}"))?;
+        let leaf_key = PublicKey::from_certificate(&leaf)?;
+
+        if let Some(trusted_root) = &self.trusted_root {
+            if verified_times.is_empty() {
+                bail!("Bundle verification failed: no verified log entry or timestamp to check the signing certificate against.");
+            }
+
+            verify_certificate(&leaf, trusted_root, verified_times)?;
+            return Ok((leaf_key, true));
+        }
+
+        match &self.public_key {
+            Some(key) if *key == leaf_key => Ok((leaf_key, false)),
+            Some(_) => {
+                bail!("Bundle verification failed: certificate key does not match the trusted public key.")
+            }
+            None => bail!(
+                "Bundle verification failed: no trusted root to verify the signing certificate."
+            ),
+        }
+    }
+}
+
+/// Computes the DSSEv1 pre-authentication encoding (PAE) of a payload.
+///
+/// `PAE(type, body) = "DSSEv1" SP LEN(type) SP type SP LEN(body) SP body`, where
+/// `LEN` is the ASCII decimal byte length. This is the message covered by DSSE signatures.
+///
+/// # Arguments
+/// * `payload_type` - Payload type string of the envelope
+/// * `payload` - Raw (not base64-encoded) payload bytes
+///
+/// # Returns
+/// * `Vec<u8>` - Pre-authentication encoding of the payload
+pub fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut msg = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    msg.extend_from_slice(payload);
+
+    msg
+}
+
+fn verify_dsse_envelope(envelope: &DsseEnvelope, key: &PublicKey) -> Result<()> {
+    if envelope.signatures.is_empty() {
+        bail!("Bundle verification failed: DSSE envelope has no signatures.");
+    }
+
+    let msg = pae(&envelope.payload_type, &envelope.payload);
+
+    let verified = envelope
+        .signatures
+        .iter()
+        .any(|signature| key.verify(&msg, &signature.sig).is_ok());
+
+    if !verified {
+        bail!("Bundle verification failed: DSSE signature does not verify under the signing key.");
+    }
+
+    Ok(())
+}
+
+fn verify_statement_subject(envelope: &DsseEnvelope, artifact: &[u8]) -> Result<()> {
+    let statement: Value = serde_json::from_slice(&envelope.payload)
+        .map_err(|e| anyhow!("DSSE payload is not an in-toto statement: {e}"))?;
+    let digest = hex::encode(Sha256::digest(artifact));
+
+    let matches = statement["subject"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .any(|subject| subject["digest"]["sha256"].as_str() == Some(digest.as_str()));
+
+    if !matches {
+        bail!("Bundle verification failed: artifact digest {digest} is not a statement subject.");
+    }
+
+    Ok(())
+}
+
+fn verify_message_signature(
+    signature: &MessageSignature,
+    key: &PublicKey,
+    artifact: Option<&[u8]>,
+) -> Result<()> {
+    let artifact_digest = artifact.map(|a| Sha256::digest(a).to_vec());
+
+    let digest = match (&signature.message_digest, &artifact_digest) {
+        (Some(message_digest), artifact_digest) => {
+            if message_digest.algorithm != "SHA2_256" {
+                bail!(
+                    "Unsupported message digest algorithm '{}'.",
+                    message_digest.algorithm
+                );
+            }
+            if artifact_digest
+                .as_ref()
+                .is_some_and(|d| *d != message_digest.digest)
+            {
+                bail!(
+                    "Bundle verification failed: artifact digest does not match the signed digest."
+                );
+            }
+            message_digest.digest.clone()
+        }
+        (None, Some(artifact_digest)) => artifact_digest.clone(),
+        (None, None) => {
+            bail!("Bundle verification failed: message signature has no digest and no artifact was given.")
+        }
+    };
+
+    let result = match (key, artifact) {
+        (PublicKey::Ed25519(_), Some(artifact)) => key.verify(artifact, &signature.signature),
+        (PublicKey::Ed25519(_), None) => {
+            bail!("Bundle verification failed: Ed25519 message signatures need the artifact.")
+        }
+        _ => key.verify_prehash(&digest, &signature.signature),
+    };
+
+    result.map_err(|_| {
+        anyhow!(
+            "Bundle verification failed: message signature does not verify under the signing key."
+        )
+    })
+}
+
+/// Checks the leaf certificate chains to a trusted certificate authority and was valid
+/// at each verified signing time.
+fn verify_certificate(
+    leaf: &Certificate,
+    trusted_root: &TrustedRoot,
+    verified_times: &[i64],
+) -> Result<()> {
+    for &time in verified_times {
+        check_validity(leaf, time)?;
+    }
+
+    if let Some((_, eku)) = leaf
+        .tbs_certificate
+        .get::<ExtendedKeyUsage>()
+        .map_err(|e| anyhow!("Failed to parse extended key usage: {e}"))?
+    {
+        if !eku
+            .0
+            .iter()
+            .any(|oid| oid.to_string() == ID_KP_CODE_SIGNING)
+        {
+            bail!("Bundle verification failed: signing certificate is not valid for code signing.");
+        }
+    }
+
+    let mut errors = vec![];
+
+    for authority in &trusted_root.certificate_authorities {
+        if !verified_times
+            .iter()
+            .all(|&time| authority.valid_for.contains(time))
+        {
+            continue;
+        }
+
+        let chain = authority
+            .cert_chain
+            .certificates
+            .iter()
+            .map(|c| Certificate::from_der(&c.raw_bytes))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("Failed to parse certificate authority chain: {e}"))?;
+
+        match verify_chain(leaf, &chain, verified_times) {
+            Ok(()) => return Ok(()),
+            Err(e) => errors.push(format!("{}: {e}", authority.uri)),
+        }
+    }
+
+    bail!(
+        "Bundle verification failed: signing certificate does not chain to a trusted certificate authority ({}).",
+        errors.join("; ")
+    )
+}
+
+/// Verifies `leaf` is issued by a certificate in `chain`, and each certificate from there is
+/// issued by the next, ending at the root.
+pub(crate) fn verify_chain(leaf: &Certificate, chain: &[Certificate], times: &[i64]) -> Result<()> {
+    let start = chain
+        .iter()
+        .position(|ca| ca.tbs_certificate.subject == leaf.tbs_certificate.issuer)
+        .ok_or_else(|| anyhow!("no issuer for the signing certificate"))?;
+
+    let mut child = leaf;
+    for issuer in &chain[start..] {
+        if issuer.tbs_certificate.subject != child.tbs_certificate.issuer {
+            bail!("certificate chain is out of order");
+        }
+
+        let is_ca = issuer
+            .tbs_certificate
+            .get::<BasicConstraints>()
+            .map_err(|e| anyhow!("failed to parse basic constraints: {e}"))?
+            .is_some_and(|(_, constraints)| constraints.ca);
+        if !is_ca {
+            bail!("issuer '{}' is not a CA", issuer.tbs_certificate.subject);
+        }
+
+        for &time in times {
+            check_validity(issuer, time)?;
+        }
+
+        verify_certificate_signature(child, issuer)?;
+        child = issuer;
+    }
+
+    if child.tbs_certificate.subject != child.tbs_certificate.issuer {
+        bail!("certificate chain does not end in a self-signed root");
+    }
+    verify_certificate_signature(child, child)
+}
+
+fn verify_certificate_signature(certificate: &Certificate, issuer: &Certificate) -> Result<()> {
+    let key = PublicKey::from_certificate(issuer)?;
+    let tbs = certificate
+        .tbs_certificate
+        .to_der()
+        .map_err(|e| anyhow!("failed to encode certificate: {e}"))?;
+    let sig = certificate
+        .signature
+        .as_bytes()
+        .ok_or_else(|| anyhow!("certificate signature has unused bits"))?;
+
+    let algorithm = certificate.signature_algorithm.oid.to_string();
+    let result = match (algorithm.as_str(), &key) {
+        (ECDSA_WITH_SHA256, PublicKey::P256(_) | PublicKey::Secp256k1(_))
+        | (ECDSA_WITH_SHA384, PublicKey::P384(_))
+        | (ID_ED25519, PublicKey::Ed25519(_)) => key.verify(&tbs, sig),
+        (ECDSA_WITH_SHA384, PublicKey::P256(_)) => {
+            key.verify_prehash(&sha2::Sha384::digest(&tbs), sig)
+        }
+        (ECDSA_WITH_SHA256, PublicKey::P384(_)) => key.verify_prehash(&Sha256::digest(&tbs), sig),
+        (algorithm, _) => bail!("unsupported certificate signature algorithm {algorithm}"),
+    };
+
+    result.map_err(|_| {
+        anyhow!(
+            "invalid signature on certificate '{}'",
+            certificate.tbs_certificate.subject
+        )
+    })
+}
+
+pub(crate) fn check_validity(certificate: &Certificate, time: i64) -> Result<()> {
+    let validity = &certificate.tbs_certificate.validity;
+    let not_before = validity.not_before.to_unix_duration().as_secs() as i64;
+    let not_after = validity.not_after.to_unix_duration().as_secs() as i64;
+
+    if time < not_before || time > not_after {
+        bail!(
+            "certificate '{}' was not valid at {time}",
+            certificate.tbs_certificate.subject
+        );
+    }
+
+    Ok(())
+}
+
+/// Fields of a Rekor entry covered by its signed entry timestamp.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedEntryTimestampPayload {
+    body: String,
+    integrated_time: i64,
+    #[serde(rename = "logID")]
+    log_id: String,
+    log_index: i64,
+}
+
+/// Returns the canonical payload a log's signed entry timestamp signs.
+pub(crate) fn signed_entry_timestamp_payload(entry: &TransparencyLogEntry) -> Result<Vec<u8>> {
+    let payload = SignedEntryTimestampPayload {
+        body: BASE64.encode(&entry.canonicalized_body),
+        integrated_time: entry.integrated_time,
+        log_id: hex::encode(&entry.log_id.key_id),
+        log_index: entry.log_index,
+    };
+
+    Ok(serde_jcs::to_vec(&payload)?)
+}
+
+/// Verifies a log entry is signed by a trusted log, through its signed entry timestamp or the
+/// checkpoint of its inclusion proof.
+///
+/// Returns the entry's integrated time if the signed entry timestamp covers it; the time of
+/// an entry only proven by a checkpoint is unsigned and can't be trusted.
+fn verify_tlog_entry(
+    entry: &TransparencyLogEntry,
+    content: &BundleContent,
+    trusted_root: &TrustedRoot,
+) -> Result<Option<i64>> {
+    let log = trusted_root.tlog(&entry.log_id.key_id).ok_or_else(|| {
+        anyhow!(
+            "unknown transparency log {}",
+            hex::encode(&entry.log_id.key_id)
+        )
+    })?;
+    let log_key = PublicKey::from_spki_der(&log.public_key.raw_bytes)?;
+
+    if !log.public_key.valid_for.contains(entry.integrated_time) {
+        bail!(
+            "log key was not valid at integrated time {}",
+            entry.integrated_time
+        );
+    }
+
+    verify_entry_body(entry, content)?;
+
+    let mut integrated_time = None;
+    if let Some(promise) = &entry.inclusion_promise {
+        let payload = signed_entry_timestamp_payload(entry)?;
+        log_key
+            .verify(&payload, &promise.signed_entry_timestamp)
+            .map_err(|_| anyhow!("invalid signed entry timestamp"))?;
+        integrated_time = Some(entry.integrated_time);
+    }
+
+    let mut checkpoint_verified = false;
+
+    if let Some(proof) = &entry.inclusion_proof {
+        let index = u64::try_from(proof.log_index)?;
+        let tree_size = u64::try_from(proof.tree_size)?;
+        let root = merkle::root_from_inclusion_proof(
+            index,
+            tree_size,
+            merkle::leaf_hash(&entry.canonicalized_body),
+            &proof.hashes,
+        )?;
+
+        if root[..] != proof.root_hash[..] {
+            bail!("inclusion proof does not match the root hash");
+        }
+
+        if let Some(checkpoint) = &proof.checkpoint {
+            verify_checkpoint(
+                &checkpoint.envelope,
+                &log_key,
+                &entry.log_id.key_id,
+                tree_size,
+                &proof.root_hash,
+            )?;
+            checkpoint_verified = true;
+        }
+    }
+
+    if integrated_time.is_none() && !checkpoint_verified {
+        bail!("entry has neither a signed entry timestamp nor a signed checkpoint");
+    }
+
+    Ok(integrated_time)
+}
+
+/// Verifies a signed note checkpoint commits to the given tree size and root hash.
+fn verify_checkpoint(
+    envelope: &str,
+    log_key: &PublicKey,
+    key_id: &[u8],
+    tree_size: u64,
+    root_hash: &[u8],
+) -> Result<()> {
+    let (note, signatures) = envelope
+        .split_once("\n\n")
+        .ok_or_else(|| anyhow!("malformed checkpoint"))?;
+    let note = format!("{note}\n");
+
+    let mut lines = note.lines();
+    let _origin = lines.next();
+    let size = lines
+        .next()
+        .and_then(|size| size.parse::<u64>().ok())
+        .ok_or_else(|| anyhow!("malformed checkpoint tree size"))?;
+    let root = lines
+        .next()
+        .and_then(|root| BASE64.decode(root).ok())
+        .ok_or_else(|| anyhow!("malformed checkpoint root hash"))?;
+
+    if size != tree_size || root != root_hash {
+        bail!("checkpoint does not match the inclusion proof");
+    }
+
+    let verified = signatures
+        .lines()
+        .filter_map(|line| line.strip_prefix("\u{2014} "))
+        .filter_map(|line| line.split_once(' '))
+        .filter_map(|(_, sig)| BASE64.decode(sig).ok())
+        .filter(|sig| sig.len() > 4 && key_id.starts_with(&sig[..4]))
+        .any(|sig| log_key.verify(note.as_bytes(), &sig[4..]).is_ok());
+
+    if !verified {
+        bail!("checkpoint is not signed by the log");
+    }
+
+    Ok(())
+}
+
+/// Checks the log entry body records the bundle's signature and content.
+fn verify_entry_body(entry: &TransparencyLogEntry, content: &BundleContent) -> Result<()> {
+    let body: Value = serde_json::from_slice(&entry.canonicalized_body)
+        .map_err(|e| anyhow!("malformed entry body: {e}"))?;
+
+    let kind = body["kind"].as_str().unwrap_or_default();
+    if kind != entry.kind_version.kind {
+        bail!(
+            "entry body kind '{kind}' does not match '{}'",
+            entry.kind_version.kind
+        );
+    }
+
+    let spec = &body["spec"];
+    let decode = |value: &Value| value.as_str().and_then(|s| BASE64.decode(s).ok());
+
+    match (kind, content) {
+        ("dsse", BundleContent::DsseEnvelope(envelope)) => {
+            let payload_hash = hex::encode(Sha256::digest(&envelope.payload));
+            if spec["payloadHash"]["value"].as_str() != Some(payload_hash.as_str()) {
+                bail!("entry payload hash does not match the DSSE payload");
+            }
+
+            let logged = spec["signatures"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|s| decode(&s["signature"]))
+                .collect::<Vec<_>>();
+            if !envelope.signatures.iter().all(|s| logged.contains(&s.sig)) {
+                bail!("entry signatures do not match the DSSE envelope");
+            }
+        }
+        ("intoto", BundleContent::DsseEnvelope(envelope)) => {
+            let payload_hash = hex::encode(Sha256::digest(&envelope.payload));
+            if spec["content"]["payloadHash"]["value"].as_str() != Some(payload_hash.as_str()) {
+                bail!("entry payload hash does not match the DSSE payload");
+            }
+        }
+        ("hashedrekord", BundleContent::MessageSignature(signature)) => {
+            if decode(&spec["signature"]["content"]).as_ref() != Some(&signature.signature) {
+                bail!("entry signature does not match the message signature");
+            }
+
+            if let Some(digest) = &signature.message_digest {
+                let logged = spec["data"]["hash"]["value"].as_str().unwrap_or_default();
+                if logged != hex::encode(&digest.digest) {
+                    bail!("entry digest does not match the message digest");
+                }
+            }
+        }
+        (kind, _) => bail!("unsupported entry kind '{kind}' for the bundle content"),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, time::Duration};
+
+    use p256::ecdsa::{signature::Signer, DerSignature, SigningKey};
+    use serde_json::json;
+    use x509_cert::{
+        builder::{Builder, CertificateBuilder, Profile},
+        der::asn1::ObjectIdentifier,
+        name::Name,
+        serial_number::SerialNumber,
+        spki::SubjectPublicKeyInfoOwned,
+        time::{Time, Validity},
+    };
+
+    use super::*;
+    use crate::{
+        bundle::{DsseSignature, HashOutput, X509Certificate, X509CertificateChain},
+        testing::{key, TestLog, TimestampAuthority, INTEGRATED_TIME, TIMESTAMP_TIME},
+        trust::{CertificateAuthority, TimeRange},
+        VerificationMaterial, VerificationMaterialContent,
+    };
+
+    const ARTIFACT: &[u8] = b"model weights";
+    const PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+    fn validity(not_before: u64, not_after: u64) -> Validity {
+        let time =
+            |secs| Time::try_from(std::time::UNIX_EPOCH + Duration::from_secs(secs)).unwrap();
+        Validity {
+            not_before: time(not_before),
+            not_after: time(not_after),
+        }
+    }
+
+    fn spki(key: &SigningKey) -> SubjectPublicKeyInfoOwned {
+        SubjectPublicKeyInfoOwned::from_key(*key.verifying_key()).unwrap()
+    }
+
+    struct TestPki {
+        ca_key: SigningKey,
+        ca: Certificate,
+        signer: SigningKey,
+    }
+
+    impl TestPki {
+        fn new() -> TestPki {
+            let ca_key = key(1);
+            let ca = CertificateBuilder::new(
+                Profile::Root,
+                SerialNumber::new(&[1]).unwrap(),
+                validity(1_600_000_000, 2_000_000_000),
+                Name::from_str("CN=test-fulcio,O=integrity").unwrap(),
+                spki(&ca_key),
+                &ca_key,
+            )
+            .unwrap()
+            .build::<DerSignature>()
+            .unwrap();
+
+            TestPki {
+                ca_key,
+                ca,
+                signer: key(2),
+            }
+        }
+
+        fn leaf(&self, not_before: u64, eku: &str) -> Certificate {
+            let mut builder = CertificateBuilder::new(
+                Profile::Leaf {
+                    issuer: self.ca.tbs_certificate.subject.clone(),
+                    enable_key_agreement: false,
+                    enable_key_encipherment: false,
+                },
+                SerialNumber::new(&[2]).unwrap(),
+                validity(not_before, not_before + 600),
+                Name::from_str("CN=signer").unwrap(),
+                spki(&self.signer),
+                &self.ca_key,
+            )
+            .unwrap();
+            builder
+                .add_extension(&ExtendedKeyUsage(vec![ObjectIdentifier::new_unwrap(eku)]))
+                .unwrap();
+
+            builder.build::<DerSignature>().unwrap()
+        }
+    }
+
+    fn trusted_root(pki: &TestPki, log: &TestLog) -> TrustedRoot {
+        TrustedRoot {
+            certificate_authorities: vec![CertificateAuthority {
+                subject: Default::default(),
+                uri: "https://fulcio.example.com".to_owned(),
+                cert_chain: X509CertificateChain {
+                    certificates: vec![X509Certificate {
+                        raw_bytes: pki.ca.to_der().unwrap(),
+                    }],
+                },
+                valid_for: TimeRange {
+                    start: chrono::DateTime::from_timestamp(1_600_000_000, 0).unwrap(),
+                    end: None,
+                },
+            }],
+            ..log.trusted_root()
+        }
+    }
+
+    fn dsse_envelope(key: &SigningKey) -> DsseEnvelope {
+        let payload = serde_json::to_vec(&json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{
+                "name": "model",
+                "digest": { "sha256": hex::encode(Sha256::digest(ARTIFACT)) }
+            }],
+            "predicateType": "https://model_signing/signature/v1.0",
+            "predicate": {}
+        }))
+        .unwrap();
+        let sig: DerSignature = key.sign(&pae(PAYLOAD_TYPE, &payload));
+
+        DsseEnvelope {
+            payload,
+            payload_type: PAYLOAD_TYPE.to_owned(),
+            signatures: vec![DsseSignature {
+                sig: sig.as_bytes().to_vec(),
+                keyid: String::new(),
+            }],
+        }
+    }
+
+    /// Rekor `dsse` v0.0.1 entry body, as cosign's bundles record it.
+    fn dsse_body(envelope: &DsseEnvelope, leaf: &Certificate) -> Vec<u8> {
+        let pem = x509_cert::der::EncodePem::to_pem(leaf, Default::default()).unwrap();
+        let body = json!({
+            "apiVersion": "0.0.1",
+            "kind": "dsse",
+            "spec": {
+                "envelopeHash": { "algorithm": "sha256", "value": "00" },
+                "payloadHash": {
+                    "algorithm": "sha256",
+                    "value": hex::encode(Sha256::digest(&envelope.payload))
+                },
+                "signatures": [{
+                    "signature": BASE64.encode(&envelope.signatures[0].sig),
+                    "verifier": BASE64.encode(pem)
+                }]
+            }
+        });
+
+        serde_jcs::to_vec(&body).unwrap()
+    }
+
+    fn certificate_bundle(pki: &TestPki, log: &TestLog, leaf: &Certificate) -> SigstoreBundle {
+        let envelope = dsse_envelope(&pki.signer);
+        let entry = log.entry("dsse", dsse_body(&envelope, leaf));
+
+        SigstoreBundle::new(
+            VerificationMaterial {
+                content: VerificationMaterialContent::Certificate(X509Certificate {
+                    raw_bytes: leaf.to_der().unwrap(),
+                }),
+                tlog_entries: vec![entry],
+                timestamp_verification_data: None,
+            },
+            BundleContent::DsseEnvelope(envelope),
+        )
+    }
+
+    fn fixture() -> (TestPki, TestLog, SigstoreBundle) {
+        let pki = TestPki::new();
+        let log = TestLog { key: key(3) };
+        let leaf = pki.leaf(1_700_000_000, ID_KP_CODE_SIGNING);
+        let bundle = certificate_bundle(&pki, &log, &leaf);
+
+        (pki, log, bundle)
+    }
+
+    #[test]
+    fn verifies_certificate_bundle_against_trusted_root_file() {
+        let (pki, log, bundle) = fixture();
+
+        let path = std::env::temp_dir().join(format!(
+            "integrity-sigstore-trusted-root-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, trusted_root(&pki, &log).to_json().unwrap()).unwrap();
+        let verifier = BundleVerifier::from_trusted_root_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // round-trip through JSON, as a bundle read from disk would be
+        let bundle = SigstoreBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        let verification = verifier.verify_artifact(&bundle, ARTIFACT).unwrap();
+
+        assert!(verification.certificate_verified);
+        assert_eq!(verification.integrated_times, vec![INTEGRATED_TIME]);
+        assert_eq!(
+            verification.signing_key,
+            PublicKey::P256(*pki.signer.verifying_key())
+        );
+        assert!(verifier.verify_artifact(&bundle, b"other").is_err());
+    }
+
+    #[test]
+    fn rejects_tampered_payload() {
+        let (pki, log, mut bundle) = fixture();
+        let verifier = BundleVerifier::with_trusted_root(trusted_root(&pki, &log));
+
+        let BundleContent::DsseEnvelope(envelope) = &bundle.content else {
+            unreachable!()
+        };
+        let mut envelope = envelope.clone();
+        envelope.payload = b"{}".to_vec();
+        bundle.content = BundleContent::DsseEnvelope(envelope);
+
+        let err = verifier.verify(&bundle).unwrap_err();
+        assert!(format!("{err:#}").contains("payload hash"), "{err:#}");
+    }
+
+    #[test]
+    fn rejects_invalid_inclusion_proof_and_promise() {
+        let (pki, log, bundle) = fixture();
+        let verifier = BundleVerifier::with_trusted_root(trusted_root(&pki, &log));
+        assert!(verifier.verify(&bundle).is_ok());
+
+        let mut bad_proof = bundle.clone();
+        let entry = &mut bad_proof.verification_material_mut().tlog_entries[0];
+        entry.inclusion_proof.as_mut().unwrap().hashes[0][0] ^= 1;
+        let err = verifier.verify(&bad_proof).unwrap_err();
+        assert!(format!("{err:#}").contains("inclusion proof"), "{err:#}");
+
+        let mut bad_promise = bundle.clone();
+        let entry = &mut bad_promise.verification_material_mut().tlog_entries[0];
+        entry.integrated_time += 1;
+        let err = verifier.verify(&bad_promise).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("signed entry timestamp"),
+            "{err:#}"
+        );
+
+        // a checkpoint proves inclusion but doesn't sign the integrated time, so the
+        // certificate has no trusted time to be checked against
+        let mut checkpoint_only = bundle.clone();
+        checkpoint_only.verification_material_mut().tlog_entries[0].inclusion_promise = None;
+        let err = verifier.verify(&checkpoint_only).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("no verified log entry or timestamp"),
+            "{err:#}"
+        );
+
+        let mut neither = checkpoint_only;
+        neither.verification_material_mut().tlog_entries[0].inclusion_proof = None;
+        assert!(verifier.verify(&neither).is_err());
+    }
+
+    #[test]
+    fn rejects_proof_without_checkpoint_or_promise() {
+        let (pki, log, mut bundle) = fixture();
+        let verifier = BundleVerifier::with_trusted_root(trusted_root(&pki, &log));
+
+        // an unsigned proof is self-consistent whatever the root hash and integrated time
+        let entry = &mut bundle.verification_material_mut().tlog_entries[0];
+        entry.inclusion_promise = None;
+        entry.integrated_time = 1_700_000_100;
+        entry.inclusion_proof.as_mut().unwrap().checkpoint = None;
+
+        let err = verifier.verify(&bundle).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("neither a signed entry timestamp nor a signed checkpoint"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn rejects_entries_from_unknown_logs() {
+        let (pki, _, bundle) = fixture();
+        let other_log = TestLog { key: key(4) };
+        let verifier = BundleVerifier::with_trusted_root(trusted_root(&pki, &other_log));
+
+        let err = verifier.verify(&bundle).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unknown transparency log"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn rejects_certificate_not_valid_at_integrated_time() {
+        let pki = TestPki::new();
+        let log = TestLog { key: key(3) };
+        let verifier = BundleVerifier::with_trusted_root(trusted_root(&pki, &log));
+
+        let expired = pki.leaf(1_600_000_000, ID_KP_CODE_SIGNING);
+        let err = verifier
+            .verify(&certificate_bundle(&pki, &log, &expired))
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("not valid at"), "{err:#}");
+
+        let wrong_usage = pki.leaf(1_700_000_000, "1.3.6.1.5.5.7.3.1");
+        let err = verifier
+            .verify(&certificate_bundle(&pki, &log, &wrong_usage))
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("code signing"), "{err:#}");
+    }
+
+    #[test]
+    fn rejects_certificate_from_untrusted_authority() {
+        let (_, log, bundle) = fixture();
+        let other_pki = TestPki {
+            ca_key: key(5),
+            ..TestPki::new()
+        };
+        let other_ca = CertificateBuilder::new(
+            Profile::Root,
+            SerialNumber::new(&[1]).unwrap(),
+            validity(1_600_000_000, 2_000_000_000),
+            Name::from_str("CN=test-fulcio,O=integrity").unwrap(),
+            spki(&other_pki.ca_key),
+            &other_pki.ca_key,
+        )
+        .unwrap()
+        .build::<DerSignature>()
+        .unwrap();
+        let other_pki = TestPki {
+            ca: other_ca,
+            ..other_pki
+        };
+
+        let verifier = BundleVerifier::with_trusted_root(trusted_root(&other_pki, &log));
+        let err = verifier.verify(&bundle).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("does not chain to a trusted certificate authority"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn verifies_public_key_bundle_without_log_entries() {
+        let signer = key(6);
+        let bundle = SigstoreBundle::new(
+            VerificationMaterial {
+                content: VerificationMaterialContent::PublicKey(
+                    crate::bundle::PublicKeyIdentifier {
+                        hint: "test".to_owned(),
+                    },
+                ),
+                tlog_entries: vec![],
+                timestamp_verification_data: None,
+            },
+            BundleContent::DsseEnvelope(dsse_envelope(&signer)),
+        );
+
+        let public_key = PublicKey::P256(*signer.verifying_key());
+        let verification = BundleVerifier::with_public_key(public_key)
+            .verify_artifact(&bundle, ARTIFACT)
+            .unwrap();
+        assert!(!verification.certificate_verified);
+        assert!(verification.integrated_times.is_empty());
+
+        let other_key = PublicKey::P256(*key(7).verifying_key());
+        assert!(BundleVerifier::with_public_key(other_key.clone())
+            .verify(&bundle)
+            .is_err());
+        assert!(BundleVerifier::with_public_key(other_key)
+            .require_tlog_entries(true)
+            .verify(&bundle)
+            .is_err());
+    }
+
+    #[test]
+    fn verifies_hashedrekord_message_signature() {
+        let pki = TestPki::new();
+        let log = TestLog { key: key(3) };
+        let leaf = pki.leaf(1_700_000_000, ID_KP_CODE_SIGNING);
+
+        let digest = Sha256::digest(ARTIFACT).to_vec();
+        let signature: DerSignature = pki.signer.sign(ARTIFACT);
+        let body = serde_jcs::to_vec(&json!({
+            "apiVersion": "0.0.1",
+            "kind": "hashedrekord",
+            "spec": {
+                "data": { "hash": { "algorithm": "sha256", "value": hex::encode(&digest) } },
+                "signature": { "content": BASE64.encode(signature.as_bytes()) }
+            }
+        }))
+        .unwrap();
+
+        let bundle = SigstoreBundle::new(
+            VerificationMaterial {
+                content: VerificationMaterialContent::Certificate(X509Certificate {
+                    raw_bytes: leaf.to_der().unwrap(),
+                }),
+                tlog_entries: vec![log.entry("hashedrekord", body)],
+                timestamp_verification_data: None,
+            },
+            BundleContent::MessageSignature(MessageSignature {
+                message_digest: Some(HashOutput {
+                    algorithm: "SHA2_256".to_owned(),
+                    digest,
+                }),
+                signature: signature.as_bytes().to_vec(),
+            }),
+        );
+
+        let verifier = BundleVerifier::with_trusted_root(trusted_root(&pki, &log));
+        assert!(verifier.verify_artifact(&bundle, ARTIFACT).is_ok());
+        assert!(verifier.verify_artifact(&bundle, b"other").is_err());
+    }
+
+    #[test]
+    fn verifies_certificate_bundle_against_rfc3161_timestamp() {
+        let pki = TestPki::new();
+        let tsa = TimestampAuthority::new();
+        let leaf = pki.leaf(1_700_000_000, ID_KP_CODE_SIGNING);
+
+        let mut bundle = SigstoreBundle::new(
+            VerificationMaterial {
+                content: VerificationMaterialContent::Certificate(X509Certificate {
+                    raw_bytes: leaf.to_der().unwrap(),
+                }),
+                tlog_entries: vec![],
+                timestamp_verification_data: None,
+            },
+            BundleContent::DsseEnvelope(dsse_envelope(&pki.signer)),
+        );
+        let token = tsa.issue(bundle.timestamped_signature().unwrap());
+        bundle.add_rfc3161_timestamp(&token);
+
+        let root = TrustedRoot {
+            timestamp_authorities: tsa.trusted_root().timestamp_authorities,
+            ..trusted_root(&pki, &TestLog { key: key(3) })
+        };
+        let verifier = BundleVerifier::with_trusted_root(root).require_tlog_entries(false);
+
+        let bundle = SigstoreBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        let verification = verifier.verify_artifact(&bundle, ARTIFACT).unwrap();
+        assert!(verification.certificate_verified);
+        assert!(verification.integrated_times.is_empty());
+        assert_eq!(verification.timestamps, vec![TIMESTAMP_TIME]);
+
+        // a timestamp from an untrusted authority fails verification
+        let untrusted = TimestampAuthority::with_seed(9);
+        let mut tampered = bundle.clone();
+        tampered.add_rfc3161_timestamp(&untrusted.issue(bundle.timestamped_signature().unwrap()));
+        assert!(verifier.verify(&tampered).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+#!/usr/bin/env python3
+#
+# Generate the model signing regression fixtures under fixtures/model-signing/.
+#
+# This writes statements and bundles in the OpenSSF model signing v1.0 payload
+# layout without using the upstream `model_signing` library
+# (sigstore/model-transparency), so the fixtures pin down this repository's
+# reading of that layout; they don't show compatibility with the upstream
+# tooling:
+#
+#   - `files` serialization: one sha256 digest per file
+#   - `shards` serialization: one sha256 digest per `shard_size` byte range,
+#     named `<path>:<start>:<end>`
+#   - resources sorted by path (then shard start), subject digest is the
+#     sha256 of the concatenated resource digests in that order
+#   - bundles signed with an EC key, `publicKey.hint` is the sha256 of the
+#     SubjectPublicKeyInfo PEM
+#
+# The signing key is derived from a fixed scalar so the public key stays the
+# same across runs; ECDSA signatures are randomized, so regenerating changes
+# the bundles but not the statements.
+#
+# Requires the `cryptography` package. Run from the repository root:
+#
+#   python3 scripts/generate-model-signing-fixtures.py
+
+import base64
+import hashlib
+import json
+import os
+import pathlib
+
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec
+
+FIXTURES = pathlib.Path("fixtures/model-signing")
+MODEL = FIXTURES / "model"
+SHARD_SIZE = 1000
+IGNORE_PATHS = ["notes"]
+PAYLOAD_TYPE = "application/vnd.in-toto+json"
+
+
+def model_files():
+    files = []
+    for root, _, names in os.walk(MODEL):
+        for name in names:
+            path = (pathlib.Path(root) / name).relative_to(MODEL)
+            if any(path.parts[0] == p for p in IGNORE_PATHS):
+                continue
+            files.append(pathlib.PurePosixPath(path.as_posix()))
+    return sorted(files)
+
+
+def resources(method):
+    out = []
+    for path in model_files():
+        data = (MODEL / path).read_bytes()
+        if method == "files":
+            out.append((str(path), hashlib.sha256(data).digest()))
+            continue
+        start = 0
+        while True:
+            end = min(start + SHARD_SIZE, len(data))
+            name = f"{path}:{start}:{end}"
+            out.append((name, hashlib.sha256(data[start:end]).digest()))
+            start = end
+            if start >= len(data):
+                break
+    return out
+
+
+def statement(method):
+    items = resources(method)
+    serialization_args = {
+        "method": method,
+        "hash_type": "sha256",
+        "allow_symlinks": False,
+        "ignore_paths": IGNORE_PATHS,
+    }
+    if method == "shards":
+        serialization_args["shard_size"] = SHARD_SIZE
+
+    root = hashlib.sha256(b"".join(d for _, d in items)).hexdigest()
+    return {
+        "_type": "https://in-toto.io/Statement/v1",
+        "subject": [{"name": "model", "digest": {"sha256": root}}],
+        "predicateType": "https://model_signing/signature/v1.0",
+        "predicate": {
+            "serialization": serialization_args,
+            "resources": [
+                {"name": name, "algorithm": "sha256", "digest": d.hex()}
+                for name, d in items
+            ],
+        },
+    }
+
+
+def pae(payload_type, payload):
+    t = payload_type.encode()
+    return b"DSSEv1 %d %s %d %s" % (len(t), t, len(payload), payload)
+
+
+def main():
+    key = ec.derive_private_key(0x1D5E_5EED, ec.SECP256R1())
+    pem = key.public_key().public_bytes(
+        serialization.Encoding.PEM, serialization.PublicFormat.SubjectPublicKeyInfo
+    )
+    (FIXTURES / "signing-key.pub.pem").write_bytes(pem)
+
+    for method in ["files", "shards"]:
+        payload = json.dumps(statement(method)).encode()
+        sig = key.sign(pae(PAYLOAD_TYPE, payload), ec.ECDSA(hashes.SHA256()))
+        bundle = {
+            "mediaType": "application/vnd.dev.sigstore.bundle.v0.3+json",
+            "verificationMaterial": {
+                "publicKey": {"hint": hashlib.sha256(pem).hexdigest()}
+            },
+            "dsseEnvelope": {
+                "payload": base64.b64encode(payload).decode(),
+                "payloadType": PAYLOAD_TYPE,
+                "signatures": [{"sig": base64.b64encode(sig).decode()}],
+            },
+        }
+        path = FIXTURES / f"model.sha256-{method}.sigstore.json"
+        path.write_text(json.dumps(bundle, indent=2) + "\n")
+
+
+if __name__ == "__main__":
+    main()